@@ -1,5 +1,10 @@
 pub mod project;
 pub mod spec;
+pub mod spec_source;
+pub mod requirement;
+pub mod tag;
+pub mod search;
+pub mod rules;
 pub mod test_gen;
 pub mod test_exec;
 pub mod report;
@@ -2,6 +2,7 @@ use tauri::State;
 use crate::db::Database;
 use crate::db::queries;
 use crate::models::project::{CreateProjectRequest, ProjectWithStats, Project};
+use crate::services::spec_watcher::SpecWatchers;
 use crate::errors::AppError;
 
 #[tauri::command]
@@ -46,12 +47,17 @@ pub fn get_project(state: State<'_, Database>, id: String) -> Result<ProjectWith
 }
 
 #[tauri::command]
-pub fn delete_project(state: State<'_, Database>, id: String) -> Result<(), AppError> {
+pub fn delete_project(
+    state: State<'_, Database>,
+    watchers: State<'_, SpecWatchers>,
+    id: String,
+) -> Result<(), AppError> {
     if id.trim().is_empty() {
         return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
     }
     let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
-    queries::delete_project(&conn, &id)
+    queries::delete_project(&conn, &id)?;
+    watchers.unwatch_project(&id)
 }
 
 #[tauri::command]
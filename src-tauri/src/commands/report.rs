@@ -2,20 +2,23 @@ use tauri::State;
 use crate::db::Database;
 use crate::db::queries;
 use crate::services::alignment;
-use crate::models::report::{AlignmentReport, AlignmentReportWithMismatches};
+use crate::models::report::{AlignmentReport, AlignmentReportWithMismatches, Mismatch};
+use crate::models::spec::SourceSpan;
 use crate::errors::AppError;
 
 #[tauri::command]
 pub fn generate_alignment_report(
     state: State<'_, Database>,
     project_id: String,
+    tag: Option<String>,
 ) -> Result<AlignmentReportWithMismatches, AppError> {
     if project_id.trim().is_empty() {
         return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
     }
+    let tag = tag.filter(|t| !t.trim().is_empty());
     let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
     queries::get_project(&conn, &project_id)?;
-    alignment::generate_report(&conn, &project_id)
+    alignment::generate_report(&conn, &project_id, tag.as_deref())
 }
 
 #[tauri::command]
@@ -62,11 +65,20 @@ pub fn export_report(
             serde_json::to_string_pretty(&report).map_err(AppError::Serde)
         }
         "csv" => {
-            let mut csv = String::from("requirement_id,spec_section,mismatch_type,code_element,details\n");
+            let mut csv = String::from(
+                "requirement_id,external_id,spec_file,start_line,end_line,start_offset,end_offset,spec_section,mismatch_type,code_element,details\n",
+            );
             for m in &report.mismatches {
+                let span_field = |f: fn(&SourceSpan) -> i64| m.span.as_ref().map(|s| f(s).to_string()).unwrap_or_default();
                 csv.push_str(&format!(
-                    "{},{},{},{},{}\n",
+                    "{},{},{},{},{},{},{},{},{},{},{}\n",
                     escape_csv(&m.requirement_id),
+                    escape_csv(m.external_id.as_deref().unwrap_or("")),
+                    escape_csv(m.spec_filename.as_deref().unwrap_or("")),
+                    span_field(|s| s.start_line),
+                    span_field(|s| s.end_line),
+                    span_field(|s| s.start_offset),
+                    span_field(|s| s.end_offset),
                     escape_csv(&m.spec_section),
                     escape_csv(&m.mismatch_type),
                     escape_csv(m.code_element.as_deref().unwrap_or("")),
@@ -96,23 +108,50 @@ th { background: #252538; }
                 report.report.covered_requirements,
                 report.report.total_requirements,
             ));
+            if let Some(tag) = &report.report.tag {
+                html.push_str(&format!("<p>Requirements tagged <strong>#{}</strong> only.</p>", html_escape(tag)));
+            }
+            if report.report.excluded_requirements > 0 {
+                html.push_str(&format!(
+                    "<p>{} waived or deprecated requirements not counted.</p>",
+                    report.report.excluded_requirements
+                ));
+            }
 
             if report.mismatches.is_empty() {
                 html.push_str("<p>No mismatches found.</p>");
             } else {
-                html.push_str("<table><thead><tr><th>Section</th><th>Type</th><th>Details</th></tr></thead><tbody>");
+                html.push_str("<table><thead><tr><th>ID</th><th>Location</th><th>Section</th><th>Type</th><th>Component</th><th>Details</th></tr></thead><tbody>");
                 for m in &report.mismatches {
                     html.push_str(&format!(
-                        "<tr><td>{}</td><td><span class=\"badge {}\">{}</span></td><td>{}</td></tr>",
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td><span class=\"badge {}\">{}</span></td><td>{}</td><td>{}</td></tr>",
+                        html_escape(m.external_id.as_deref().unwrap_or("")),
+                        html_escape(&source_location(m)),
                         html_escape(&m.spec_section),
                         html_escape(&m.mismatch_type),
                         html_escape(&m.mismatch_type.replace('_', " ")),
+                        html_escape(m.code_element.as_deref().unwrap_or("")),
                         html_escape(&m.details),
                     ));
                 }
                 html.push_str("</tbody></table>");
             }
 
+            if !report.excluded.is_empty() {
+                html.push_str("<h2>Excluded Requirements</h2><table><thead><tr><th>ID</th><th>Section</th><th>Status</th><th>Requirement</th><th>Reason</th></tr></thead><tbody>");
+                for e in &report.excluded {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        html_escape(e.external_id.as_deref().unwrap_or("")),
+                        html_escape(&e.spec_section),
+                        html_escape(&e.status),
+                        html_escape(&e.description),
+                        html_escape(e.reason.as_deref().unwrap_or("")),
+                    ));
+                }
+                html.push_str("</tbody></table>");
+            }
+
             html.push_str("</body></html>");
             Ok(html)
         }
@@ -120,6 +159,16 @@ th { background: #252538; }
     }
 }
 
+/// `spec.md:12` or `spec.md:12-14`; empty when the requirement has no recorded position.
+fn source_location(m: &Mismatch) -> String {
+    let file = m.spec_filename.as_deref().unwrap_or("");
+    match &m.span {
+        Some(span) if span.start_line == span.end_line => format!("{}:{}", file, span.start_line),
+        Some(span) => format!("{}:{}-{}", file, span.start_line, span.end_line),
+        None => file.to_string(),
+    }
+}
+
 fn escape_csv(s: &str) -> String {
     if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
         format!("\"{}\"", s.replace('"', "\"\""))
@@ -0,0 +1,106 @@
+use tauri::State;
+use crate::db::Database;
+use crate::db::queries;
+use crate::models::spec::{
+    CreateRequirementRequest, Requirement, RequirementEdit, RequirementStatusChange, UpdateRequirementRequest,
+};
+use crate::services::{requirement_edit, requirement_status};
+use crate::errors::AppError;
+
+/// Moves a requirement through its review workflow. `changed_by` defaults
+/// to the OS account running the app; waiving requires a `reason`.
+#[tauri::command]
+pub fn set_requirement_status(
+    state: State<'_, Database>,
+    requirement_id: String,
+    status: String,
+    changed_by: Option<String>,
+    reason: Option<String>,
+) -> Result<Requirement, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let changed_by = changed_by
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(requirement_status::current_user);
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    let requirement = requirement_status::set_status(&tx, &requirement_id, &status, &changed_by, reason.as_deref())?;
+    tx.commit().map_err(AppError::Database)?;
+    Ok(requirement)
+}
+
+#[tauri::command]
+pub fn get_requirement_status_history(
+    state: State<'_, Database>,
+    requirement_id: String,
+) -> Result<Vec<RequirementStatusChange>, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_requirement(&conn, &requirement_id)?;
+    queries::get_requirement_status_changes(&conn, &requirement_id)
+}
+
+/// Adds a requirement by hand. It is marked as manually edited, so
+/// re-parsing the spec leaves it in place.
+#[tauri::command]
+pub fn create_requirement(
+    state: State<'_, Database>,
+    request: CreateRequirementRequest,
+) -> Result<Requirement, AppError> {
+    if request.spec_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    let requirement = requirement_edit::create_requirement(&tx, &request, &requirement_status::current_user())?;
+    tx.commit().map_err(AppError::Database)?;
+    Ok(requirement)
+}
+
+/// Edits a requirement's description, section, type or priority. Fields
+/// left out of the request are kept.
+#[tauri::command]
+pub fn update_requirement(
+    state: State<'_, Database>,
+    requirement_id: String,
+    request: UpdateRequirementRequest,
+) -> Result<Requirement, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    let requirement =
+        requirement_edit::update_requirement(&tx, &requirement_id, &request, &requirement_status::current_user())?;
+    tx.commit().map_err(AppError::Database)?;
+    Ok(requirement)
+}
+
+#[tauri::command]
+pub fn delete_requirement(state: State<'_, Database>, requirement_id: String) -> Result<(), AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    requirement_edit::delete_requirement(&tx, &requirement_id, &requirement_status::current_user())?;
+    tx.commit().map_err(AppError::Database)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_requirement_edit_history(
+    state: State<'_, Database>,
+    requirement_id: String,
+) -> Result<Vec<RequirementEdit>, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_requirement(&conn, &requirement_id)?;
+    queries::get_requirement_edits(&conn, &requirement_id)
+}
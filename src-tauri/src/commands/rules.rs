@@ -0,0 +1,65 @@
+use tauri::State;
+use crate::db::Database;
+use crate::db::queries;
+use crate::models::rules::DetectionRules;
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::spec_format;
+use crate::errors::AppError;
+
+#[tauri::command]
+pub fn get_detection_rules(state: State<'_, Database>, project_id: String) -> Result<DetectionRules, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    Ok(queries::get_detection_rules(&conn, &project_id)?.unwrap_or_default())
+}
+
+/// Stores a project's rules. They apply from the next upload or re-parse.
+#[tauri::command]
+pub fn save_detection_rules(
+    state: State<'_, Database>,
+    project_id: String,
+    rules: DetectionRules,
+) -> Result<DetectionRules, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    ParserRules::compile(&rules)?;
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    queries::save_detection_rules(&conn, &project_id, &rules)?;
+    Ok(rules)
+}
+
+#[tauri::command]
+pub fn reset_detection_rules(state: State<'_, Database>, project_id: String) -> Result<DetectionRules, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    queries::delete_detection_rules(&conn, &project_id)?;
+    Ok(DetectionRules::default())
+}
+
+/// Parses a stored spec under a draft rule set without saving anything, so
+/// rule changes can be tried before they are applied.
+#[tauri::command]
+pub fn preview_spec_parse(
+    state: State<'_, Database>,
+    spec_id: String,
+    rules: DetectionRules,
+) -> Result<Vec<Requirement>, AppError> {
+    if spec_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec ID cannot be empty".into()));
+    }
+    let rules = ParserRules::compile(&rules)?;
+    let spec = {
+        let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+        queries::get_spec(&conn, &spec_id)?
+    };
+    spec_format::parse_spec_file(&spec.id, &spec.filename, &spec.content, &rules)
+}
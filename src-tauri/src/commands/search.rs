@@ -0,0 +1,24 @@
+use tauri::State;
+use crate::db::Database;
+use crate::db::queries;
+use crate::models::search::SearchResults;
+use crate::services::search;
+use crate::errors::AppError;
+
+/// Full-text search across a project's specs, requirements and generated
+/// tests, with up to `limit` hits of each kind.
+#[tauri::command]
+pub fn search(
+    state: State<'_, Database>,
+    project_id: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<SearchResults, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let limit = limit.unwrap_or(search::DEFAULT_LIMIT).clamp(1, 100);
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    search::search(&conn, &project_id, &query, limit)
+}
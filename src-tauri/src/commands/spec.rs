@@ -1,8 +1,9 @@
 use tauri::State;
 use crate::db::Database;
 use crate::db::queries;
-use crate::models::spec::{Spec, Requirement, ParsedSpec};
-use crate::services::spec_parser;
+use crate::models::lint::{LintFinding, RequirementOverlaps};
+use crate::models::spec::{Spec, SpecFilter, SpecRevision, SpecDiff, Requirement, ParsedSpec};
+use crate::services::{spec_format, spec_sync, requirement_conflicts, requirement_diff, detection_rules, spec_parser};
 use crate::errors::AppError;
 
 #[tauri::command]
@@ -33,24 +34,11 @@ pub fn upload_spec(
     queries::get_project(&conn, &project_id)?;
 
     let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
-
-    let spec = queries::create_spec(&tx, &project_id, &safe_filename, &content)?;
-    let requirements = spec_parser::parse_spec(&spec.id, &content);
-
-    if !requirements.is_empty() {
-        queries::insert_requirements(&tx, &requirements)?;
-    }
-
-    queries::update_spec_parsed_at(&tx, &spec.id)?;
-    queries::touch_project_updated_at(&tx, &project_id)?;
-    let updated_spec = queries::get_spec(&tx, &spec.id)?;
-
+    // Re-uploading a known file adds a revision and keeps requirement IDs
+    let parsed = spec_sync::save_spec(&tx, &project_id, &safe_filename, &content)?;
     tx.commit().map_err(AppError::Database)?;
 
-    Ok(ParsedSpec {
-        spec: updated_spec,
-        requirements,
-    })
+    Ok(parsed)
 }
 
 #[tauri::command]
@@ -65,12 +53,28 @@ pub fn get_spec(state: State<'_, Database>, id: String) -> Result<ParsedSpec, Ap
 }
 
 #[tauri::command]
-pub fn list_specs(state: State<'_, Database>, project_id: String) -> Result<Vec<Spec>, AppError> {
+pub fn list_specs(
+    state: State<'_, Database>,
+    project_id: String,
+    filter: Option<SpecFilter>,
+) -> Result<Vec<Spec>, AppError> {
     if project_id.trim().is_empty() {
         return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
     }
+    // Front matter defaults are stored normalized ("Must" -> "high")
+    let mut filter = filter.unwrap_or_default();
+    if let Some(priority) = filter.default_priority.as_deref() {
+        let normalized = spec_parser::normalize_priority(priority)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown priority: {}", priority)))?;
+        filter.default_priority = Some(normalized);
+    }
+    if let Some(req_type) = filter.default_req_type.as_deref() {
+        let normalized = spec_parser::normalize_req_type(req_type)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown requirement type: {}", req_type)))?;
+        filter.default_req_type = Some(normalized);
+    }
     let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
-    queries::list_specs(&conn, &project_id)
+    queries::list_specs(&conn, &project_id, &filter)
 }
 
 #[tauri::command]
@@ -94,20 +98,73 @@ pub fn reparse_spec(state: State<'_, Database>, id: String) -> Result<Vec<Requir
     let spec = queries::get_spec(&conn, &id)?;
 
     let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    let requirements = spec_sync::sync_requirements(&tx, &spec)?;
+    tx.commit().map_err(AppError::Database)?;
 
-    queries::delete_requirements_for_spec(&tx, &id)?;
-
-    let requirements = spec_parser::parse_spec(&id, &spec.content);
+    Ok(requirements)
+}
 
-    if !requirements.is_empty() {
-        queries::insert_requirements(&tx, &requirements)?;
+#[tauri::command]
+pub fn list_spec_revisions(state: State<'_, Database>, spec_id: String) -> Result<Vec<SpecRevision>, AppError> {
+    if spec_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec ID cannot be empty".into()));
     }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_spec(&conn, &spec_id)?;
+    queries::list_spec_revisions(&conn, &spec_id)
+}
 
-    queries::update_spec_parsed_at(&tx, &id)?;
+/// Requirement-level diff between two revisions of a spec, both parsed with
+/// the project's current detection rules.
+#[tauri::command]
+pub fn diff_spec_revisions(
+    state: State<'_, Database>,
+    spec_id: String,
+    from_revision: i64,
+    to_revision: i64,
+) -> Result<SpecDiff, AppError> {
+    if spec_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let spec = queries::get_spec(&conn, &spec_id)?;
+    let from = queries::get_spec_revision(&conn, &spec_id, from_revision)?;
+    let to = queries::get_spec_revision(&conn, &spec_id, to_revision)?;
+    let rules = detection_rules::rules_for_project(&conn, &spec.project_id)?;
+    drop(conn);
+
+    let before = spec_format::parse_spec_file(&spec.id, &spec.filename, &from.content, &rules)?;
+    let after = spec_format::parse_spec_file(&spec.id, &spec.filename, &to.content, &rules)?;
+    Ok(SpecDiff {
+        spec_id,
+        from_revision,
+        to_revision,
+        changes: requirement_diff::diff_requirements(before, after),
+    })
+}
 
-    tx.commit().map_err(AppError::Database)?;
+/// Wording problems found in the spec's requirements at its last parse.
+#[tauri::command]
+pub fn get_lint_findings(state: State<'_, Database>, spec_id: String) -> Result<Vec<LintFinding>, AppError> {
+    if spec_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_spec(&conn, &spec_id)?;
+    queries::get_lint_findings_for_spec(&conn, &spec_id)
+}
 
-    Ok(requirements)
+/// Near-duplicate and contradicting requirements across all of a project's specs.
+#[tauri::command]
+pub fn find_requirement_overlaps(state: State<'_, Database>, project_id: String) -> Result<RequirementOverlaps, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    let requirements = queries::get_requirements_for_project(&conn, &project_id)?;
+    drop(conn);
+    Ok(requirement_conflicts::find_overlaps(&requirements))
 }
 
 #[tauri::command]
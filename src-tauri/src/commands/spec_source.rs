@@ -0,0 +1,65 @@
+use tauri::{AppHandle, State};
+use crate::db::Database;
+use crate::db::queries;
+use crate::models::spec::{SpecImport, SpecSource};
+use crate::services::spec_sources;
+use crate::services::spec_watcher::SpecWatchers;
+use crate::errors::AppError;
+
+#[tauri::command]
+pub fn list_spec_sources(state: State<'_, Database>, project_id: String) -> Result<Vec<SpecSource>, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    queries::list_spec_sources(&conn, &project_id)
+}
+
+/// Registers a glob relative to the project's codebase and starts watching
+/// it. Matching files are imported by `import_spec_sources`.
+#[tauri::command]
+pub fn add_spec_source(
+    app_handle: AppHandle,
+    state: State<'_, Database>,
+    watchers: State<'_, SpecWatchers>,
+    project_id: String,
+    pattern: String,
+) -> Result<SpecSource, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let pattern = spec_sources::normalize_pattern(&pattern)?;
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    let source = queries::add_spec_source(&conn, &project_id, &pattern)?;
+    watchers.watch_project(&app_handle, &conn, &project_id)?;
+    Ok(source)
+}
+
+/// Stops watching a glob. Specs already imported from it are kept.
+#[tauri::command]
+pub fn remove_spec_source(
+    app_handle: AppHandle,
+    state: State<'_, Database>,
+    watchers: State<'_, SpecWatchers>,
+    id: String,
+) -> Result<(), AppError> {
+    if id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Spec source ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let source = queries::delete_spec_source(&conn, &id)?;
+    watchers.watch_project(&app_handle, &conn, &source.project_id)
+}
+
+/// Imports every file matching the project's spec sources, adding revisions
+/// for files whose content changed since the last import.
+#[tauri::command]
+pub fn import_spec_sources(state: State<'_, Database>, project_id: String) -> Result<SpecImport, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    spec_sources::import_sources(&conn, &project_id)
+}
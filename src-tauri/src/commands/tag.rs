@@ -0,0 +1,86 @@
+use tauri::State;
+use crate::db::Database;
+use crate::db::queries;
+use crate::models::spec::Requirement;
+use crate::models::tag::Tag;
+use crate::services::tags;
+use crate::errors::AppError;
+
+#[tauri::command]
+pub fn list_tags(state: State<'_, Database>, project_id: String) -> Result<Vec<Tag>, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    queries::list_tags(&conn, &project_id)
+}
+
+/// Creates a tag or replaces the codebase paths it maps to. Paths are globs
+/// relative to the project's codebase; a plain directory covers everything
+/// below it.
+#[tauri::command]
+pub fn save_tag(
+    state: State<'_, Database>,
+    project_id: String,
+    name: String,
+    paths: Vec<String>,
+) -> Result<Tag, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_project(&conn, &project_id)?;
+    tags::save_tag(&conn, &project_id, &name, &paths)
+}
+
+/// Deletes a tag and unlinks it from every requirement. Inline `#tag`
+/// tokens bring it back on the next parse.
+#[tauri::command]
+pub fn delete_tag(state: State<'_, Database>, project_id: String, id: String) -> Result<(), AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    if id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Tag ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::delete_tag(&conn, &project_id, &id)
+}
+
+#[tauri::command]
+pub fn add_requirement_tag(
+    state: State<'_, Database>,
+    requirement_id: String,
+    tag: String,
+) -> Result<Requirement, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let name = tags::normalize_tag(&tag).ok_or_else(|| AppError::InvalidInput(format!("Invalid tag: {}", tag)))?;
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let requirement = queries::get_requirement(&conn, &requirement_id)?;
+    let spec = queries::get_spec(&conn, &requirement.spec_id)?;
+    let tag = queries::get_or_create_tag(&conn, &spec.project_id, &name)?;
+    queries::add_requirement_tag(&conn, &requirement_id, &tag.id, "manual")?;
+    queries::get_requirement(&conn, &requirement_id)
+}
+
+/// Unlinks a tag from a requirement. A tag written inline in the spec comes
+/// back when it is re-parsed.
+#[tauri::command]
+pub fn remove_requirement_tag(
+    state: State<'_, Database>,
+    requirement_id: String,
+    tag: String,
+) -> Result<Requirement, AppError> {
+    if requirement_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Requirement ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    let requirement = queries::get_requirement(&conn, &requirement_id)?;
+    let spec = queries::get_spec(&conn, &requirement.spec_id)?;
+    let tag = tags::find_tag(&conn, &spec.project_id, &tag)?;
+    queries::remove_requirement_tag(&conn, &requirement_id, &tag.id)?;
+    queries::get_requirement(&conn, &requirement_id)
+}
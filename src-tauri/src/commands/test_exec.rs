@@ -1,3 +1,4 @@
+use std::sync::atomic::AtomicBool;
 use tauri::{State, AppHandle, Emitter};
 use uuid::Uuid;
 use chrono::Utc;
@@ -45,32 +46,7 @@ pub async fn execute_tests(
             status: "running".to_string(),
         });
 
-        // Write test to temp file if no file_path
-        let mut is_temp = false;
-        let test_file_path = if let Some(ref path) = test.file_path {
-            path.clone()
-        } else {
-            is_temp = true;
-            let ext = match test.framework.as_str() {
-                "pytest" => "py",
-                _ => "test.js",
-            };
-            let temp_dir = std::env::temp_dir().join("spec-companion-tests");
-            std::fs::create_dir_all(&temp_dir)?;
-            let temp_path = temp_dir.join(format!("{}.{}", test.id, ext));
-            std::fs::write(&temp_path, &test.code)?;
-            temp_path.to_string_lossy().to_string()
-        };
-
-        let exec_result = match test.framework.as_str() {
-            "pytest" => test_runner::run_pytest_test(&test_file_path, &codebase_path)?,
-            _ => test_runner::run_jest_test(&test_file_path, &codebase_path)?,
-        };
-
-        // Clean up temp file after execution
-        if is_temp {
-            let _ = std::fs::remove_file(&test_file_path);
-        }
+        let exec_result = test_runner::run_test(test, &codebase_path, &AtomicBool::new(false))?;
 
         results.push(TestResult {
             id: Uuid::new_v4().to_string(),
@@ -1,19 +1,32 @@
-use tauri::{State, AppHandle, Manager};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tauri::{State, AppHandle, Emitter, Manager};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use uuid::Uuid;
 use chrono::Utc;
 use crate::db::Database;
 use crate::db::queries;
-use crate::models::test::{GeneratedTest, GenerateTestsRequest};
-use crate::services::{template_generator, llm_generator, codebase_scanner};
+use crate::models::spec::Requirement;
+use crate::models::test::{GeneratedTest, GenerateTestsRequest, GenerateTestsResult, GenerationError, GenerationProgress, TestResult};
+use crate::services::codebase_scanner::CodeSymbol;
+use crate::services::{template_generator, llm_generator, llm_context, codebase_scanner, requirement_status, tags, test_repair, test_runner};
+use crate::services::llm_context::{CodeContext, SourceFiles};
+use crate::services::llm_generator::FailedAttempt;
+use crate::services::test_runner::ExecutionResult;
+use crate::services::generation_jobs::GenerationJobs;
+use crate::services::llm_provider::{LlmSettings, Provider};
 use crate::errors::AppError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct AppSettings {
     pub api_key: String,
     pub default_framework: String,
     pub default_mode: String,
     pub scan_exclusions: Vec<String>,
+    pub llm: LlmSettings,
 }
 
 impl Default for AppSettings {
@@ -23,6 +36,7 @@ impl Default for AppSettings {
             default_framework: "jest".to_string(),
             default_mode: "template".to_string(),
             scan_exclusions: Vec::new(),
+            llm: LlmSettings::default(),
         }
     }
 }
@@ -30,13 +44,15 @@ impl Default for AppSettings {
 #[tauri::command]
 pub async fn generate_tests(
     state: State<'_, Database>,
+    jobs: State<'_, GenerationJobs>,
     app_handle: AppHandle,
     request: GenerateTestsRequest,
-) -> Result<Vec<GeneratedTest>, AppError> {
+) -> Result<GenerateTestsResult, AppError> {
     if request.project_id.trim().is_empty() {
         return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
     }
-    if request.requirement_ids.is_empty() {
+    let tag = request.tag.as_deref().filter(|t| !t.trim().is_empty());
+    if request.requirement_ids.is_empty() && tag.is_none() {
         return Err(AppError::InvalidInput("No requirements selected".into()));
     }
     if !matches!(request.framework.as_str(), "jest" | "pytest") {
@@ -45,14 +61,18 @@ pub async fn generate_tests(
     if !matches!(request.mode.as_str(), "template" | "llm") {
         return Err(AppError::InvalidInput(format!("Unsupported mode: {}", request.mode)));
     }
+    if request.self_heal && request.mode != "llm" {
+        return Err(AppError::InvalidInput("Self-heal is only available in LLM mode".into()));
+    }
 
     let settings = load_settings_internal(&app_handle)?;
-    if request.mode == "llm" && settings.api_key.is_empty() {
-        return Err(AppError::InvalidInput("API key is required for LLM mode. Set it in Settings.".into()));
-    }
+    let provider = match request.mode.as_str() {
+        "llm" => Some(Provider::from_settings(&settings.api_key, &settings.llm)?),
+        _ => None,
+    };
 
     // Fetch project + requirements under a single lock
-    let (codebase_path, requirements) = {
+    let (codebase_path, requirements, project_tags) = {
         let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
         let project = queries::get_project(&conn, &request.project_id)?;
         let codebase_path = project.project.codebase_path.clone();
@@ -61,52 +81,331 @@ pub async fn generate_tests(
         for req_id in &request.requirement_ids {
             requirements.push(queries::get_requirement(&conn, req_id)?);
         }
-        (codebase_path, requirements)
+        if let Some(tag) = tag {
+            let tag = tags::find_tag(&conn, &request.project_id, tag)?;
+            let tagged = tags::with_tag(queries::get_requirements_for_project(&conn, &request.project_id)?, &tag.name);
+            requirements = if request.requirement_ids.is_empty() {
+                tagged
+            } else {
+                requirements.into_iter().filter(|r| tagged.iter().any(|t| t.id == r.id)).collect()
+            };
+            if requirements.is_empty() {
+                return Err(AppError::InvalidInput(format!("No requirements tagged {}", tag.name)));
+            }
+        }
+        // Waived and deprecated requirements need no tests, however selected
+        requirements.retain(|r| !requirement_status::is_excluded(&r.status));
+        if requirements.is_empty() {
+            return Err(AppError::InvalidInput("The selected requirements are waived or deprecated".into()));
+        }
+        (codebase_path, requirements, queries::list_tags(&conn, &request.project_id)?)
     }; // lock released
 
+    let job = jobs.start(&request.project_id)?;
     let symbols = codebase_scanner::scan_codebase(&codebase_path, &settings.scan_exclusions)
         .unwrap_or_default();
+    // Shown to the model as examples of how this project writes tests
+    let test_files = match provider {
+        Some(_) => codebase_scanner::find_test_files(&codebase_path, &settings.scan_exclusions, &request.framework)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
 
-    let mut generated_tests = Vec::new();
-
-    for req in &requirements {
-        let code = match request.mode.as_str() {
-            "llm" => {
-                llm_generator::generate_test_with_llm(
-                    &settings.api_key,
-                    req,
-                    &request.framework,
-                    &symbols,
-                ).await?
-            }
-            _ => match request.framework.as_str() {
-                "pytest" => template_generator::generate_pytest_test(req, &symbols),
-                _ => template_generator::generate_jest_test(req, &symbols),
+    // Template generation is instant; only LLM requests run side by side
+    let concurrency = if provider.is_some() { settings.llm.concurrency } else { 1 };
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let batch = Arc::new(GenerationBatch {
+        app_handle: app_handle.clone(),
+        provider,
+        project_id: request.project_id.clone(),
+        source_files: SourceFiles::new(&codebase_path),
+        codebase_path,
+        test_files,
+        framework: request.framework.clone(),
+        mode: request.mode.clone(),
+        max_retries: settings.llm.max_retries,
+        context_tokens: settings.llm.context_tokens,
+        repair_attempts: if request.self_heal { settings.llm.repair_attempts } else { 0 },
+        total: requirements.len(),
+        completed: AtomicUsize::new(0),
+    });
+
+    let mut tasks = JoinSet::new();
+    for (index, req) in requirements.into_iter().enumerate() {
+        // Requirements mapped to components only see the code under them
+        let symbols = tags::component_symbols(&symbols, &tags::component_paths(&req, &project_tags))?;
+        let (batch, semaphore) = (batch.clone(), semaphore.clone());
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = batch.generate(&req, &symbols).await;
+            (index, req.id, result)
+        });
+    }
+
+    let mut finished = Vec::new();
+    loop {
+        tokio::select! {
+            next = tasks.join_next() => match next {
+                Some(joined) => finished.push(joined.map_err(|e| AppError::General(e.to_string()))?),
+                None => break,
             },
+            _ = job.cancelled() => {
+                // Abandons requests in flight; tests already saved are kept
+                tasks.abort_all();
+                while let Some(joined) = tasks.join_next().await {
+                    if let Ok(done) = joined {
+                        finished.push(done);
+                    }
+                }
+                break;
+            }
+        }
+    }
+    finished.sort_by_key(|(index, _, _)| *index);
+
+    let mut result = GenerateTestsResult { cancelled: job.is_cancelled(), ..Default::default() };
+    for (_, requirement_id, generated) in finished {
+        match generated {
+            Ok((test, repair_error)) => {
+                if let Some(error) = repair_error {
+                    result.repair_errors.push(GenerationError { requirement_id, error });
+                }
+                result.tests.push(test);
+            }
+            Err(e) => result.errors.push(GenerationError { requirement_id, error: e.to_string() }),
+        }
+    }
+    let status = if result.cancelled { "cancelled" } else { "completed" };
+    batch.progress("", status, "", None, None);
+
+    Ok(result)
+}
+
+/// Settings shared by the requirements of one `generate_tests` call.
+struct GenerationBatch {
+    app_handle: AppHandle,
+    provider: Option<Provider>,
+    project_id: String,
+    codebase_path: String,
+    /// Read once for all requirements
+    source_files: SourceFiles,
+    test_files: Vec<String>,
+    framework: String,
+    mode: String,
+    max_retries: u32,
+    context_tokens: usize,
+    /// Zero unless self-healing
+    repair_attempts: u32,
+    total: usize,
+    completed: AtomicUsize,
+}
+
+impl GenerationBatch {
+    /// Generates and saves one requirement's test, reporting progress as
+    /// the code arrives. When self-healing, the test is then run and
+    /// repaired until it runs; a run or repair that errors is returned
+    /// alongside the test rather than failing it.
+    async fn generate(
+        self: &Arc<Self>,
+        req: &Requirement,
+        symbols: &[CodeSymbol],
+    ) -> Result<(GeneratedTest, Option<String>), AppError> {
+        self.progress(&req.id, "generating", "", None, None);
+        let saved = match &self.provider {
+            Some(provider) => {
+                let generated = async {
+                    let context = self.context(req, symbols).await?;
+                    let code = llm_generator::generate_test_with_llm(
+                        provider,
+                        req,
+                        &self.framework,
+                        &context,
+                        self.max_retries,
+                        |partial| {
+                            self.progress(&req.id, "generating", partial, None, None);
+                            Ok(())
+                        },
+                    ).await?;
+                    // Saved as soon as it is generated, so failures elsewhere
+                    // in the batch or cancelling it keep what was already done
+                    Ok::<_, AppError>((context, self.save(req, code, None)?))
+                };
+                match generated.await {
+                    Ok((context, test)) if self.repair_attempts > 0 => self.heal(provider, req, &context, test).await,
+                    generated => generated.map(|(_, test)| (test, None)),
+                }
+            }
+            None => {
+                let code = match self.framework.as_str() {
+                    "pytest" => template_generator::generate_pytest_test(req, symbols),
+                    _ => template_generator::generate_jest_test(req, symbols),
+                };
+                self.save(req, code, None).map(|test| (test, None))
+            }
         };
 
-        generated_tests.push(GeneratedTest {
+        self.completed.fetch_add(1, Ordering::SeqCst);
+        match &saved {
+            Ok((test, repair_error)) => {
+                self.progress(&req.id, "generated", &test.code, Some(test.clone()), repair_error.clone())
+            }
+            Err(e) => self.progress(&req.id, "failed", "", None, Some(e.to_string())),
+        }
+        saved
+    }
+
+    /// Picks the code to send with the requirement on a blocking thread,
+    /// since it reads source files.
+    async fn context(self: &Arc<Self>, req: &Requirement, symbols: &[CodeSymbol]) -> Result<CodeContext, AppError> {
+        let (batch, req, symbols) = (self.clone(), req.clone(), symbols.to_vec());
+        tokio::task::spawn_blocking(move || {
+            llm_context::gather(&batch.source_files, &req, &symbols, &batch.test_files, batch.context_tokens)
+        })
+        .await
+        .map_err(|e| AppError::General(e.to_string()))
+    }
+
+    /// Runs the test and, while it fails for reasons unrelated to the
+    /// requirement, has the model repair it. Every repair is saved as the
+    /// next version of the test, and the last version records how it ended.
+    /// A test that couldn't be run or repaired is kept as broken, with the
+    /// error that stopped it.
+    async fn heal(
+        &self,
+        provider: &Provider,
+        req: &Requirement,
+        context: &CodeContext,
+        mut test: GeneratedTest,
+    ) -> Result<(GeneratedTest, Option<String>), AppError> {
+        let mut repairs = 0;
+        loop {
+            self.progress(&req.id, "running", &test.code, None, None);
+            let result = match self.run(&test).await {
+                Ok(result) => result,
+                Err(e) => return Ok((self.set_outcome(test, "broken")?, Some(format!("Couldn't run the test: {}", e)))),
+            };
+            if repairs == self.repair_attempts || !test_repair::is_setup_failure(&test, &result) {
+                let outcome = test_repair::outcome(&test, &result);
+                return Ok((self.set_outcome(test, outcome)?, None));
+            }
+
+            repairs += 1;
+            self.progress(&req.id, "repairing", "", None, None);
+            let output = test_repair::failure_output(&result);
+            let failed = FailedAttempt { code: &test.code, output: &output };
+            let repaired = llm_generator::repair_test_with_llm(
+                provider,
+                req,
+                &self.framework,
+                context,
+                &failed,
+                self.max_retries,
+                |partial| {
+                    self.progress(&req.id, "repairing", partial, None, None);
+                    Ok(())
+                },
+            ).await;
+            match repaired {
+                Ok(code) => test = self.save(req, code, Some(&test))?,
+                Err(e) => return Ok((self.set_outcome(test, "broken")?, Some(format!("Couldn't repair the test: {}", e)))),
+            }
+        }
+    }
+
+    /// Records how self-healing a test ended.
+    fn set_outcome(&self, mut test: GeneratedTest, outcome: &str) -> Result<GeneratedTest, AppError> {
+        self.with_conn(|conn| queries::set_repair_outcome(conn, &test.id, outcome))?;
+        test.repair_outcome = Some(outcome.to_string());
+        Ok(test)
+    }
+
+    /// Runs a test against the codebase and keeps the result with it.
+    /// Cancelling the batch drops this future, which kills the test process.
+    async fn run(&self, test: &GeneratedTest) -> Result<ExecutionResult, AppError> {
+        let (to_run, codebase_path) = (test.clone(), self.codebase_path.clone());
+        let stop = Arc::new(AtomicBool::new(false));
+        let _stop_on_drop = StopOnDrop(stop.clone());
+        let exec_result = tokio::task::spawn_blocking(move || test_runner::run_test_in_codebase(&to_run, &codebase_path, &stop))
+            .await
+            .map_err(|e| AppError::General(e.to_string()))??;
+        let result = TestResult {
+            id: Uuid::new_v4().to_string(),
+            generated_test_id: test.id.clone(),
+            status: exec_result.status.clone(),
+            execution_time_ms: exec_result.execution_time_ms,
+            stdout: exec_result.stdout.clone(),
+            stderr: exec_result.stderr.clone(),
+            executed_at: Utc::now().to_rfc3339(),
+        };
+        self.with_conn(|conn| {
+            queries::insert_test_result(conn, &result)?;
+            let _ = queries::touch_project_updated_at(conn, &self.project_id);
+            Ok(())
+        })?;
+        Ok(exec_result)
+    }
+
+    /// Saves a new test, or a new version of `previous`.
+    fn save(&self, req: &Requirement, code: String, previous: Option<&GeneratedTest>) -> Result<GeneratedTest, AppError> {
+        let test = GeneratedTest {
             id: Uuid::new_v4().to_string(),
             requirement_id: req.id.clone(),
-            framework: request.framework.clone(),
+            framework: self.framework.clone(),
             code,
-            generation_mode: request.mode.clone(),
+            generation_mode: self.mode.clone(),
             file_path: None,
             created_at: Utc::now().to_rfc3339(),
-        });
+            version: previous.map_or(1, |p| p.version + 1),
+            previous_version_id: previous.map(|p| p.id.clone()),
+            repair_outcome: None,
+        };
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+            queries::insert_generated_test(&tx, &test)?;
+            tx.commit().map_err(AppError::Database)
+        })?;
+        Ok(test)
     }
 
-    // Batch insert all tests under a single lock + transaction
-    {
+    fn with_conn<T>(&self, f: impl FnOnce(&rusqlite::Connection) -> Result<T, AppError>) -> Result<T, AppError> {
+        let state = self.app_handle.state::<Database>();
         let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
-        let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
-        for test in &generated_tests {
-            queries::insert_generated_test(&tx, test)?;
-        }
-        tx.commit().map_err(AppError::Database)?;
+        f(&conn)
+    }
+
+    fn progress(&self, requirement_id: &str, status: &str, partial_code: &str, test: Option<GeneratedTest>, error: Option<String>) {
+        let _ = self.app_handle.emit("generation-progress", GenerationProgress {
+            total: self.total,
+            completed: self.completed.load(Ordering::SeqCst),
+            current_requirement: requirement_id.to_string(),
+            status: status.to_string(),
+            partial_code: partial_code.to_string(),
+            test,
+            error,
+        });
+    }
+}
+
+/// Tells a blocking test run to stop once the future waiting on it is gone.
+struct StopOnDrop(Arc<AtomicBool>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
     }
+}
 
-    Ok(generated_tests)
+/// Stops the project's running generation batch, abandoning any LLM requests
+/// in flight; tests already generated are kept. Returns false when nothing was running.
+#[tauri::command]
+pub fn cancel_generation(
+    jobs: State<'_, GenerationJobs>,
+    project_id: String,
+) -> Result<bool, AppError> {
+    if project_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Project ID cannot be empty".into()));
+    }
+    jobs.cancel(&project_id)
 }
 
 #[tauri::command]
@@ -121,6 +420,19 @@ pub fn get_generated_tests(
     queries::get_generated_tests_for_requirement(&conn, &requirement_id)
 }
 
+/// Every attempt of a self-healed test, first version first.
+#[tauri::command]
+pub fn get_generated_test_versions(
+    state: State<'_, Database>,
+    test_id: String,
+) -> Result<Vec<GeneratedTest>, AppError> {
+    if test_id.trim().is_empty() {
+        return Err(AppError::InvalidInput("Test ID cannot be empty".into()));
+    }
+    let conn = state.conn.lock().map_err(|e| AppError::General(e.to_string()))?;
+    queries::get_generated_test_versions(&conn, &test_id)
+}
+
 #[tauri::command]
 pub fn get_all_generated_tests(
     state: State<'_, Database>,
@@ -187,6 +499,7 @@ pub fn save_settings(app_handle: AppHandle, settings: AppSettings) -> Result<(),
     if !matches!(settings.default_mode.as_str(), "template" | "llm") {
         return Err(AppError::InvalidInput(format!("Unsupported mode: {}", settings.default_mode)));
     }
+    settings.llm.validate()?;
     let config_dir = app_handle
         .path()
         .app_data_dir()
@@ -2,9 +2,13 @@ use rusqlite::{params, Connection};
 use uuid::Uuid;
 use chrono::Utc;
 use crate::models::project::{Project, CreateProjectRequest, ProjectWithStats};
-use crate::models::spec::{Spec, Requirement};
+use crate::models::spec::{Spec, SpecFilter, SpecMetadata, SpecRevision, SpecSource, Requirement, RequirementEdit, RequirementStatusChange, SourceSpan};
+use crate::models::rules::DetectionRules;
+use crate::models::lint::LintFinding;
+use crate::models::tag::Tag;
+use crate::models::search::SearchHit;
 use crate::models::test::{GeneratedTest, TestResult};
-use crate::models::report::{AlignmentReport, Mismatch, AlignmentReportWithMismatches};
+use crate::models::report::{AlignmentReport, Mismatch, AlignmentReportWithMismatches, ExcludedRequirement};
 use crate::errors::AppError;
 
 // ─── Projects ───────────────────────────────────────────────────
@@ -29,8 +33,8 @@ pub fn list_projects(conn: &Connection) -> Result<Vec<ProjectWithStats>, AppErro
     let mut stmt = conn.prepare(
         "SELECT p.id, p.name, p.codebase_path, p.created_at, p.updated_at,
                 COALESCE((SELECT COUNT(*) FROM specs WHERE project_id = p.id), 0) as spec_count,
-                (SELECT coverage_percent FROM alignment_reports WHERE project_id = p.id ORDER BY generated_at DESC LIMIT 1) as coverage_percent,
-                (SELECT generated_at FROM alignment_reports WHERE project_id = p.id ORDER BY generated_at DESC LIMIT 1) as last_run_at
+                (SELECT coverage_percent FROM alignment_reports WHERE project_id = p.id AND tag IS NULL ORDER BY generated_at DESC LIMIT 1) as coverage_percent,
+                (SELECT generated_at FROM alignment_reports WHERE project_id = p.id AND tag IS NULL ORDER BY generated_at DESC LIMIT 1) as last_run_at
          FROM projects p ORDER BY p.updated_at DESC"
     )?;
     let rows = stmt.query_map([], |row| {
@@ -58,8 +62,8 @@ pub fn get_project(conn: &Connection, id: &str) -> Result<ProjectWithStats, AppE
     conn.query_row(
         "SELECT p.id, p.name, p.codebase_path, p.created_at, p.updated_at,
                 COALESCE((SELECT COUNT(*) FROM specs WHERE project_id = p.id), 0) as spec_count,
-                (SELECT coverage_percent FROM alignment_reports WHERE project_id = p.id ORDER BY generated_at DESC LIMIT 1) as coverage_percent,
-                (SELECT generated_at FROM alignment_reports WHERE project_id = p.id ORDER BY generated_at DESC LIMIT 1) as last_run_at
+                (SELECT coverage_percent FROM alignment_reports WHERE project_id = p.id AND tag IS NULL ORDER BY generated_at DESC LIMIT 1) as coverage_percent,
+                (SELECT generated_at FROM alignment_reports WHERE project_id = p.id AND tag IS NULL ORDER BY generated_at DESC LIMIT 1) as last_run_at
          FROM projects p WHERE p.id = ?1",
         params![id],
         |row| {
@@ -98,13 +102,42 @@ pub fn touch_project_updated_at(conn: &Connection, project_id: &str) -> Result<(
 
 // ─── Specs ──────────────────────────────────────────────────────
 
-pub fn create_spec(conn: &Connection, project_id: &str, filename: &str, content: &str) -> Result<Spec, AppError> {
+const SPEC_COLUMNS: &str = "id, project_id, filename, content, parsed_at, created_at, owner, version, status, component, default_priority, default_req_type, tags, revision";
+
+fn spec_from_row(row: &rusqlite::Row) -> rusqlite::Result<Spec> {
+    Ok(Spec {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        filename: row.get(2)?,
+        content: row.get(3)?,
+        parsed_at: row.get(4)?,
+        created_at: row.get(5)?,
+        metadata: SpecMetadata {
+            owner: row.get(6)?,
+            version: row.get(7)?,
+            status: row.get(8)?,
+            component: row.get(9)?,
+            default_priority: row.get(10)?,
+            default_req_type: row.get(11)?,
+            tags: json_column(row, 12)?.unwrap_or_default(),
+        },
+        revision: row.get(13)?,
+    })
+}
+
+pub fn create_spec(conn: &Connection, project_id: &str, filename: &str, content: &str, metadata: &SpecMetadata) -> Result<Spec, AppError> {
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
     conn.execute(
         "INSERT INTO specs (id, project_id, filename, content, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
         params![id, project_id, filename, content, now],
     )?;
+    update_spec_metadata(conn, &id, metadata)?;
+    conn.execute(
+        "INSERT INTO spec_revisions (id, spec_id, revision, content, created_at) VALUES (?1, ?2, 1, ?3, ?4)",
+        params![Uuid::new_v4().to_string(), id, content, now],
+    )?;
+    index_spec(conn, &id)?;
     Ok(Spec {
         id,
         project_id: project_id.to_string(),
@@ -112,40 +145,116 @@ pub fn create_spec(conn: &Connection, project_id: &str, filename: &str, content:
         content: content.to_string(),
         parsed_at: None,
         created_at: now,
+        metadata: metadata.clone(),
+        revision: 1,
+    })
+}
+
+/// Stores new content for a spec as its next revision and returns that revision number.
+pub fn add_spec_revision(conn: &Connection, spec_id: &str, content: &str) -> Result<i64, AppError> {
+    let now = Utc::now().to_rfc3339();
+    let revision: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(revision), 0) + 1 FROM spec_revisions WHERE spec_id = ?1",
+        params![spec_id],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT INTO spec_revisions (id, spec_id, revision, content, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![Uuid::new_v4().to_string(), spec_id, revision, content, now],
+    )?;
+    conn.execute(
+        "UPDATE specs SET content = ?1, revision = ?2 WHERE id = ?3",
+        params![content, revision, spec_id],
+    )?;
+    index_spec(conn, spec_id)?;
+    Ok(revision)
+}
+
+/// Newest first.
+pub fn list_spec_revisions(conn: &Connection, spec_id: &str) -> Result<Vec<SpecRevision>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, spec_id, revision, content, created_at FROM spec_revisions WHERE spec_id = ?1 ORDER BY revision DESC"
+    )?;
+    let rows = stmt.query_map(params![spec_id], spec_revision_from_row)?;
+    let mut revisions = Vec::new();
+    for row in rows {
+        revisions.push(row?);
+    }
+    Ok(revisions)
+}
+
+pub fn get_spec_revision(conn: &Connection, spec_id: &str, revision: i64) -> Result<SpecRevision, AppError> {
+    conn.query_row(
+        "SELECT id, spec_id, revision, content, created_at FROM spec_revisions WHERE spec_id = ?1 AND revision = ?2",
+        params![spec_id, revision],
+        spec_revision_from_row,
+    ).map_err(|_| AppError::NotFound(format!("Revision {} of spec {} not found", revision, spec_id)))
+}
+
+fn spec_revision_from_row(row: &rusqlite::Row) -> rusqlite::Result<SpecRevision> {
+    Ok(SpecRevision {
+        id: row.get(0)?,
+        spec_id: row.get(1)?,
+        revision: row.get(2)?,
+        content: row.get(3)?,
+        created_at: row.get(4)?,
     })
 }
 
+pub fn update_spec_metadata(conn: &Connection, spec_id: &str, metadata: &SpecMetadata) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE specs SET owner = ?1, version = ?2, status = ?3, component = ?4, default_priority = ?5, default_req_type = ?6, tags = ?7 WHERE id = ?8",
+        params![
+            metadata.owner,
+            metadata.version,
+            metadata.status,
+            metadata.component,
+            metadata.default_priority,
+            metadata.default_req_type,
+            serde_json::to_string(&metadata.tags)?,
+            spec_id
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn get_spec(conn: &Connection, id: &str) -> Result<Spec, AppError> {
     conn.query_row(
-        "SELECT id, project_id, filename, content, parsed_at, created_at FROM specs WHERE id = ?1",
+        &format!("SELECT {} FROM specs WHERE id = ?1", SPEC_COLUMNS),
         params![id],
-        |row| {
-            Ok(Spec {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                filename: row.get(2)?,
-                content: row.get(3)?,
-                parsed_at: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        },
+        spec_from_row,
     ).map_err(|_| AppError::NotFound(format!("Spec not found: {}", id)))
 }
 
-pub fn list_specs(conn: &Connection, project_id: &str) -> Result<Vec<Spec>, AppError> {
-    let mut stmt = conn.prepare(
-        "SELECT id, project_id, filename, content, parsed_at, created_at FROM specs WHERE project_id = ?1 ORDER BY created_at DESC"
+/// Lists a project's specs, optionally narrowed by front-matter fields.
+/// Text fields match case-insensitively.
+pub fn list_specs(conn: &Connection, project_id: &str, filter: &SpecFilter) -> Result<Vec<Spec>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM specs
+         WHERE project_id = ?1
+           AND (?2 IS NULL OR lower(owner) = lower(?2))
+           AND (?3 IS NULL OR lower(version) = lower(?3))
+           AND (?4 IS NULL OR lower(status) = lower(?4))
+           AND (?5 IS NULL OR lower(component) = lower(?5))
+           AND (?6 IS NULL OR default_priority = ?6)
+           AND (?7 IS NULL OR default_req_type = ?7)
+           AND (?8 IS NULL OR EXISTS (SELECT 1 FROM json_each(specs.tags) WHERE lower(json_each.value) = lower(?8)))
+         ORDER BY created_at DESC",
+        SPEC_COLUMNS
+    ))?;
+    let rows = stmt.query_map(
+        params![
+            project_id,
+            filter.owner,
+            filter.version,
+            filter.status,
+            filter.component,
+            filter.default_priority,
+            filter.default_req_type,
+            filter.tag,
+        ],
+        spec_from_row,
     )?;
-    let rows = stmt.query_map(params![project_id], |row| {
-        Ok(Spec {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            filename: row.get(2)?,
-            content: row.get(3)?,
-            parsed_at: row.get(4)?,
-            created_at: row.get(5)?,
-        })
-    })?;
     let mut specs = Vec::new();
     for row in rows {
         specs.push(row?);
@@ -153,6 +262,16 @@ pub fn list_specs(conn: &Connection, project_id: &str) -> Result<Vec<Spec>, AppE
     Ok(specs)
 }
 
+/// The most recently uploaded spec of a project with the given filename.
+pub fn find_spec_by_filename(conn: &Connection, project_id: &str, filename: &str) -> Result<Option<Spec>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM specs WHERE project_id = ?1 AND filename = ?2 ORDER BY created_at DESC LIMIT 1",
+        SPEC_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map(params![project_id, filename], spec_from_row)?;
+    Ok(rows.next().transpose()?)
+}
+
 pub fn delete_spec(conn: &Connection, id: &str) -> Result<(), AppError> {
     let affected = conn.execute("DELETE FROM specs WHERE id = ?1", params![id])?;
     if affected == 0 {
@@ -170,32 +289,195 @@ pub fn update_spec_parsed_at(conn: &Connection, spec_id: &str) -> Result<(), App
     Ok(())
 }
 
+// ─── Spec Sources ───────────────────────────────────────────────
+
+pub fn add_spec_source(conn: &Connection, project_id: &str, pattern: &str) -> Result<SpecSource, AppError> {
+    let source = SpecSource {
+        id: Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        pattern: pattern.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+    };
+    conn.execute(
+        "INSERT INTO spec_sources (id, project_id, pattern, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![source.id, source.project_id, source.pattern, source.created_at],
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            AppError::InvalidInput(format!("Spec source already registered: {}", pattern))
+        }
+        e => AppError::Database(e),
+    })?;
+    Ok(source)
+}
+
+pub fn list_spec_sources(conn: &Connection, project_id: &str) -> Result<Vec<SpecSource>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, pattern, created_at FROM spec_sources WHERE project_id = ?1 ORDER BY created_at"
+    )?;
+    let rows = stmt.query_map(params![project_id], spec_source_from_row)?;
+    let mut sources = Vec::new();
+    for row in rows {
+        sources.push(row?);
+    }
+    Ok(sources)
+}
+
+/// Projects with at least one spec source, whose folders are watched at launch.
+pub fn list_projects_with_spec_sources(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare("SELECT DISTINCT project_id FROM spec_sources")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    Ok(ids)
+}
+
+pub fn delete_spec_source(conn: &Connection, id: &str) -> Result<SpecSource, AppError> {
+    let source = conn.query_row(
+        "SELECT id, project_id, pattern, created_at FROM spec_sources WHERE id = ?1",
+        params![id],
+        spec_source_from_row,
+    ).map_err(|_| AppError::NotFound(format!("Spec source not found: {}", id)))?;
+    conn.execute("DELETE FROM spec_sources WHERE id = ?1", params![id])?;
+    Ok(source)
+}
+
+fn spec_source_from_row(row: &rusqlite::Row) -> rusqlite::Result<SpecSource> {
+    Ok(SpecSource {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        pattern: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
 // ─── Requirements ───────────────────────────────────────────────
 
+const REQUIREMENT_COLUMNS: &str = "r.id, r.spec_id, r.parent_id, r.external_id, r.section, r.description, r.req_type, r.priority, r.change_status, r.retired_at, r.scenario, r.priority_keyword, r.start_offset, r.end_offset, r.start_line, r.end_line, r.threshold, r.status, r.manually_edited,
+    (SELECT json_group_array(name) FROM (SELECT t.name FROM requirement_tags rt JOIN tags t ON rt.tag_id = t.id WHERE rt.requirement_id = r.id ORDER BY t.name))";
+
+fn requirement_from_row(row: &rusqlite::Row) -> rusqlite::Result<Requirement> {
+    Ok(Requirement {
+        id: row.get(0)?,
+        spec_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        external_id: row.get(3)?,
+        section: row.get(4)?,
+        description: row.get(5)?,
+        req_type: row.get(6)?,
+        priority: row.get(7)?,
+        change_status: row.get(8)?,
+        status: row.get(17)?,
+        manually_edited: row.get(18)?,
+        retired_at: row.get(9)?,
+        scenario: json_column(row, 10)?,
+        priority_keyword: row.get(11)?,
+        span: span_columns(row, 12)?,
+        threshold: json_column(row, 16)?,
+        tags: json_column(row, 19)?.unwrap_or_default(),
+    })
+}
+
+/// Reads four consecutive span columns; all are NULL for requirements
+/// without a recorded position.
+fn span_columns(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<Option<SourceSpan>> {
+    let start_offset: Option<i64> = row.get(idx)?;
+    Ok(match start_offset {
+        Some(start_offset) => Some(SourceSpan {
+            start_offset,
+            end_offset: row.get(idx + 1)?,
+            start_line: row.get(idx + 2)?,
+            end_line: row.get(idx + 3)?,
+        }),
+        None => None,
+    })
+}
+
+/// Reads an optional JSON-encoded column into its structured form.
+fn json_column<T: serde::de::DeserializeOwned>(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<Option<T>> {
+    let raw: Option<String> = row.get(idx)?;
+    raw.map(|json| {
+        serde_json::from_str(&json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+        })
+    })
+    .transpose()
+}
+
+fn to_json<T: serde::Serialize>(value: &Option<T>) -> Result<Option<String>, AppError> {
+    value.as_ref().map(serde_json::to_string).transpose().map_err(AppError::Serde)
+}
+
 pub fn insert_requirements(conn: &Connection, requirements: &[Requirement]) -> Result<(), AppError> {
     let mut stmt = conn.prepare(
-        "INSERT INTO requirements (id, spec_id, section, description, req_type, priority) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        "INSERT INTO requirements (id, spec_id, parent_id, external_id, section, description, req_type, priority, change_status, retired_at, scenario, priority_keyword, start_offset, end_offset, start_line, end_line, threshold, status, manually_edited) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)"
     )?;
     for req in requirements {
-        stmt.execute(params![req.id, req.spec_id, req.section, req.description, req.req_type, req.priority])?;
+        let span = req.span.as_ref();
+        stmt.execute(params![
+            req.id, req.spec_id, req.parent_id, req.external_id, req.section, req.description, req.req_type, req.priority,
+            req.change_status, req.retired_at, to_json(&req.scenario)?, req.priority_keyword,
+            span.map(|s| s.start_offset), span.map(|s| s.end_offset), span.map(|s| s.start_line), span.map(|s| s.end_line),
+            to_json(&req.threshold)?, req.status, req.manually_edited,
+        ])?;
+        index_requirement(conn, &req.id)?;
     }
     Ok(())
 }
 
-pub fn get_requirements_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec<Requirement>, AppError> {
+/// Overwrites the parsed fields of existing requirements, reactivating any
+/// that were retired. Review status is left as it was.
+pub fn update_requirements(conn: &Connection, requirements: &[Requirement]) -> Result<(), AppError> {
     let mut stmt = conn.prepare(
-        "SELECT id, spec_id, section, description, req_type, priority FROM requirements WHERE spec_id = ?1 ORDER BY section, id"
+        "UPDATE requirements SET parent_id = ?1, external_id = ?2, section = ?3, description = ?4, req_type = ?5, priority = ?6, change_status = ?7, scenario = ?8, priority_keyword = ?9, start_offset = ?10, end_offset = ?11, start_line = ?12, end_line = ?13, threshold = ?14, retired_at = NULL WHERE id = ?15"
     )?;
-    let rows = stmt.query_map(params![spec_id], |row| {
-        Ok(Requirement {
-            id: row.get(0)?,
-            spec_id: row.get(1)?,
-            section: row.get(2)?,
-            description: row.get(3)?,
-            req_type: row.get(4)?,
-            priority: row.get(5)?,
-        })
-    })?;
+    for req in requirements {
+        let span = req.span.as_ref();
+        stmt.execute(params![
+            req.parent_id, req.external_id, req.section, req.description, req.req_type, req.priority, req.change_status,
+            to_json(&req.scenario)?, req.priority_keyword,
+            span.map(|s| s.start_offset), span.map(|s| s.end_offset), span.map(|s| s.start_line), span.map(|s| s.end_line),
+            to_json(&req.threshold)?, req.id,
+        ])?;
+        index_requirement(conn, &req.id)?;
+    }
+    Ok(())
+}
+
+pub fn retire_requirements(conn: &Connection, ids: &[String]) -> Result<(), AppError> {
+    let now = Utc::now().to_rfc3339();
+    let mut stmt = conn.prepare(
+        "UPDATE requirements SET retired_at = ?1 WHERE id = ?2 AND retired_at IS NULL"
+    )?;
+    for id in ids {
+        stmt.execute(params![now, id])?;
+        index_requirement(conn, id)?;
+    }
+    Ok(())
+}
+
+pub fn get_requirements_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec<Requirement>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM requirements r WHERE r.spec_id = ?1 AND r.retired_at IS NULL ORDER BY r.section, r.id",
+        REQUIREMENT_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![spec_id], requirement_from_row)?;
+    let mut reqs = Vec::new();
+    for row in rows {
+        reqs.push(row?);
+    }
+    Ok(reqs)
+}
+
+/// All requirements ever parsed from a spec, retired ones included.
+pub fn get_all_requirements_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec<Requirement>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM requirements r WHERE r.spec_id = ?1 ORDER BY r.section, r.id",
+        REQUIREMENT_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![spec_id], requirement_from_row)?;
     let mut reqs = Vec::new();
     for row in rows {
         reqs.push(row?);
@@ -204,23 +486,15 @@ pub fn get_requirements_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec
 }
 
 pub fn get_requirements_for_project(conn: &Connection, project_id: &str) -> Result<Vec<Requirement>, AppError> {
-    let mut stmt = conn.prepare(
-        "SELECT r.id, r.spec_id, r.section, r.description, r.req_type, r.priority
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
          FROM requirements r
          JOIN specs s ON r.spec_id = s.id
-         WHERE s.project_id = ?1
-         ORDER BY r.section, r.id"
-    )?;
-    let rows = stmt.query_map(params![project_id], |row| {
-        Ok(Requirement {
-            id: row.get(0)?,
-            spec_id: row.get(1)?,
-            section: row.get(2)?,
-            description: row.get(3)?,
-            req_type: row.get(4)?,
-            priority: row.get(5)?,
-        })
-    })?;
+         WHERE s.project_id = ?1 AND r.retired_at IS NULL
+         ORDER BY r.section, r.id",
+        REQUIREMENT_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![project_id], requirement_from_row)?;
     let mut reqs = Vec::new();
     for row in rows {
         reqs.push(row?);
@@ -228,53 +502,338 @@ pub fn get_requirements_for_project(conn: &Connection, project_id: &str) -> Resu
     Ok(reqs)
 }
 
-pub fn delete_requirements_for_spec(conn: &Connection, spec_id: &str) -> Result<(), AppError> {
-    conn.execute("DELETE FROM requirements WHERE spec_id = ?1", params![spec_id])?;
-    Ok(())
+/// External IDs already used by active requirements of other specs in the project.
+pub fn get_external_ids_in_other_specs(conn: &Connection, project_id: &str, spec_id: &str) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT r.external_id
+         FROM requirements r
+         JOIN specs s ON r.spec_id = s.id
+         WHERE s.project_id = ?1 AND r.spec_id != ?2 AND r.external_id IS NOT NULL AND r.retired_at IS NULL"
+    )?;
+    let rows = stmt.query_map(params![project_id, spec_id], |row| row.get(0))?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    Ok(ids)
 }
 
 pub fn get_requirement(conn: &Connection, id: &str) -> Result<Requirement, AppError> {
     conn.query_row(
-        "SELECT id, spec_id, section, description, req_type, priority FROM requirements WHERE id = ?1",
+        &format!("SELECT {} FROM requirements r WHERE r.id = ?1", REQUIREMENT_COLUMNS),
         params![id],
-        |row| {
-            Ok(Requirement {
-                id: row.get(0)?,
-                spec_id: row.get(1)?,
-                section: row.get(2)?,
-                description: row.get(3)?,
-                req_type: row.get(4)?,
-                priority: row.get(5)?,
-            })
-        },
+        requirement_from_row,
     ).map_err(|_| AppError::NotFound(format!("Requirement not found: {}", id)))
 }
 
-// ─── Generated Tests ────────────────────────────────────────────
+/// Saves a hand edit of a requirement's fields and marks it as manually edited.
+pub fn update_requirement_fields(conn: &Connection, req: &Requirement) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE requirements SET section = ?1, description = ?2, req_type = ?3, priority = ?4, priority_keyword = ?5, threshold = ?6, manually_edited = 1 WHERE id = ?7",
+        params![req.section, req.description, req.req_type, req.priority, req.priority_keyword, to_json(&req.threshold)?, req.id],
+    )?;
+    index_requirement(conn, &req.id)?;
+    Ok(())
+}
 
-pub fn insert_generated_test(conn: &Connection, test: &GeneratedTest) -> Result<(), AppError> {
+/// Retires a requirement deleted by hand, so re-parsing does not bring it back.
+pub fn retire_requirement_manually(conn: &Connection, id: &str) -> Result<(), AppError> {
     conn.execute(
-        "INSERT INTO generated_tests (id, requirement_id, framework, code, generation_mode, file_path, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![test.id, test.requirement_id, test.framework, test.code, test.generation_mode, test.file_path, test.created_at],
+        "UPDATE requirements SET retired_at = ?1, manually_edited = 1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), id],
     )?;
+    index_requirement(conn, id)?;
     Ok(())
 }
 
-pub fn get_generated_tests_for_requirement(conn: &Connection, requirement_id: &str) -> Result<Vec<GeneratedTest>, AppError> {
+pub fn insert_requirement_edit(conn: &Connection, edit: &RequirementEdit) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO requirement_edits (id, requirement_id, action, before_fields, after_fields, edited_by, edited_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![edit.id, edit.requirement_id, edit.action, to_json(&edit.before)?, to_json(&edit.after)?, edit.edited_by, edit.edited_at],
+    )?;
+    Ok(())
+}
+
+const REQUIREMENT_EDIT_COLUMNS: &str = "e.id, e.requirement_id, e.action, e.before_fields, e.after_fields, e.edited_by, e.edited_at";
+
+fn requirement_edit_from_row(row: &rusqlite::Row) -> rusqlite::Result<RequirementEdit> {
+    Ok(RequirementEdit {
+        id: row.get(0)?,
+        requirement_id: row.get(1)?,
+        action: row.get(2)?,
+        before: json_column(row, 3)?,
+        after: json_column(row, 4)?,
+        edited_by: row.get(5)?,
+        edited_at: row.get(6)?,
+    })
+}
+
+/// A requirement's manual edits, oldest first.
+pub fn get_requirement_edits(conn: &Connection, requirement_id: &str) -> Result<Vec<RequirementEdit>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM requirement_edits e WHERE e.requirement_id = ?1 ORDER BY e.edited_at, e.rowid",
+        REQUIREMENT_EDIT_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![requirement_id], requirement_edit_from_row)?;
+    let mut edits = Vec::new();
+    for row in rows {
+        edits.push(row?);
+    }
+    Ok(edits)
+}
+
+/// Manual edits of all of a spec's requirements, oldest first.
+pub fn get_requirement_edits_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec<RequirementEdit>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM requirement_edits e JOIN requirements r ON e.requirement_id = r.id
+         WHERE r.spec_id = ?1 ORDER BY e.edited_at, e.rowid",
+        REQUIREMENT_EDIT_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![spec_id], requirement_edit_from_row)?;
+    let mut edits = Vec::new();
+    for row in rows {
+        edits.push(row?);
+    }
+    Ok(edits)
+}
+
+pub fn update_requirement_status(conn: &Connection, id: &str, status: &str) -> Result<(), AppError> {
+    conn.execute("UPDATE requirements SET status = ?1 WHERE id = ?2", params![status, id])?;
+    Ok(())
+}
+
+pub fn insert_requirement_status_change(conn: &Connection, change: &RequirementStatusChange) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO requirement_status_changes (id, requirement_id, from_status, to_status, changed_by, reason, changed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![change.id, change.requirement_id, change.from_status, change.to_status, change.changed_by, change.reason, change.changed_at],
+    )?;
+    Ok(())
+}
+
+/// A requirement's status history, oldest first.
+pub fn get_requirement_status_changes(conn: &Connection, requirement_id: &str) -> Result<Vec<RequirementStatusChange>, AppError> {
     let mut stmt = conn.prepare(
-        "SELECT id, requirement_id, framework, code, generation_mode, file_path, created_at FROM generated_tests WHERE requirement_id = ?1 ORDER BY created_at DESC"
+        "SELECT id, requirement_id, from_status, to_status, changed_by, reason, changed_at
+         FROM requirement_status_changes WHERE requirement_id = ?1 ORDER BY changed_at, rowid"
     )?;
     let rows = stmt.query_map(params![requirement_id], |row| {
-        Ok(GeneratedTest {
+        Ok(RequirementStatusChange {
             id: row.get(0)?,
             requirement_id: row.get(1)?,
-            framework: row.get(2)?,
-            code: row.get(3)?,
-            generation_mode: row.get(4)?,
-            file_path: row.get(5)?,
-            created_at: row.get(6)?,
+            from_status: row.get(2)?,
+            to_status: row.get(3)?,
+            changed_by: row.get(4)?,
+            reason: row.get(5)?,
+            changed_at: row.get(6)?,
         })
     })?;
+    let mut changes = Vec::new();
+    for row in rows {
+        changes.push(row?);
+    }
+    Ok(changes)
+}
+
+// ─── Lint Findings ──────────────────────────────────────────────
+
+/// Replaces a spec's findings with those of its latest parse.
+pub fn replace_lint_findings(conn: &Connection, spec_id: &str, findings: &[LintFinding]) -> Result<(), AppError> {
+    conn.execute("DELETE FROM lint_findings WHERE spec_id = ?1", params![spec_id])?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO lint_findings (id, spec_id, requirement_id, rule, severity, message, excerpt, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+    )?;
+    for f in findings {
+        stmt.execute(params![f.id, f.spec_id, f.requirement_id, f.rule, f.severity, f.message, f.excerpt, f.created_at])?;
+    }
+    Ok(())
+}
+
+/// Findings for a spec's active requirements, in document order.
+pub fn get_lint_findings_for_spec(conn: &Connection, spec_id: &str) -> Result<Vec<LintFinding>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT f.id, f.spec_id, f.requirement_id, f.rule, f.severity, f.message, f.excerpt, f.created_at
+         FROM lint_findings f
+         JOIN requirements r ON f.requirement_id = r.id
+         WHERE f.spec_id = ?1 AND r.retired_at IS NULL
+         ORDER BY r.start_offset, r.section, r.id, f.rule"
+    )?;
+    let rows = stmt.query_map(params![spec_id], |row| {
+        Ok(LintFinding {
+            id: row.get(0)?,
+            spec_id: row.get(1)?,
+            requirement_id: row.get(2)?,
+            rule: row.get(3)?,
+            severity: row.get(4)?,
+            message: row.get(5)?,
+            excerpt: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    })?;
+    let mut findings = Vec::new();
+    for row in rows {
+        findings.push(row?);
+    }
+    Ok(findings)
+}
+
+// ─── Tags ───────────────────────────────────────────────────────
+
+const TAG_COLUMNS: &str = "t.id, t.project_id, t.name, t.paths, t.created_at,
+    (SELECT COUNT(*) FROM requirement_tags rt JOIN requirements r ON rt.requirement_id = r.id WHERE rt.tag_id = t.id AND r.retired_at IS NULL)";
+
+fn tag_from_row(row: &rusqlite::Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        paths: json_column(row, 3)?.unwrap_or_default(),
+        created_at: row.get(4)?,
+        requirement_count: row.get(5)?,
+    })
+}
+
+pub fn list_tags(conn: &Connection, project_id: &str) -> Result<Vec<Tag>, AppError> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM tags t WHERE t.project_id = ?1 ORDER BY t.name", TAG_COLUMNS))?;
+    let rows = stmt.query_map(params![project_id], tag_from_row)?;
+    let mut tags = Vec::new();
+    for row in rows {
+        tags.push(row?);
+    }
+    Ok(tags)
+}
+
+pub fn get_tag(conn: &Connection, id: &str) -> Result<Tag, AppError> {
+    conn.query_row(&format!("SELECT {} FROM tags t WHERE t.id = ?1", TAG_COLUMNS), params![id], tag_from_row)
+        .map_err(|_| AppError::NotFound(format!("Tag not found: {}", id)))
+}
+
+pub fn get_tag_by_name(conn: &Connection, project_id: &str, name: &str) -> Result<Option<Tag>, AppError> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM tags t WHERE t.project_id = ?1 AND t.name = ?2", TAG_COLUMNS))?;
+    let mut rows = stmt.query_map(params![project_id, name], tag_from_row)?;
+    Ok(rows.next().transpose()?)
+}
+
+/// The project's tag with this name, created when it doesn't exist yet.
+pub fn get_or_create_tag(conn: &Connection, project_id: &str, name: &str) -> Result<Tag, AppError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tags (id, project_id, name, paths, created_at) VALUES (?1, ?2, ?3, '[]', ?4)",
+        params![Uuid::new_v4().to_string(), project_id, name, Utc::now().to_rfc3339()],
+    )?;
+    get_tag_by_name(conn, project_id, name)?
+        .ok_or_else(|| AppError::NotFound(format!("Tag not found: {}", name)))
+}
+
+pub fn update_tag_paths(conn: &Connection, id: &str, paths: &[String]) -> Result<(), AppError> {
+    conn.execute("UPDATE tags SET paths = ?1 WHERE id = ?2", params![serde_json::to_string(paths)?, id])?;
+    Ok(())
+}
+
+pub fn delete_tag(conn: &Connection, project_id: &str, id: &str) -> Result<(), AppError> {
+    let affected = conn.execute("DELETE FROM tags WHERE id = ?1 AND project_id = ?2", params![id, project_id])?;
+    if affected == 0 {
+        return Err(AppError::NotFound(format!("Tag not found: {}", id)));
+    }
+    Ok(())
+}
+
+/// Replaces a requirement's inline tags; tags added by hand stay.
+pub fn set_inline_tags(conn: &Connection, requirement_id: &str, tag_ids: &[String]) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM requirement_tags WHERE requirement_id = ?1 AND source = 'inline'",
+        params![requirement_id],
+    )?;
+    for tag_id in tag_ids {
+        add_requirement_tag(conn, requirement_id, tag_id, "inline")?;
+    }
+    Ok(())
+}
+
+/// Links a tag to a requirement. `source` is "inline" or "manual"; an
+/// existing link keeps its source.
+pub fn add_requirement_tag(conn: &Connection, requirement_id: &str, tag_id: &str, source: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO requirement_tags (requirement_id, tag_id, source) VALUES (?1, ?2, ?3)",
+        params![requirement_id, tag_id, source],
+    )?;
+    Ok(())
+}
+
+pub fn remove_requirement_tag(conn: &Connection, requirement_id: &str, tag_id: &str) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM requirement_tags WHERE requirement_id = ?1 AND tag_id = ?2",
+        params![requirement_id, tag_id],
+    )?;
+    Ok(())
+}
+
+// ─── Detection Rules ────────────────────────────────────────────
+
+/// The project's configured detection rules, or `None` to use the defaults.
+pub fn get_detection_rules(conn: &Connection, project_id: &str) -> Result<Option<DetectionRules>, AppError> {
+    let mut stmt = conn.prepare("SELECT rules FROM detection_rules WHERE project_id = ?1")?;
+    let mut rows = stmt.query(params![project_id])?;
+    match rows.next()? {
+        Some(row) => {
+            let json: String = row.get(0)?;
+            Ok(Some(serde_json::from_str(&json)?))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn save_detection_rules(conn: &Connection, project_id: &str, rules: &DetectionRules) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO detection_rules (project_id, rules, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(project_id) DO UPDATE SET rules = excluded.rules, updated_at = excluded.updated_at",
+        params![project_id, serde_json::to_string(rules)?, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+pub fn delete_detection_rules(conn: &Connection, project_id: &str) -> Result<(), AppError> {
+    conn.execute("DELETE FROM detection_rules WHERE project_id = ?1", params![project_id])?;
+    Ok(())
+}
+
+// ─── Generated Tests ────────────────────────────────────────────
+
+const GENERATED_TEST_COLUMNS: &str =
+    "gt.id, gt.requirement_id, gt.framework, gt.code, gt.generation_mode, gt.file_path, gt.created_at, gt.version, gt.previous_version_id, gt.repair_outcome";
+
+/// Earlier attempts of a repaired test are history; lists only show the
+/// latest one.
+const LATEST_VERSION: &str = "NOT EXISTS (SELECT 1 FROM generated_tests n WHERE n.previous_version_id = gt.id)";
+
+fn generated_test_from_row(row: &rusqlite::Row) -> rusqlite::Result<GeneratedTest> {
+    Ok(GeneratedTest {
+        id: row.get(0)?,
+        requirement_id: row.get(1)?,
+        framework: row.get(2)?,
+        code: row.get(3)?,
+        generation_mode: row.get(4)?,
+        file_path: row.get(5)?,
+        created_at: row.get(6)?,
+        version: row.get(7)?,
+        previous_version_id: row.get(8)?,
+        repair_outcome: row.get(9)?,
+    })
+}
+
+pub fn insert_generated_test(conn: &Connection, test: &GeneratedTest) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO generated_tests (id, requirement_id, framework, code, generation_mode, file_path, created_at, version, previous_version_id, repair_outcome) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![test.id, test.requirement_id, test.framework, test.code, test.generation_mode, test.file_path, test.created_at, test.version, test.previous_version_id, test.repair_outcome],
+    )?;
+    index_generated_test(conn, &test.id)?;
+    Ok(())
+}
+
+pub fn get_generated_tests_for_requirement(conn: &Connection, requirement_id: &str) -> Result<Vec<GeneratedTest>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM generated_tests gt WHERE gt.requirement_id = ?1 AND {} ORDER BY gt.created_at DESC",
+        GENERATED_TEST_COLUMNS, LATEST_VERSION
+    ))?;
+    let rows = stmt.query_map(params![requirement_id], generated_test_from_row)?;
     let mut tests = Vec::new();
     for row in rows {
         tests.push(row?);
@@ -284,50 +843,63 @@ pub fn get_generated_tests_for_requirement(conn: &Connection, requirement_id: &s
 
 pub fn get_generated_test(conn: &Connection, id: &str) -> Result<GeneratedTest, AppError> {
     conn.query_row(
-        "SELECT id, requirement_id, framework, code, generation_mode, file_path, created_at FROM generated_tests WHERE id = ?1",
+        &format!("SELECT {} FROM generated_tests gt WHERE gt.id = ?1", GENERATED_TEST_COLUMNS),
         params![id],
-        |row| {
-            Ok(GeneratedTest {
-                id: row.get(0)?,
-                requirement_id: row.get(1)?,
-                framework: row.get(2)?,
-                code: row.get(3)?,
-                generation_mode: row.get(4)?,
-                file_path: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        },
+        generated_test_from_row,
     ).map_err(|_| AppError::NotFound(format!("Generated test not found: {}", id)))
 }
 
+/// Every attempt that led to a test, first version first.
+pub fn get_generated_test_versions(conn: &Connection, id: &str) -> Result<Vec<GeneratedTest>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "WITH RECURSIVE chain(id) AS (
+            SELECT ?1
+            UNION ALL
+            SELECT t.previous_version_id FROM generated_tests t JOIN chain c ON t.id = c.id
+            WHERE t.previous_version_id IS NOT NULL
+         )
+         SELECT {} FROM generated_tests gt JOIN chain c ON gt.id = c.id ORDER BY gt.version",
+        GENERATED_TEST_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![id], generated_test_from_row)?;
+    let mut tests = Vec::new();
+    for row in rows {
+        tests.push(row?);
+    }
+    if tests.is_empty() {
+        return Err(AppError::NotFound(format!("Generated test not found: {}", id)));
+    }
+    Ok(tests)
+}
+
 pub fn update_generated_test_path(conn: &Connection, id: &str, path: &str) -> Result<(), AppError> {
     conn.execute(
         "UPDATE generated_tests SET file_path = ?1 WHERE id = ?2",
         params![path, id],
     )?;
+    index_generated_test(conn, id)?;
+    Ok(())
+}
+
+pub fn set_repair_outcome(conn: &Connection, id: &str, outcome: &str) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE generated_tests SET repair_outcome = ?1 WHERE id = ?2",
+        params![outcome, id],
+    )?;
     Ok(())
 }
 
 pub fn get_generated_tests_for_project(conn: &Connection, project_id: &str) -> Result<Vec<GeneratedTest>, AppError> {
-    let mut stmt = conn.prepare(
-        "SELECT gt.id, gt.requirement_id, gt.framework, gt.code, gt.generation_mode, gt.file_path, gt.created_at
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
          FROM generated_tests gt
          JOIN requirements r ON gt.requirement_id = r.id
          JOIN specs s ON r.spec_id = s.id
-         WHERE s.project_id = ?1
-         ORDER BY gt.created_at DESC"
-    )?;
-    let rows = stmt.query_map(params![project_id], |row| {
-        Ok(GeneratedTest {
-            id: row.get(0)?,
-            requirement_id: row.get(1)?,
-            framework: row.get(2)?,
-            code: row.get(3)?,
-            generation_mode: row.get(4)?,
-            file_path: row.get(5)?,
-            created_at: row.get(6)?,
-        })
-    })?;
+         WHERE s.project_id = ?1 AND {}
+         ORDER BY gt.created_at DESC",
+        GENERATED_TEST_COLUMNS, LATEST_VERSION
+    ))?;
+    let rows = stmt.query_map(params![project_id], generated_test_from_row)?;
     let mut tests = Vec::new();
     for row in rows {
         tests.push(row?);
@@ -345,16 +917,18 @@ pub fn insert_test_result(conn: &Connection, result: &TestResult) -> Result<(),
     Ok(())
 }
 
+/// Results of the latest version of each of a project's tests.
 pub fn get_test_results_for_project(conn: &Connection, project_id: &str) -> Result<Vec<TestResult>, AppError> {
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare(&format!(
         "SELECT tr.id, tr.generated_test_id, tr.status, tr.execution_time_ms, tr.stdout, tr.stderr, tr.executed_at
          FROM test_results tr
          JOIN generated_tests gt ON tr.generated_test_id = gt.id
          JOIN requirements r ON gt.requirement_id = r.id
          JOIN specs s ON r.spec_id = s.id
-         WHERE s.project_id = ?1
-         ORDER BY tr.executed_at DESC"
-    )?;
+         WHERE s.project_id = ?1 AND {}
+         ORDER BY tr.executed_at DESC",
+        LATEST_VERSION
+    ))?;
     let rows = stmt.query_map(params![project_id], |row| {
         Ok(TestResult {
             id: row.get(0)?,
@@ -418,8 +992,8 @@ pub fn get_latest_test_result_for_test(conn: &Connection, generated_test_id: &st
 
 pub fn insert_alignment_report(conn: &Connection, report: &AlignmentReport) -> Result<(), AppError> {
     conn.execute(
-        "INSERT INTO alignment_reports (id, project_id, coverage_percent, total_requirements, covered_requirements, generated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        params![report.id, report.project_id, report.coverage_percent, report.total_requirements, report.covered_requirements, report.generated_at],
+        "INSERT INTO alignment_reports (id, project_id, coverage_percent, total_requirements, covered_requirements, excluded_requirements, tag, generated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![report.id, report.project_id, report.coverage_percent, report.total_requirements, report.covered_requirements, report.excluded_requirements, report.tag, report.generated_at],
     )?;
     Ok(())
 }
@@ -432,40 +1006,88 @@ pub fn insert_mismatch(conn: &Connection, mismatch: &Mismatch) -> Result<(), App
     Ok(())
 }
 
+pub fn insert_report_exclusion(conn: &Connection, report_id: &str, excluded: &ExcludedRequirement) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO report_exclusions (id, report_id, requirement_id, spec_section, description, status, reason) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![Uuid::new_v4().to_string(), report_id, excluded.requirement_id, excluded.spec_section, excluded.description, excluded.status, excluded.reason],
+    )?;
+    Ok(())
+}
+
 pub fn get_alignment_report(conn: &Connection, id: &str) -> Result<AlignmentReportWithMismatches, AppError> {
     let report = conn.query_row(
-        "SELECT id, project_id, coverage_percent, total_requirements, covered_requirements, generated_at FROM alignment_reports WHERE id = ?1",
+        "SELECT id, project_id, coverage_percent, total_requirements, covered_requirements, excluded_requirements, generated_at, tag FROM alignment_reports WHERE id = ?1",
         params![id],
-        |row| {
-            Ok(AlignmentReport {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                coverage_percent: row.get(2)?,
-                total_requirements: row.get(3)?,
-                covered_requirements: row.get(4)?,
-                generated_at: row.get(5)?,
-            })
-        },
+        report_from_row,
     ).map_err(|_| AppError::NotFound(format!("Report not found: {}", id)))?;
 
     let mismatches = get_mismatches_for_report(conn, &report.id)?;
+    let excluded = get_exclusions_for_report(conn, &report.id)?;
+
+    Ok(AlignmentReportWithMismatches { report, mismatches, excluded })
+}
+
+fn report_from_row(row: &rusqlite::Row) -> rusqlite::Result<AlignmentReport> {
+    Ok(AlignmentReport {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        coverage_percent: row.get(2)?,
+        total_requirements: row.get(3)?,
+        covered_requirements: row.get(4)?,
+        excluded_requirements: row.get(5)?,
+        tag: row.get(7)?,
+        generated_at: row.get(6)?,
+    })
+}
 
-    Ok(AlignmentReportWithMismatches { report, mismatches })
+pub fn get_exclusions_for_report(conn: &Connection, report_id: &str) -> Result<Vec<ExcludedRequirement>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT e.requirement_id, r.external_id, s.filename, e.spec_section, e.description, e.status, e.reason
+         FROM report_exclusions e
+         LEFT JOIN requirements r ON e.requirement_id = r.id
+         LEFT JOIN specs s ON r.spec_id = s.id
+         WHERE e.report_id = ?1
+         ORDER BY e.rowid"
+    )?;
+    let rows = stmt.query_map(params![report_id], |row| {
+        Ok(ExcludedRequirement {
+            requirement_id: row.get(0)?,
+            external_id: row.get(1)?,
+            spec_filename: row.get(2)?,
+            spec_section: row.get(3)?,
+            description: row.get(4)?,
+            status: row.get(5)?,
+            reason: row.get(6)?,
+        })
+    })?;
+    let mut excluded = Vec::new();
+    for row in rows {
+        excluded.push(row?);
+    }
+    Ok(excluded)
 }
 
 pub fn get_mismatches_for_report(conn: &Connection, report_id: &str) -> Result<Vec<Mismatch>, AppError> {
     let mut stmt = conn.prepare(
-        "SELECT id, report_id, requirement_id, spec_section, code_element, mismatch_type, details FROM alignment_mismatches WHERE report_id = ?1"
+        "SELECT m.id, m.report_id, m.requirement_id, r.external_id, m.spec_section, m.code_element, m.mismatch_type, m.details,
+                s.filename, r.start_offset, r.end_offset, r.start_line, r.end_line
+         FROM alignment_mismatches m
+         LEFT JOIN requirements r ON m.requirement_id = r.id
+         LEFT JOIN specs s ON r.spec_id = s.id
+         WHERE m.report_id = ?1"
     )?;
     let rows = stmt.query_map(params![report_id], |row| {
         Ok(Mismatch {
             id: row.get(0)?,
             report_id: row.get(1)?,
             requirement_id: row.get(2)?,
-            spec_section: row.get(3)?,
-            code_element: row.get(4)?,
-            mismatch_type: row.get(5)?,
-            details: row.get(6)?,
+            external_id: row.get(3)?,
+            spec_filename: row.get(8)?,
+            span: span_columns(row, 9)?,
+            spec_section: row.get(4)?,
+            code_element: row.get(5)?,
+            mismatch_type: row.get(6)?,
+            details: row.get(7)?,
         })
     })?;
     let mut mismatches = Vec::new();
@@ -477,21 +1099,111 @@ pub fn get_mismatches_for_report(conn: &Connection, report_id: &str) -> Result<V
 
 pub fn list_reports(conn: &Connection, project_id: &str) -> Result<Vec<AlignmentReport>, AppError> {
     let mut stmt = conn.prepare(
-        "SELECT id, project_id, coverage_percent, total_requirements, covered_requirements, generated_at FROM alignment_reports WHERE project_id = ?1 ORDER BY generated_at DESC"
+        "SELECT id, project_id, coverage_percent, total_requirements, covered_requirements, excluded_requirements, generated_at, tag FROM alignment_reports WHERE project_id = ?1 ORDER BY generated_at DESC"
     )?;
-    let rows = stmt.query_map(params![project_id], |row| {
-        Ok(AlignmentReport {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            coverage_percent: row.get(2)?,
-            total_requirements: row.get(3)?,
-            covered_requirements: row.get(4)?,
-            generated_at: row.get(5)?,
-        })
-    })?;
+    let rows = stmt.query_map(params![project_id], report_from_row)?;
     let mut reports = Vec::new();
     for row in rows {
         reports.push(row?);
     }
     Ok(reports)
 }
+
+// ─── Search ─────────────────────────────────────────────────────
+
+/// Start and end of a matched term in highlighted search text. Private-use
+/// characters, so they survive escaping and can't appear in real content.
+pub const MATCH_START: char = '\u{E000}';
+pub const MATCH_END: char = '\u{E001}';
+
+fn index_spec(conn: &Connection, spec_id: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+         SELECT 'spec', id, project_id, id, filename, content FROM specs WHERE id = ?1
+         ON CONFLICT (entity_type, entity_id) DO UPDATE SET title = excluded.title, body = excluded.body",
+        params![spec_id],
+    )?;
+    Ok(())
+}
+
+/// Active requirements are searchable; retired ones drop out.
+fn index_requirement(conn: &Connection, requirement_id: &str) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM search_documents WHERE entity_type = 'requirement' AND entity_id = ?1",
+        params![requirement_id],
+    )?;
+    conn.execute(
+        "INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+         SELECT 'requirement', r.id, s.project_id, s.id, COALESCE(r.external_id || ' ', '') || r.section, r.description
+         FROM requirements r JOIN specs s ON r.spec_id = s.id
+         WHERE r.id = ?1 AND r.retired_at IS NULL",
+        params![requirement_id],
+    )?;
+    Ok(())
+}
+
+/// Only the latest version of a repaired test is searchable; indexing a
+/// repair drops the attempt it replaces.
+fn index_generated_test(conn: &Connection, test_id: &str) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM search_documents WHERE entity_type = 'test'
+           AND entity_id IN (?1, (SELECT previous_version_id FROM generated_tests WHERE id = ?1))",
+        params![test_id],
+    )?;
+    conn.execute(
+        &format!(
+            "INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+             SELECT 'test', gt.id, s.project_id, s.id, COALESCE(gt.file_path, r.description), gt.code
+             FROM generated_tests gt JOIN requirements r ON gt.requirement_id = r.id JOIN specs s ON r.spec_id = s.id
+             WHERE gt.id = ?1 AND {}",
+            LATEST_VERSION
+        ),
+        params![test_id],
+    )?;
+    Ok(())
+}
+
+/// Best matches of an FTS5 query among one type of a project's documents.
+/// Titles weigh more than bodies; matched terms are wrapped in
+/// `MATCH_START` and `MATCH_END`.
+pub fn search_documents(
+    conn: &Connection,
+    project_id: &str,
+    entity_type: &str,
+    fts_query: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT d.entity_type, d.entity_id, d.spec_id, s.filename,
+                highlight(search_index, 0, ?4, ?5),
+                snippet(search_index, 1, ?4, ?5, '…', 24),
+                bm25(search_index, 10.0, 1.0) AS rank
+         FROM search_index
+         JOIN search_documents d ON d.id = search_index.rowid
+         JOIN specs s ON d.spec_id = s.id
+         WHERE search_index MATCH ?1 AND d.project_id = ?2 AND d.entity_type = ?3
+         ORDER BY rank
+         LIMIT ?6"
+    )?;
+    let rows = stmt.query_map(
+        params![fts_query, project_id, entity_type, MATCH_START.to_string(), MATCH_END.to_string(), limit as i64],
+        |row| {
+            let rank: f64 = row.get(6)?;
+            Ok(SearchHit {
+                entity_type: row.get(0)?,
+                entity_id: row.get(1)?,
+                spec_id: row.get(2)?,
+                spec_filename: row.get(3)?,
+                title: row.get(4)?,
+                snippet: row.get(5)?,
+                // bm25 is lower for better matches
+                score: -rank,
+            })
+        },
+    )?;
+    let mut hits = Vec::new();
+    for row in rows {
+        hits.push(row?);
+    }
+    Ok(hits)
+}
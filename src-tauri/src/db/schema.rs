@@ -1,6 +1,6 @@
 use rusqlite::Connection;
 
-const CURRENT_VERSION: i32 = 1;
+const CURRENT_VERSION: i32 = 18;
 
 pub fn run_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
@@ -15,7 +15,60 @@ pub fn run_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
 
     if version < CURRENT_VERSION {
         let tx = conn.unchecked_transaction()?;
-        migrate_v1(&tx)?;
+        if version < 1 {
+            migrate_v1(&tx)?;
+        }
+        if version < 2 {
+            migrate_v2(&tx)?;
+        }
+        if version < 3 {
+            migrate_v3(&tx)?;
+        }
+        if version < 4 {
+            migrate_v4(&tx)?;
+        }
+        if version < 5 {
+            migrate_v5(&tx)?;
+        }
+        if version < 6 {
+            migrate_v6(&tx)?;
+        }
+        if version < 7 {
+            migrate_v7(&tx)?;
+        }
+        if version < 8 {
+            migrate_v8(&tx)?;
+        }
+        if version < 9 {
+            migrate_v9(&tx)?;
+        }
+        if version < 10 {
+            migrate_v10(&tx)?;
+        }
+        if version < 11 {
+            migrate_v11(&tx)?;
+        }
+        if version < 12 {
+            migrate_v12(&tx)?;
+        }
+        if version < 13 {
+            migrate_v13(&tx)?;
+        }
+        if version < 14 {
+            migrate_v14(&tx)?;
+        }
+        if version < 15 {
+            migrate_v15(&tx)?;
+        }
+        if version < 16 {
+            migrate_v16(&tx)?;
+        }
+        if version < 17 {
+            migrate_v17(&tx)?;
+        }
+        if version < 18 {
+            migrate_v18(&tx)?;
+        }
         tx.execute("DELETE FROM schema_version", [])?;
         tx.execute("INSERT INTO schema_version (version) VALUES (?1)", [CURRENT_VERSION])?;
         tx.commit()?;
@@ -108,3 +161,328 @@ fn migrate_v1(conn: &Connection) -> Result<(), rusqlite::Error> {
 
     Ok(())
 }
+
+/// Requirement change tracking: re-parses merge into existing rows instead of
+/// replacing them, so removed requirements are retired rather than deleted.
+fn migrate_v2(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN change_status TEXT NOT NULL DEFAULT 'new';
+        ALTER TABLE requirements ADD COLUMN retired_at TEXT;"
+    )?;
+
+    Ok(())
+}
+
+/// Human requirement IDs (`REQ-AUTH-001`) parsed from spec text.
+fn migrate_v3(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN external_id TEXT;
+
+        CREATE INDEX IF NOT EXISTS idx_requirements_external_id ON requirements(external_id);"
+    )?;
+
+    Ok(())
+}
+
+/// Gherkin scenario steps and examples, stored as JSON.
+fn migrate_v4(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch("ALTER TABLE requirements ADD COLUMN scenario TEXT;")?;
+
+    Ok(())
+}
+
+/// Spec front-matter metadata; tags are a JSON array.
+fn migrate_v5(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE specs ADD COLUMN owner TEXT;
+        ALTER TABLE specs ADD COLUMN version TEXT;
+        ALTER TABLE specs ADD COLUMN status TEXT;
+        ALTER TABLE specs ADD COLUMN component TEXT;
+        ALTER TABLE specs ADD COLUMN default_priority TEXT;
+        ALTER TABLE specs ADD COLUMN default_req_type TEXT;
+        ALTER TABLE specs ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+
+        CREATE INDEX IF NOT EXISTS idx_specs_project_status ON specs(project_id, status);
+        CREATE INDEX IF NOT EXISTS idx_specs_project_component ON specs(project_id, component);"
+    )?;
+
+    Ok(())
+}
+
+/// Acceptance criteria point at the requirement they belong to.
+fn migrate_v6(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN parent_id TEXT REFERENCES requirements(id) ON DELETE SET NULL;
+
+        CREATE INDEX IF NOT EXISTS idx_requirements_parent ON requirements(parent_id);"
+    )?;
+
+    Ok(())
+}
+
+/// Per-project requirement detection rules, stored as JSON.
+fn migrate_v7(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS detection_rules (
+            project_id TEXT PRIMARY KEY NOT NULL,
+            rules TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );"
+    )?;
+
+    Ok(())
+}
+
+/// The wording that set a requirement's priority.
+fn migrate_v8(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch("ALTER TABLE requirements ADD COLUMN priority_keyword TEXT;")?;
+
+    Ok(())
+}
+
+/// Where each requirement sits in its spec file.
+fn migrate_v9(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN start_offset INTEGER;
+        ALTER TABLE requirements ADD COLUMN end_offset INTEGER;
+        ALTER TABLE requirements ADD COLUMN start_line INTEGER;
+        ALTER TABLE requirements ADD COLUMN end_line INTEGER;"
+    )?;
+
+    Ok(())
+}
+
+/// Spec revisions; existing specs become revision 1 of themselves.
+fn migrate_v10(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE specs ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;
+
+        CREATE TABLE IF NOT EXISTS spec_revisions (
+            id TEXT PRIMARY KEY NOT NULL,
+            spec_id TEXT NOT NULL,
+            revision INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (spec_id) REFERENCES specs(id) ON DELETE CASCADE,
+            UNIQUE (spec_id, revision)
+        );"
+    )?;
+
+    let specs: Vec<(String, String, String)> = {
+        let mut stmt = conn.prepare("SELECT id, content, created_at FROM specs")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    for (spec_id, content, created_at) in specs {
+        conn.execute(
+            "INSERT INTO spec_revisions (id, spec_id, revision, content, created_at) VALUES (?1, ?2, 1, ?3, ?4)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), spec_id, content, created_at],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Glob patterns, relative to a project's codebase, that specs are imported from.
+fn migrate_v11(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS spec_sources (
+            id TEXT PRIMARY KEY NOT NULL,
+            project_id TEXT NOT NULL,
+            pattern TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            UNIQUE (project_id, pattern)
+        );"
+    )?;
+
+    Ok(())
+}
+
+/// Requirement quality findings, replaced each time a spec is parsed.
+fn migrate_v12(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS lint_findings (
+            id TEXT PRIMARY KEY NOT NULL,
+            spec_id TEXT NOT NULL,
+            requirement_id TEXT NOT NULL,
+            rule TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            message TEXT NOT NULL,
+            excerpt TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (spec_id) REFERENCES specs(id) ON DELETE CASCADE,
+            FOREIGN KEY (requirement_id) REFERENCES requirements(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_lint_findings_spec ON lint_findings(spec_id);
+        CREATE INDEX IF NOT EXISTS idx_lint_findings_requirement ON lint_findings(requirement_id);"
+    )?;
+
+    Ok(())
+}
+
+/// Structured numeric limits of non-functional requirements, stored as JSON.
+fn migrate_v13(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch("ALTER TABLE requirements ADD COLUMN threshold TEXT;")?;
+
+    Ok(())
+}
+
+/// Requirement review status with its audit trail, and the requirements each
+/// report left out of coverage because of it.
+fn migrate_v14(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN status TEXT NOT NULL DEFAULT 'draft';
+
+        CREATE TABLE IF NOT EXISTS requirement_status_changes (
+            id TEXT PRIMARY KEY NOT NULL,
+            requirement_id TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            changed_by TEXT NOT NULL,
+            reason TEXT,
+            changed_at TEXT NOT NULL,
+            FOREIGN KEY (requirement_id) REFERENCES requirements(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_requirement_status_changes_requirement ON requirement_status_changes(requirement_id);
+
+        ALTER TABLE alignment_reports ADD COLUMN excluded_requirements INTEGER NOT NULL DEFAULT 0;
+
+        CREATE TABLE IF NOT EXISTS report_exclusions (
+            id TEXT PRIMARY KEY NOT NULL,
+            report_id TEXT NOT NULL,
+            requirement_id TEXT NOT NULL,
+            spec_section TEXT NOT NULL,
+            description TEXT NOT NULL,
+            status TEXT NOT NULL,
+            reason TEXT,
+            FOREIGN KEY (report_id) REFERENCES alignment_reports(id) ON DELETE CASCADE,
+            FOREIGN KEY (requirement_id) REFERENCES requirements(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_report_exclusions_report ON report_exclusions(report_id);"
+    )?;
+
+    Ok(())
+}
+
+/// Manually created, edited and deleted requirements, with their edit history.
+fn migrate_v15(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE requirements ADD COLUMN manually_edited INTEGER NOT NULL DEFAULT 0;
+
+        CREATE TABLE IF NOT EXISTS requirement_edits (
+            id TEXT PRIMARY KEY NOT NULL,
+            requirement_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            before_fields TEXT,
+            after_fields TEXT,
+            edited_by TEXT NOT NULL,
+            edited_at TEXT NOT NULL,
+            FOREIGN KEY (requirement_id) REFERENCES requirements(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_requirement_edits_requirement ON requirement_edits(requirement_id);"
+    )?;
+
+    Ok(())
+}
+
+/// Project tags mapped to codebase paths, linked to requirements, and the tag
+/// an alignment report was limited to.
+fn migrate_v16(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id TEXT PRIMARY KEY NOT NULL,
+            project_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            paths TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            UNIQUE (project_id, name)
+        );
+
+        CREATE TABLE IF NOT EXISTS requirement_tags (
+            requirement_id TEXT NOT NULL,
+            tag_id TEXT NOT NULL,
+            source TEXT NOT NULL DEFAULT 'inline',
+            PRIMARY KEY (requirement_id, tag_id),
+            FOREIGN KEY (requirement_id) REFERENCES requirements(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_requirement_tags_tag ON requirement_tags(tag_id);
+
+        ALTER TABLE alignment_reports ADD COLUMN tag TEXT;"
+    )?;
+
+    Ok(())
+}
+
+/// Full-text search over spec content, requirement descriptions and
+/// generated test code. Queries keep `search_documents` up to date; the FTS5
+/// index follows it through triggers, and deleting a spec or project removes
+/// its documents.
+fn migrate_v17(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS search_documents (
+            id INTEGER PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            project_id TEXT NOT NULL,
+            spec_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            FOREIGN KEY (spec_id) REFERENCES specs(id) ON DELETE CASCADE,
+            UNIQUE (entity_type, entity_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_search_documents_project ON search_documents(project_id);
+        CREATE INDEX IF NOT EXISTS idx_search_documents_spec ON search_documents(spec_id);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            title, body, content='search_documents', content_rowid='id', tokenize='porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS search_documents_ai AFTER INSERT ON search_documents BEGIN
+            INSERT INTO search_index (rowid, title, body) VALUES (new.id, new.title, new.body);
+        END;
+        CREATE TRIGGER IF NOT EXISTS search_documents_ad AFTER DELETE ON search_documents BEGIN
+            INSERT INTO search_index (search_index, rowid, title, body) VALUES ('delete', old.id, old.title, old.body);
+        END;
+        CREATE TRIGGER IF NOT EXISTS search_documents_au AFTER UPDATE ON search_documents BEGIN
+            INSERT INTO search_index (search_index, rowid, title, body) VALUES ('delete', old.id, old.title, old.body);
+            INSERT INTO search_index (rowid, title, body) VALUES (new.id, new.title, new.body);
+        END;
+
+        INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+            SELECT 'spec', id, project_id, id, filename, content FROM specs;
+        INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+            SELECT 'requirement', r.id, s.project_id, s.id, COALESCE(r.external_id || ' ', '') || r.section, r.description
+            FROM requirements r JOIN specs s ON r.spec_id = s.id
+            WHERE r.retired_at IS NULL;
+        INSERT INTO search_documents (entity_type, entity_id, project_id, spec_id, title, body)
+            SELECT 'test', t.id, s.project_id, s.id, COALESCE(t.file_path, r.description), t.code
+            FROM generated_tests t JOIN requirements r ON t.requirement_id = r.id JOIN specs s ON r.spec_id = s.id;"
+    )?;
+
+    Ok(())
+}
+
+/// Versioned generated tests for the generate-run-repair loop: each repair
+/// is a new row pointing at the attempt it replaces, and the last attempt
+/// records how the loop ended.
+fn migrate_v18(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "ALTER TABLE generated_tests ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+        ALTER TABLE generated_tests ADD COLUMN previous_version_id TEXT REFERENCES generated_tests(id) ON DELETE SET NULL;
+        ALTER TABLE generated_tests ADD COLUMN repair_outcome TEXT;
+
+        CREATE INDEX IF NOT EXISTS idx_generated_tests_previous_version ON generated_tests(previous_version_id);"
+    )?;
+
+    Ok(())
+}
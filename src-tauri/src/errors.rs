@@ -17,6 +17,15 @@ pub enum AppError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    /// An error response from an LLM provider's API
+    #[error("{provider} API error ({status}): {body}")]
+    Api {
+        provider: String,
+        status: reqwest::StatusCode,
+        retry_after: Option<std::time::Duration>,
+        body: String,
+    },
+
     #[error("Not found: {0}")]
     NotFound(String),
 
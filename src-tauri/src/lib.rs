@@ -6,6 +6,7 @@ mod services;
 mod utils;
 
 use db::Database;
+use services::{generation_jobs::GenerationJobs, spec_watcher::{self, SpecWatchers}};
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -21,7 +22,15 @@ pub fn run() {
                 .expect("failed to get app data dir");
             let database = Database::new(&app_data_dir)
                 .expect("failed to initialize database");
+
             app.manage(database);
+            app.manage(SpecWatchers::default());
+            app.manage(GenerationJobs::default());
+
+            // Watchers need the state above; importing a large glob must not
+            // hold up the window
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || spec_watcher::resume(&app_handle));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -37,11 +46,42 @@ pub fn run() {
             commands::spec::list_specs,
             commands::spec::delete_spec,
             commands::spec::reparse_spec,
+            commands::spec::list_spec_revisions,
+            commands::spec::diff_spec_revisions,
+            commands::spec::get_lint_findings,
+            commands::spec::find_requirement_overlaps,
             commands::spec::read_file_content,
+            // Spec Sources
+            commands::spec_source::list_spec_sources,
+            commands::spec_source::add_spec_source,
+            commands::spec_source::remove_spec_source,
+            commands::spec_source::import_spec_sources,
+            // Requirements
+            commands::requirement::set_requirement_status,
+            commands::requirement::get_requirement_status_history,
+            commands::requirement::create_requirement,
+            commands::requirement::update_requirement,
+            commands::requirement::delete_requirement,
+            commands::requirement::get_requirement_edit_history,
+            // Tags
+            commands::tag::list_tags,
+            commands::tag::save_tag,
+            commands::tag::delete_tag,
+            commands::tag::add_requirement_tag,
+            commands::tag::remove_requirement_tag,
+            // Search
+            commands::search::search,
+            // Detection Rules
+            commands::rules::get_detection_rules,
+            commands::rules::save_detection_rules,
+            commands::rules::reset_detection_rules,
+            commands::rules::preview_spec_parse,
             // Test Generation
             commands::test_gen::generate_tests,
+            commands::test_gen::cancel_generation,
             commands::test_gen::get_generated_tests,
             commands::test_gen::get_all_generated_tests,
+            commands::test_gen::get_generated_test_versions,
             commands::test_gen::save_test_to_disk,
             commands::test_gen::save_settings,
             commands::test_gen::load_settings,
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use crate::models::spec::Requirement;
+
+/// A quality problem found in a requirement's wording.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LintFinding {
+    pub id: String,
+    pub spec_id: String,
+    pub requirement_id: String,
+    /// "ambiguous_term", "unmeasurable", "compound", "passive_voice" or "duplicate"
+    pub rule: String,
+    /// "warning" or "info"
+    pub severity: String,
+    pub message: String,
+    /// The words that triggered the finding, when there are specific ones
+    pub excerpt: Option<String>,
+    pub created_at: String,
+}
+
+/// Duplicate clusters and conflicting pairs among a project's requirements.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RequirementOverlaps {
+    pub duplicates: Vec<DuplicateCluster>,
+    pub conflicts: Vec<RequirementConflict>,
+}
+
+/// Requirements, possibly from different specs, that say the same thing.
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateCluster {
+    pub requirements: Vec<Requirement>,
+    /// Lowest similarity among the pairs that link the cluster
+    pub similarity: f64,
+}
+
+/// Two requirements about the same thing that cannot both hold.
+#[derive(Debug, Serialize, Clone)]
+pub struct RequirementConflict {
+    /// "modal" (one requires what the other forbids) or "numeric" (different limits)
+    pub kind: String,
+    pub message: String,
+    pub first: Requirement,
+    pub second: Requirement,
+}
@@ -1,4 +1,8 @@
 pub mod project;
 pub mod spec;
+pub mod rules;
+pub mod lint;
 pub mod test;
 pub mod report;
+pub mod tag;
+pub mod search;
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::spec::SourceSpan;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AlignmentReport {
@@ -7,6 +8,12 @@ pub struct AlignmentReport {
     pub coverage_percent: f64,
     pub total_requirements: i64,
     pub covered_requirements: i64,
+    /// Top-level requirements left out of the coverage figures for being
+    /// waived or deprecated. Excluded acceptance criteria are listed with the
+    /// report but, as in `total_requirements`, not counted.
+    pub excluded_requirements: i64,
+    /// Tag the report was limited to; `None` covers the whole project
+    pub tag: Option<String>,
     pub generated_at: String,
 }
 
@@ -15,15 +22,31 @@ pub struct Mismatch {
     pub id: String,
     pub report_id: String,
     pub requirement_id: String,
+    pub external_id: Option<String>,
+    pub spec_filename: Option<String>,
+    pub span: Option<SourceSpan>,
     pub spec_section: String,
     pub code_element: Option<String>,
     pub mismatch_type: String,
     pub details: String,
 }
 
+/// A requirement a report did not count because it was waived or deprecated.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExcludedRequirement {
+    pub requirement_id: String,
+    pub external_id: Option<String>,
+    pub spec_filename: Option<String>,
+    pub spec_section: String,
+    pub description: String,
+    pub status: String,
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct AlignmentReportWithMismatches {
     #[serde(flatten)]
     pub report: AlignmentReport,
     pub mismatches: Vec<Mismatch>,
+    pub excluded: Vec<ExcludedRequirement>,
 }
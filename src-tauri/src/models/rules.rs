@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-project configuration of how the spec parser recognises and classifies
+/// requirements. Keywords match case-insensitively as substrings; patterns
+/// are case-insensitive regular expressions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DetectionRules {
+    /// Headings whose list items are all treated as requirements.
+    pub section_keywords: Vec<String>,
+    pub section_patterns: Vec<String>,
+    /// Openings that mark a list item or paragraph as a requirement.
+    pub requirement_prefixes: Vec<String>,
+    pub requirement_patterns: Vec<String>,
+    /// Words that make a free-standing paragraph a requirement.
+    pub normative_keywords: Vec<String>,
+    /// Checked in order; the first keyword found sets the priority. Keywords
+    /// match whole words, and negated uses ("could not", "not required") are
+    /// skipped unless the rule is `negatable`.
+    pub priority_keywords: Vec<PriorityRule>,
+    /// Checked in order; the first matching rule sets the type.
+    pub type_rules: Vec<TypeRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PriorityRule {
+    pub keyword: String,
+    /// "high", "medium" or "low"
+    pub priority: String,
+    /// "<keyword> not" keeps the priority, as RFC 2119 "MUST NOT" does.
+    #[serde(default)]
+    pub negatable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct TypeRule {
+    /// Built-in ("functional", "non_functional", "constraint") or custom, e.g. "security".
+    pub req_type: String,
+    pub section_keywords: Vec<String>,
+    pub text_keywords: Vec<String>,
+    pub patterns: Vec<String>,
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for DetectionRules {
+    fn default() -> Self {
+        Self {
+            section_keywords: strings(&[
+                "requirement",
+                "user stor",
+                "feature",
+                "functional",
+                "specification",
+                "capability",
+                "constraint",
+                "acceptance criteria",
+                "use case",
+            ]),
+            section_patterns: Vec::new(),
+            requirement_prefixes: strings(&[
+                "as a ",
+                "the system shall ",
+                "the system must ",
+                "the application shall ",
+                "the application must ",
+                "shall ",
+                "must ",
+            ]),
+            requirement_patterns: strings(&[r"\*\*(shall|must)\*\*"]),
+            normative_keywords: strings(&["shall", "must"]),
+            // RFC 2119 and MoSCoW, strongest first
+            priority_keywords: [
+                ("must", "high", true),
+                ("shall", "high", true),
+                ("required", "high", false),
+                ("critical", "high", false),
+                ("should", "medium", true),
+                ("recommended", "medium", false),
+                ("may", "low", false),
+                ("optional", "low", false),
+                ("could", "low", false),
+                ("won't", "low", false),
+                ("nice to have", "low", false),
+            ]
+            .iter()
+            .map(|(keyword, priority, negatable)| PriorityRule {
+                keyword: keyword.to_string(),
+                priority: priority.to_string(),
+                negatable: *negatable,
+            })
+            .collect(),
+            type_rules: vec![
+                TypeRule {
+                    req_type: "non_functional".to_string(),
+                    section_keywords: strings(&["non-functional", "performance", "security", "scalability"]),
+                    text_keywords: strings(&["performance", "latency", "availability"]),
+                    patterns: Vec::new(),
+                },
+                TypeRule {
+                    req_type: "constraint".to_string(),
+                    section_keywords: strings(&["constraint"]),
+                    text_keywords: strings(&["constraint", "limitation"]),
+                    patterns: Vec::new(),
+                },
+            ],
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+/// A spec, requirement or generated test matching a search.
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchHit {
+    /// "spec", "requirement" or "test"
+    pub entity_type: String,
+    pub entity_id: String,
+    pub spec_id: String,
+    pub spec_filename: String,
+    /// HTML-escaped, with matched terms wrapped in `<mark>`
+    pub title: String,
+    /// Excerpt around the matches, escaped and marked up like `title`
+    pub snippet: String,
+    /// Higher is a better match; only comparable within one search
+    pub score: f64,
+}
+
+/// Search hits grouped by entity type, best first.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SearchResults {
+    pub specs: Vec<SearchHit>,
+    pub requirements: Vec<SearchHit>,
+    pub tests: Vec<SearchHit>,
+}
@@ -8,20 +8,249 @@ pub struct Spec {
     pub content: String,
     pub parsed_at: Option<String>,
     pub created_at: String,
+    pub metadata: SpecMetadata,
+    /// Current revision; each re-upload of the same filename adds one.
+    pub revision: i64,
+}
+
+/// A glob, relative to the project's codebase, whose files are imported as specs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpecSource {
+    pub id: String,
+    pub project_id: String,
+    pub pattern: String,
+    pub created_at: String,
+}
+
+/// Outcome of importing a project's spec sources. Files that fail to parse
+/// are reported without stopping the rest of the import.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SpecImport {
+    /// Specs created or given a new revision
+    pub specs: Vec<Spec>,
+    pub unchanged: usize,
+    pub errors: Vec<SpecImportError>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SpecImportError {
+    pub filename: String,
+    pub error: String,
+}
+
+/// Payload of the `spec-changed` event, sent when a watched spec file is
+/// re-imported or fails to import.
+#[derive(Debug, Serialize, Clone)]
+pub struct SpecChanged {
+    pub project_id: String,
+    pub filename: String,
+    pub spec_id: Option<String>,
+    pub revision: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// A stored version of a spec's content.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpecRevision {
+    pub id: String,
+    pub spec_id: String,
+    pub revision: i64,
+    pub content: String,
+    pub created_at: String,
+}
+
+/// Front-matter metadata declared at the top of a spec file.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SpecMetadata {
+    pub owner: Option<String>,
+    pub version: Option<String>,
+    pub status: Option<String>,
+    pub component: Option<String>,
+    pub default_priority: Option<String>,
+    pub default_req_type: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct SpecFilter {
+    pub owner: Option<String>,
+    pub version: Option<String>,
+    pub status: Option<String>,
+    pub component: Option<String>,
+    pub default_priority: Option<String>,
+    pub default_req_type: Option<String>,
+    pub tag: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Requirement {
     pub id: String,
+    pub spec_id: String,
+    pub parent_id: Option<String>,
+    pub external_id: Option<String>,
+    pub section: String,
+    pub description: String,
+    pub req_type: String,
+    pub priority: String,
+    /// Wording that set the priority (e.g. "MUST NOT"); `None` when it came
+    /// from a priority column, front matter or the default.
+    pub priority_keyword: Option<String>,
+    pub change_status: String, // "new", "unchanged", "modified"
+    /// Review status: "draft", "approved", "deprecated" or "waived". Set by
+    /// people, never by parsing.
+    pub status: String,
+    /// Created, edited or deleted by hand; re-parsing leaves it alone.
+    pub manually_edited: bool,
+    pub retired_at: Option<String>,
+    pub scenario: Option<GherkinScenario>,
+    pub span: Option<SourceSpan>,
+    /// Measurable limit of a non-functional requirement, when it states one.
+    pub threshold: Option<Threshold>,
+    /// Labels from inline `#tag` tokens and those added by hand, sorted.
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRequirementRequest {
     pub spec_id: String,
+    pub section: String,
+    pub description: String,
+    /// Detected from the text when not given
+    pub req_type: Option<String>,
+    pub priority: Option<String>,
+    pub external_id: Option<String>,
+}
+
+/// Fields to change on a requirement; `None` leaves a field as it is.
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateRequirementRequest {
+    pub description: Option<String>,
+    pub section: Option<String>,
+    pub req_type: Option<String>,
+    pub priority: Option<String>,
+}
+
+/// The hand-editable fields of a requirement, as kept in its edit history.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RequirementFields {
     pub section: String,
     pub description: String,
     pub req_type: String,
     pub priority: String,
 }
 
+impl From<&Requirement> for RequirementFields {
+    fn from(req: &Requirement) -> Self {
+        RequirementFields {
+            section: req.section.clone(),
+            description: req.description.clone(),
+            req_type: req.req_type.clone(),
+            priority: req.priority.clone(),
+        }
+    }
+}
+
+/// One manual change to a requirement.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequirementEdit {
+    pub id: String,
+    pub requirement_id: String,
+    pub action: String, // "create", "edit", "delete"
+    /// `None` for a created requirement
+    pub before: Option<RequirementFields>,
+    /// `None` for a deleted requirement
+    pub after: Option<RequirementFields>,
+    pub edited_by: String,
+    pub edited_at: String,
+}
+
+/// One step in a requirement's status history.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequirementStatusChange {
+    pub id: String,
+    pub requirement_id: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub changed_at: String,
+}
+
+/// Where a requirement's text sits in its spec file: byte offsets (end
+/// exclusive) and 1-based line numbers (inclusive).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SourceSpan {
+    pub start_offset: i64,
+    pub end_offset: i64,
+    pub start_line: i64,
+    pub end_line: i64,
+}
+
+/// A numeric limit parsed from a requirement such as "respond within 200ms
+/// for 95% of API calls".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Threshold {
+    pub metric: String, // "duration", "throughput", "size", "percentage", "count"
+    pub operator: String, // "<", "<=", ">", ">=", "="
+    /// In the metric's base unit
+    pub value: f64,
+    pub unit: String, // "ms", "per_second", "bytes", "%", or the counted noun
+    /// e.g. 95 for "p95" or "95% of requests"
+    pub percentile: Option<f64>,
+    /// The words the threshold was read from
+    pub text: String,
+}
+
+/// Structured steps of a requirement parsed from a Gherkin `.feature` file.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct GherkinScenario {
+    pub keyword: String, // "Scenario", "Scenario Outline"
+    pub tags: Vec<String>,
+    pub steps: Vec<GherkinStep>,
+    pub examples: Vec<GherkinExamples>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct GherkinStep {
+    pub keyword: String, // "Given", "When", "Then", "And", "But", "*"
+    pub text: String,
+    pub doc_string: Option<String>,
+    pub table: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct GherkinExamples {
+    pub name: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct ParsedSpec {
     pub spec: Spec,
     pub requirements: Vec<Requirement>,
 }
+
+/// Requirement-level changes between two revisions of a spec.
+#[derive(Debug, Serialize, Clone)]
+pub struct SpecDiff {
+    pub spec_id: String,
+    pub from_revision: i64,
+    pub to_revision: i64,
+    #[serde(flatten)]
+    pub changes: RequirementChanges,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RequirementChanges {
+    pub added: Vec<Requirement>,
+    pub removed: Vec<Requirement>,
+    pub modified: Vec<RequirementChange>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RequirementChange {
+    pub before: Requirement,
+    pub after: Requirement,
+}
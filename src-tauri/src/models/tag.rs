@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A project-wide label for requirements, optionally mapped to the parts of
+/// the codebase that implement them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tag {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// Globs relative to the codebase path, e.g. `src/billing/**`
+    pub paths: Vec<String>,
+    /// Active requirements carrying the tag
+    pub requirement_count: i64,
+    pub created_at: String,
+}
@@ -9,6 +9,13 @@ pub struct GeneratedTest {
     pub generation_mode: String,
     pub file_path: Option<String>,
     pub created_at: String,
+    /// 1 when generated, one more for each repair of it
+    pub version: i64,
+    /// The attempt this one repairs
+    pub previous_version_id: Option<String>,
+    /// How self-heal ended, on its last attempt: "passed", "failed" (an
+    /// assertion failed) or "broken" (the test still couldn't run)
+    pub repair_outcome: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -17,6 +24,14 @@ pub struct GenerateTestsRequest {
     pub framework: String,
     pub mode: String,
     pub project_id: String,
+    /// Limits generation to requirements with this tag; with no
+    /// `requirement_ids`, every active requirement carrying it is used.
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Run each LLM test after generating it and have the model repair
+    /// tests that fail to run
+    #[serde(default)]
+    pub self_heal: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,3 +52,37 @@ pub struct TestProgress {
     pub current_test: String,
     pub status: String,
 }
+
+/// Emitted as `generation-progress` while a batch of tests is generated.
+#[derive(Debug, Serialize, Clone)]
+pub struct GenerationProgress {
+    pub total: usize,
+    pub completed: usize,
+    pub current_requirement: String,
+    /// "generating", "running", "repairing", "generated" or "failed" for a
+    /// requirement, then "completed" or "cancelled" for the batch
+    pub status: String,
+    /// Code received so far for the current requirement
+    pub partial_code: String,
+    /// The saved test, once the current requirement is "generated"
+    pub test: Option<GeneratedTest>,
+    /// Why the current requirement "failed"
+    pub error: Option<String>,
+}
+
+/// What a generation batch produced. Requirements that failed don't stop the
+/// others; a cancelled batch keeps the tests finished before it stopped.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct GenerateTestsResult {
+    pub tests: Vec<GeneratedTest>,
+    pub errors: Vec<GenerationError>,
+    /// Self-healed tests that are kept but couldn't be run or repaired
+    pub repair_errors: Vec<GenerationError>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GenerationError {
+    pub requirement_id: String,
+    pub error: String,
+}
@@ -1,100 +1,143 @@
+use std::collections::{HashMap, HashSet};
 use rusqlite::Connection;
 use uuid::Uuid;
 use chrono::Utc;
 use crate::db::queries;
 use crate::errors::AppError;
-use crate::models::report::{AlignmentReport, Mismatch, AlignmentReportWithMismatches};
+use crate::models::report::{AlignmentReport, Mismatch, AlignmentReportWithMismatches, ExcludedRequirement};
+use crate::models::spec::{Requirement, SpecFilter};
+use crate::services::{requirement_status, tags};
+
+/// Test state of a requirement: whether it counts as covered, and the
+/// mismatch to report for it, if any.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Coverage {
+    covered: bool,
+    mismatch: Option<&'static str>,
+}
 
-pub fn generate_report(conn: &Connection, project_id: &str) -> Result<AlignmentReportWithMismatches, AppError> {
-    let requirements = queries::get_requirements_for_project(conn, project_id)?;
-    let total = requirements.len() as i64;
+/// Coverage of a project's requirements, or only of those with `tag`.
+/// Mismatches point at the codebase paths mapped to the requirement's tags.
+pub fn generate_report(
+    conn: &Connection,
+    project_id: &str,
+    tag: Option<&str>,
+) -> Result<AlignmentReportWithMismatches, AppError> {
+    let project_tags = queries::list_tags(conn, project_id)?;
+    let mut requirements = queries::get_requirements_for_project(conn, project_id)?;
+    let tag = match tag {
+        Some(name) => {
+            let tag = tags::find_tag(conn, project_id, name)?;
+            requirements = tags::with_tag(requirements, &tag.name);
+            Some(tag.name)
+        }
+        None => None,
+    };
+    let filenames: HashMap<String, String> = queries::list_specs(conn, project_id, &SpecFilter::default())?
+        .into_iter()
+        .map(|spec| (spec.id, spec.filename))
+        .collect();
+    let in_scope: HashSet<String> = requirements.iter().map(|r| r.id.clone()).collect();
+    let (requirements, excluded) = split_excluded(requirements);
+    let excluded_count = top_level_count(&excluded, &in_scope);
+    let mut excluded_requirements = Vec::new();
+    for req in excluded.iter().filter(|r| requirement_status::is_excluded(&r.status)) {
+        let reason = queries::get_requirement_status_changes(conn, &req.id)?
+            .into_iter()
+            .rev()
+            .find(|c| c.to_status == req.status)
+            .and_then(|c| c.reason);
+        excluded_requirements.push(ExcludedRequirement {
+            requirement_id: req.id.clone(),
+            external_id: req.external_id.clone(),
+            spec_filename: filenames.get(&req.spec_id).cloned(),
+            spec_section: req.section.clone(),
+            description: req.description.clone(),
+            status: req.status.clone(),
+            reason,
+        });
+    }
 
-    if total == 0 {
+    if requirements.is_empty() {
         let report = AlignmentReport {
             id: Uuid::new_v4().to_string(),
             project_id: project_id.to_string(),
             coverage_percent: 0.0,
             total_requirements: 0,
             covered_requirements: 0,
+            excluded_requirements: excluded_count,
+            tag,
             generated_at: Utc::now().to_rfc3339(),
         };
-        queries::insert_alignment_report(conn, &report)?;
-        return Ok(AlignmentReportWithMismatches {
-            report,
-            mismatches: Vec::new(),
-        });
+        return save_report(conn, report, Vec::new(), excluded_requirements);
     }
 
     let report_id = Uuid::new_v4().to_string();
-    let mut covered = 0i64;
-    let mut mismatches = Vec::new();
+    let mut own = HashMap::new();
+    for req in &requirements {
+        own.insert(req.id.as_str(), requirement_coverage(conn, &req.id)?);
+    }
 
+    // Acceptance criteria roll up into their parent requirement
+    let active: HashSet<&str> = requirements.iter().map(|r| r.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
     for req in &requirements {
-        let tests = queries::get_generated_tests_for_requirement(conn, &req.id)?;
-
-        if tests.is_empty() {
-            mismatches.push(Mismatch {
-                id: Uuid::new_v4().to_string(),
-                report_id: report_id.clone(),
-                requirement_id: req.id.clone(),
-                spec_section: req.section.clone(),
-                code_element: None,
-                mismatch_type: "no_test_generated".to_string(),
-                details: format!("No test has been generated for: {}", req.description),
-            });
-            continue;
+        if let Some(parent) = req.parent_id.as_deref().filter(|p| active.contains(p)) {
+            children.entry(parent).or_default().push(req.id.as_str());
         }
+    }
+
+    let mut effective = HashMap::new();
+    for req in &requirements {
+        effective_coverage(&req.id, &own, &children, &mut effective, &mut HashSet::new());
+    }
 
-        // Check if any test has been executed and passed
-        let mut has_passing = false;
-        let mut has_failing = false;
-        let mut has_no_results = true;
-
-        for test in &tests {
-            if let Some(result) = queries::get_latest_test_result_for_test(conn, &test.id)? {
-                has_no_results = false;
-                match result.status.as_str() {
-                    "passed" => has_passing = true,
-                    "failed" | "error" => has_failing = true,
-                    _ => {}
-                }
+    let mut total = 0i64;
+    let mut covered = 0i64;
+    let mut mismatches = Vec::new();
+
+    for req in &requirements {
+        let coverage = effective[req.id.as_str()];
+        let is_top_level = !req.parent_id.as_deref().is_some_and(|p| active.contains(p));
+        if is_top_level {
+            total += 1;
+            if coverage.covered {
+                covered += 1;
             }
         }
 
-        if has_no_results {
-            mismatches.push(Mismatch {
-                id: Uuid::new_v4().to_string(),
-                report_id: report_id.clone(),
-                requirement_id: req.id.clone(),
-                spec_section: req.section.clone(),
-                code_element: None,
-                mismatch_type: "not_implemented".to_string(),
-                details: format!("Tests generated but never executed for: {}", req.description),
-            });
-        } else if has_passing && !has_failing {
-            covered += 1;
-        } else if has_passing && has_failing {
-            covered += 1;
-            mismatches.push(Mismatch {
-                id: Uuid::new_v4().to_string(),
-                report_id: report_id.clone(),
-                requirement_id: req.id.clone(),
-                spec_section: req.section.clone(),
-                code_element: None,
-                mismatch_type: "partial_coverage".to_string(),
-                details: format!("Some tests passing, some failing for: {}", req.description),
-            });
-        } else if has_failing {
-            mismatches.push(Mismatch {
-                id: Uuid::new_v4().to_string(),
-                report_id: report_id.clone(),
-                requirement_id: req.id.clone(),
-                spec_section: req.section.clone(),
-                code_element: None,
-                mismatch_type: "test_failing".to_string(),
-                details: format!("Test(s) failing for: {}", req.description),
-            });
-        }
+        let Some(mismatch_type) = coverage.mismatch else {
+            continue;
+        };
+        let details = match children.get(req.id.as_str()) {
+            Some(kids) if own[req.id.as_str()] != coverage => {
+                let kids_covered = kids.iter().filter(|k| effective[*k].covered).count();
+                format!(
+                    "{} of {} acceptance criteria covered for: {}",
+                    kids_covered,
+                    kids.len(),
+                    req.description
+                )
+            }
+            _ => match mismatch_type {
+                "no_test_generated" => format!("No test has been generated for: {}", req.description),
+                "not_implemented" => format!("Tests generated but never executed for: {}", req.description),
+                "partial_coverage" => format!("Some tests passing, some failing for: {}", req.description),
+                _ => format!("Test(s) failing for: {}", req.description),
+            },
+        };
+        mismatches.push(Mismatch {
+            id: Uuid::new_v4().to_string(),
+            report_id: report_id.clone(),
+            requirement_id: req.id.clone(),
+            external_id: req.external_id.clone(),
+            spec_filename: filenames.get(&req.spec_id).cloned(),
+            span: req.span.clone(),
+            spec_section: req.section.clone(),
+            code_element: Some(tags::component_paths(req, &project_tags).join(", ")).filter(|p| !p.is_empty()),
+            mismatch_type: mismatch_type.to_string(),
+            details,
+        });
     }
 
     let coverage_percent = if total > 0 {
@@ -109,17 +152,155 @@ pub fn generate_report(conn: &Connection, project_id: &str) -> Result<AlignmentR
         coverage_percent,
         total_requirements: total,
         covered_requirements: covered,
+        excluded_requirements: excluded_count,
+        tag,
         generated_at: Utc::now().to_rfc3339(),
     };
+    save_report(conn, report, mismatches, excluded_requirements)
+}
 
+fn save_report(
+    conn: &Connection,
+    report: AlignmentReport,
+    mismatches: Vec<Mismatch>,
+    excluded: Vec<ExcludedRequirement>,
+) -> Result<AlignmentReportWithMismatches, AppError> {
     let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
     queries::insert_alignment_report(&tx, &report)?;
     for mismatch in &mismatches {
         queries::insert_mismatch(&tx, mismatch)?;
     }
+    for requirement in &excluded {
+        queries::insert_report_exclusion(&tx, &report.id, requirement)?;
+    }
     tx.commit().map_err(AppError::Database)?;
 
-    Ok(AlignmentReportWithMismatches { report, mismatches })
+    Ok(AlignmentReportWithMismatches { report, mismatches, excluded })
+}
+
+/// Requirements whose parent isn't among `in_scope`, the way the report's
+/// total counts them.
+fn top_level_count(requirements: &[Requirement], in_scope: &HashSet<String>) -> i64 {
+    requirements
+        .iter()
+        .filter(|r| !r.parent_id.as_ref().is_some_and(|p| in_scope.contains(p)))
+        .count() as i64
+}
+
+/// Separates requirements that count toward coverage from waived or
+/// deprecated ones, which take their acceptance criteria with them.
+fn split_excluded(requirements: Vec<Requirement>) -> (Vec<Requirement>, Vec<Requirement>) {
+    let parents: HashMap<&str, &str> = requirements
+        .iter()
+        .filter_map(|r| Some((r.id.as_str(), r.parent_id.as_deref()?)))
+        .collect();
+    let statuses: HashMap<&str, &str> = requirements.iter().map(|r| (r.id.as_str(), r.status.as_str())).collect();
+
+    let mut out_of_scope = HashSet::new();
+    for req in &requirements {
+        let mut seen = HashSet::new();
+        let mut current = Some(req.id.as_str());
+        while let Some(id) = current.filter(|id| seen.insert(*id)) {
+            if statuses.get(id).is_some_and(|s| requirement_status::is_excluded(s)) {
+                out_of_scope.insert(req.id.clone());
+                break;
+            }
+            current = parents.get(id).copied();
+        }
+    }
+    requirements.into_iter().partition(|r| !out_of_scope.contains(&r.id))
+}
+
+/// Coverage from the requirement's own generated tests and their latest results.
+fn requirement_coverage(conn: &Connection, requirement_id: &str) -> Result<Coverage, AppError> {
+    let tests = queries::get_generated_tests_for_requirement(conn, requirement_id)?;
+
+    // Check if any test has been executed and passed
+    let mut has_passing = false;
+    let mut has_failing = false;
+    let mut has_results = false;
+
+    for test in &tests {
+        if let Some(result) = queries::get_latest_test_result_for_test(conn, &test.id)? {
+            has_results = true;
+            match result.status.as_str() {
+                "passed" => has_passing = true,
+                "failed" | "error" => has_failing = true,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(own_coverage(!tests.is_empty(), has_results, has_passing, has_failing))
+}
+
+fn own_coverage(has_tests: bool, has_results: bool, has_passing: bool, has_failing: bool) -> Coverage {
+    let (covered, mismatch) = if !has_tests {
+        (false, Some("no_test_generated"))
+    } else if !has_results {
+        (false, Some("not_implemented"))
+    } else if has_passing && has_failing {
+        (true, Some("partial_coverage"))
+    } else if has_passing {
+        (true, None)
+    } else if has_failing {
+        (false, Some("test_failing"))
+    } else {
+        // Only skipped results
+        (false, None)
+    };
+    Coverage { covered, mismatch }
+}
+
+/// Combines the coverage of a requirement's parts: covered only when every
+/// part is, partially covered when some are, otherwise the worst outcome.
+fn roll_up(parts: &[Coverage]) -> Coverage {
+    let covered = parts.iter().all(|p| p.covered);
+    let mismatch = if parts.iter().all(|p| p.mismatch.is_none()) {
+        None
+    } else if parts.iter().any(|p| p.covered) {
+        Some("partial_coverage")
+    } else {
+        ["test_failing", "not_implemented", "no_test_generated"]
+            .into_iter()
+            .find(|m| parts.iter().any(|p| p.mismatch == Some(*m)))
+    };
+    Coverage { covered, mismatch }
+}
+
+/// A requirement's own coverage combined with that of its acceptance
+/// criteria. Its own tests only count when it has any.
+fn effective_coverage<'a>(
+    id: &'a str,
+    own: &HashMap<&'a str, Coverage>,
+    children: &HashMap<&'a str, Vec<&'a str>>,
+    memo: &mut HashMap<&'a str, Coverage>,
+    visiting: &mut HashSet<&'a str>,
+) -> Coverage {
+    if let Some(coverage) = memo.get(id) {
+        return *coverage;
+    }
+    let own_state = own[id];
+    let kids = match children.get(id) {
+        Some(kids) if visiting.insert(id) => kids,
+        Some(_) => return own_state,
+        None => {
+            memo.insert(id, own_state);
+            return own_state;
+        }
+    };
+
+    let mut parts: Vec<Coverage> = kids
+        .iter()
+        .map(|kid| effective_coverage(kid, own, children, memo, visiting))
+        .collect();
+    if own_state.mismatch != Some("no_test_generated") {
+        parts.push(own_state);
+    }
+    let coverage = roll_up(&parts);
+    visiting.remove(id);
+    memo.insert(id, coverage);
+    coverage
 }
 
 #[cfg(test)]
@@ -276,4 +457,116 @@ mod tests {
         let coverage = calculate_coverage_percent(3, 1);
         assert!((coverage - 33.333).abs() < 0.01);
     }
+
+    const PASSING: Coverage = Coverage { covered: true, mismatch: None };
+    const FAILING: Coverage = Coverage { covered: false, mismatch: Some("test_failing") };
+    const UNTESTED: Coverage = Coverage { covered: false, mismatch: Some("no_test_generated") };
+
+    #[test]
+    fn test_own_coverage_matches_classification() {
+        assert_eq!(own_coverage(false, false, false, false), UNTESTED);
+        assert_eq!(own_coverage(true, true, true, false), PASSING);
+        assert_eq!(own_coverage(true, true, false, true), FAILING);
+        assert!(own_coverage(true, true, true, true).covered);
+    }
+
+    fn requirement(id: &str, parent: Option<&str>, status: &str) -> Requirement {
+        Requirement {
+            id: id.to_string(),
+            spec_id: "spec-1".to_string(),
+            parent_id: parent.map(str::to_string),
+            external_id: None,
+            section: "Requirements".to_string(),
+            description: id.to_string(),
+            req_type: "functional".to_string(),
+            priority: "medium".to_string(),
+            priority_keyword: None,
+            change_status: "new".to_string(),
+            status: status.to_string(),
+            manually_edited: false,
+            retired_at: None,
+            scenario: None,
+            span: None,
+            threshold: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_waived_requirements_take_their_criteria_out_of_scope() {
+        let requirements = vec![
+            requirement("a", None, "approved"),
+            requirement("b", None, "waived"),
+            requirement("b1", Some("b"), "draft"),
+            requirement("c", None, "draft"),
+            requirement("c1", Some("c"), "deprecated"),
+        ];
+        let in_scope: HashSet<String> = requirements.iter().map(|r| r.id.clone()).collect();
+        let (counted, excluded) = split_excluded(requirements);
+        let ids = |reqs: &[Requirement]| reqs.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&counted), vec!["a", "c"]);
+        assert_eq!(ids(&excluded), vec!["b", "b1", "c1"]);
+        // Only "b" counts against the total; "c1" is one of "c"'s criteria
+        assert_eq!(top_level_count(&counted, &in_scope), 2);
+        assert_eq!(top_level_count(&excluded, &in_scope), 1);
+    }
+
+    #[test]
+    fn test_roll_up_all_criteria_covered() {
+        assert_eq!(roll_up(&[PASSING, PASSING]), PASSING);
+    }
+
+    #[test]
+    fn test_roll_up_some_criteria_covered() {
+        let coverage = roll_up(&[PASSING, UNTESTED]);
+        assert!(!coverage.covered);
+        assert_eq!(coverage.mismatch, Some("partial_coverage"));
+    }
+
+    #[test]
+    fn test_roll_up_reports_worst_uncovered_criterion() {
+        assert_eq!(roll_up(&[UNTESTED, FAILING]), FAILING);
+        assert_eq!(roll_up(&[UNTESTED, UNTESTED]), UNTESTED);
+    }
+
+    #[test]
+    fn test_parent_without_tests_takes_children_coverage() {
+        let own = HashMap::from([("parent", UNTESTED), ("a", PASSING), ("b", PASSING)]);
+        let children = HashMap::from([("parent", vec!["a", "b"])]);
+        let coverage = effective_coverage("parent", &own, &children, &mut HashMap::new(), &mut HashSet::new());
+        assert_eq!(coverage, PASSING);
+    }
+
+    #[test]
+    fn test_failing_parent_tests_block_coverage() {
+        let own = HashMap::from([("parent", FAILING), ("a", PASSING)]);
+        let children = HashMap::from([("parent", vec!["a"])]);
+        let coverage = effective_coverage("parent", &own, &children, &mut HashMap::new(), &mut HashSet::new());
+        assert!(!coverage.covered);
+        assert_eq!(coverage.mismatch, Some("partial_coverage"));
+    }
+
+    #[test]
+    fn test_report_on_requirement_without_criteria() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::run_migrations(&conn).unwrap();
+        let project = queries::create_project(
+            &conn,
+            &crate::models::project::CreateProjectRequest {
+                name: "project".to_string(),
+                codebase_path: "/tmp".to_string(),
+            },
+        )
+        .unwrap();
+        let spec = queries::create_spec(&conn, &project.id, "spec.md", "", &Default::default()).unwrap();
+        let req = Requirement { spec_id: spec.id, ..requirement("req-1", None, "approved") };
+        queries::insert_requirements(&conn, &[req]).unwrap();
+
+        let report = generate_report(&conn, &project.id, None).unwrap();
+        assert_eq!(report.report.total_requirements, 1);
+        assert_eq!(report.report.covered_requirements, 0);
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].mismatch_type, "no_test_generated");
+        assert_eq!(report.mismatches[0].details, "No test has been generated for: req-1");
+    }
 }
@@ -0,0 +1,274 @@
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::text_blocks::{collect_requirements, split_lines, strip_inline_markup, PendingBlock, TextBlock};
+
+/// Delimiters of blocks whose content is not prose (listings, literals,
+/// comments, passthroughs); everything up to the closing line is skipped.
+const VERBATIM_DELIMITERS: &[char] = &['-', '.', '/', '+'];
+
+/// Delimiters of compound blocks (examples, sidebars, quotes, open blocks),
+/// whose content is read like any other.
+const COMPOUND_DELIMITERS: &[char] = &['=', '*', '_'];
+
+/// Parses an AsciiDoc spec. `==` (or Markdown-style `#`) titles set the
+/// section, `*`, `-` and `.` lists nest by marker, and listing, literal,
+/// comment and table blocks, attribute entries and block titles are skipped.
+pub fn parse_asciidoc(spec_id: &str, content: &str, rules: &ParserRules) -> Vec<Requirement> {
+    collect_requirements(spec_id, content, rules, asciidoc_blocks(content))
+}
+
+fn asciidoc_blocks(content: &str) -> Vec<TextBlock> {
+    let mut blocks = Vec::new();
+    let mut pending: Option<PendingBlock> = None;
+    // Markers of the open lists (`*`, `**`, `.`, `-`, `1.`), outermost first
+    let mut list_markers: Vec<String> = Vec::new();
+    // Closing line of the verbatim block being skipped
+    let mut skip_until: Option<&str> = None;
+    let mut in_literal_paragraph = false;
+
+    for line in split_lines(content) {
+        let trimmed = line.text.trim();
+        if let Some(close) = skip_until {
+            if trimmed == close {
+                skip_until = None;
+            }
+            continue;
+        }
+        if line.is_blank() {
+            in_literal_paragraph = false;
+            flush(&mut pending, &mut blocks);
+            continue;
+        }
+        if in_literal_paragraph {
+            continue;
+        }
+        if let Some(close) = verbatim_delimiter(trimmed) {
+            flush(&mut pending, &mut blocks);
+            skip_until = Some(close);
+            continue;
+        }
+        // `+` attaches the next line to the list item; compound delimiters just wrap content
+        if trimmed == "+" || trimmed == "--" || is_delimiter(trimmed, COMPOUND_DELIMITERS) {
+            continue;
+        }
+        if trimmed.starts_with("//") || is_attribute_entry(trimmed) || is_block_title(trimmed) {
+            continue;
+        }
+        if trimmed.starts_with('[') && trimmed.ends_with(']') && !trimmed.starts_with("[[") {
+            // Block attributes such as `[source,java]` or `[NOTE]`
+            continue;
+        }
+        if let Some(title) = section_title(trimmed) {
+            flush(&mut pending, &mut blocks);
+            list_markers.clear();
+            blocks.push(TextBlock::Heading(strip_inline_markup(title)));
+            continue;
+        }
+        if let Some((marker, text)) = split_list_marker(trimmed) {
+            flush(&mut pending, &mut blocks);
+            let depth = match list_markers.iter().position(|m| *m == marker) {
+                Some(depth) => {
+                    list_markers.truncate(depth + 1);
+                    depth
+                }
+                None => {
+                    list_markers.push(marker);
+                    list_markers.len() - 1
+                }
+            };
+            pending = Some(PendingBlock::item(depth, line, &anchor_to_id(text)));
+            continue;
+        }
+        match pending.as_mut() {
+            Some(p) => p.push_line(line),
+            // An indented paragraph is literal text
+            None if line.indent() > 0 => in_literal_paragraph = true,
+            None => {
+                list_markers.clear();
+                let mut block = PendingBlock::paragraph(line);
+                block.lines[0] = anchor_to_id(trimmed);
+                pending = Some(block);
+            }
+        }
+    }
+    flush(&mut pending, &mut blocks);
+
+    blocks
+}
+
+fn flush(pending: &mut Option<PendingBlock>, blocks: &mut Vec<TextBlock>) {
+    if let Some(block) = pending.take() {
+        blocks.push(block.into_block());
+    }
+}
+
+/// Title of a `== Section` (or `## Section`) line, without closing markers.
+fn section_title(text: &str) -> Option<&str> {
+    let marker = text.chars().next().filter(|c| *c == '=' || *c == '#')?;
+    let level = text.chars().take_while(|c| *c == marker).count();
+    let rest = &text[level..];
+    if level > 6 || !rest.starts_with(' ') {
+        return None;
+    }
+    let title = rest.trim();
+    // Symmetric titles: `== Section ==`
+    let stripped = title.trim_end_matches(marker);
+    let title = if stripped.ends_with(' ') { stripped.trim_end() } else { title };
+    (!title.is_empty()).then_some(title)
+}
+
+/// List marker and item text: `*`..`*****`, `-`, `.`..`.....` or `1.`.
+/// Checklist boxes (`[x]`) are dropped.
+fn split_list_marker(text: &str) -> Option<(String, &str)> {
+    let first = text.chars().next()?;
+    let marker_len = match first {
+        '*' | '.' => text.chars().take_while(|c| *c == first).count(),
+        '-' => 1,
+        c if c.is_ascii_digit() => {
+            let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+            if text[digits..].starts_with('.') { digits + 1 } else { return None }
+        }
+        _ => return None,
+    };
+    let rest = &text[marker_len..];
+    if marker_len > 5 || !rest.starts_with(' ') {
+        return None;
+    }
+    let marker = if first.is_ascii_digit() { "1.".to_string() } else { text[..marker_len].to_string() };
+    let item = rest.trim_start();
+    let item = ["[ ] ", "[x] ", "[X] ", "[*] "]
+        .iter()
+        .find_map(|box_| item.strip_prefix(box_))
+        .unwrap_or(item);
+    Some((marker, item))
+}
+
+/// `[[REQ-1]] text` -> `[REQ-1] text`, so an anchor at the start of a
+/// requirement is read as its external ID.
+fn anchor_to_id(text: &str) -> String {
+    if let Some(rest) = text.strip_prefix("[[") {
+        if let Some(end) = rest.find("]]") {
+            let id = rest[..end].split(',').next().unwrap_or("").trim();
+            return format!("[{}] {}", id, rest[end + 2..].trim_start());
+        }
+    }
+    text.to_string()
+}
+
+/// Closing line for a verbatim block opened by `text`, if it opens one.
+fn verbatim_delimiter(text: &str) -> Option<&str> {
+    if text.starts_with("```") {
+        return Some("```");
+    }
+    if text.len() >= 4 && text.starts_with(['|', ',', ':', '!']) && text[1..].starts_with("===") {
+        return Some(text);
+    }
+    is_delimiter(text, VERBATIM_DELIMITERS).then_some(text)
+}
+
+/// Four or more of one delimiter character, e.g. `----` or `====`.
+fn is_delimiter(text: &str, chars: &[char]) -> bool {
+    let Some(first) = text.chars().next() else {
+        return false;
+    };
+    text.len() >= 4 && chars.contains(&first) && text.chars().all(|c| c == first)
+}
+
+/// `:name: value` or `:!name:` document attribute entries.
+fn is_attribute_entry(text: &str) -> bool {
+    let Some(rest) = text.strip_prefix(':') else {
+        return false;
+    };
+    let Some(end) = rest.find(':') else {
+        return false;
+    };
+    let name = rest[..end].trim_matches('!');
+    !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        && !rest[end + 1..].starts_with(|c: char| !c.is_whitespace())
+}
+
+/// `.Title` lines naming the block below them.
+fn is_block_title(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next() == Some('.') && chars.next().is_some_and(|c| c != '.' && !c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<Requirement> {
+        parse_asciidoc("spec-1", content, ParserRules::builtin())
+    }
+
+    #[test]
+    fn test_parse_asciidoc_sections_and_lists() {
+        let content = r#"= Billing System
+:status: draft
+:toc:
+
+== Functional Requirements
+
+* The system shall generate invoices
+every month
+* Users can download invoices as *PDF*
+** PDFs include the company logo
+** PDFs are A4
++
+Page size follows the customer's locale.
+
+.Sample invoice
+----
+* The system shall not be read from a listing
+----
+
+== Background
+
+. Legacy billing ran on a mainframe
+
+== Notes
+
+// The system shall ignore comments
+The system must retain invoices for seven years.
+
+[[BIL-9]] Refunds are issued within 30 days.
+"#;
+        let reqs = parse(content);
+        let descriptions: Vec<&str> = reqs.iter().map(|r| r.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "The system shall generate invoices every month",
+                "Users can download invoices as PDF",
+                "PDFs include the company logo",
+                "PDFs are A4 Page size follows the customer's locale.",
+                "The system must retain invoices for seven years.",
+                "Refunds are issued within 30 days.",
+            ]
+        );
+        assert_eq!(reqs[0].section, "Functional Requirements");
+        assert_eq!(reqs[2].parent_id.as_deref(), Some(reqs[1].id.as_str()));
+        assert_eq!(reqs[3].parent_id.as_deref(), Some(reqs[1].id.as_str()));
+        assert_eq!(reqs[4].section, "Notes");
+        assert_eq!(reqs[5].external_id.as_deref(), Some("BIL-9"));
+    }
+
+    #[test]
+    fn test_asciidoc_spans() {
+        let content = "== Requirements\n\n* Invoices are\ngenerated monthly\n";
+        let reqs = parse(content);
+        assert_eq!(reqs.len(), 1);
+        let span = reqs[0].span.as_ref().unwrap();
+        assert_eq!(&content[span.start_offset as usize..span.end_offset as usize], "* Invoices are\ngenerated monthly");
+        assert_eq!((span.start_line, span.end_line), (3, 4));
+    }
+
+    #[test]
+    fn test_section_title() {
+        assert_eq!(section_title("== Scope"), Some("Scope"));
+        assert_eq!(section_title("=== Scope ==="), Some("Scope"));
+        assert_eq!(section_title("==== "), None);
+        assert_eq!(section_title("==not a title"), None);
+    }
+}
@@ -1,16 +1,19 @@
 use std::path::Path;
 use crate::errors::AppError;
+use crate::services::test_runner;
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CodeSymbol {
     pub name: String,
     pub kind: String, // "function", "class", "method"
     pub file_path: String,
+    /// 1-based line of the declaration
+    pub line: usize,
 }
 
-const IGNORE_DIRS: &[&str] = &[
+pub const IGNORE_DIRS: &[&str] = &[
     "node_modules", ".git", "dist", "build", "target", ".next",
-    "__pycache__", ".venv", "venv", ".tox", "coverage", ".nyc_output",
+    "__pycache__", ".venv", "venv", ".tox", "coverage", ".nyc_output", test_runner::SCRATCH_DIR,
 ];
 
 const SOURCE_EXTENSIONS: &[&str] = &[
@@ -18,24 +21,63 @@ const SOURCE_EXTENSIONS: &[&str] = &[
 ];
 
 const MAX_DEPTH: usize = 12;
+pub const MAX_FILE_SIZE: u64 = 1_024_000;
 
 pub fn scan_codebase(root: &str, exclusions: &[String]) -> Result<Vec<CodeSymbol>, AppError> {
+    let root_path = valid_root(root)?;
+    let mut symbols = Vec::new();
+    walk_dir(root_path, root_path, exclusions, 0, &mut |path, rel_path, ext| {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            extract_symbols(&content, rel_path, ext, &mut symbols);
+        }
+    })?;
+    Ok(symbols)
+}
+
+/// Relative paths of the codebase's existing Jest or pytest test files.
+pub fn find_test_files(root: &str, exclusions: &[String], framework: &str) -> Result<Vec<String>, AppError> {
+    let root_path = valid_root(root)?;
+    let mut files = Vec::new();
+    walk_dir(root_path, root_path, exclusions, 0, &mut |_, rel_path, _| {
+        if is_test_file(rel_path, framework) {
+            files.push(rel_path.to_string());
+        }
+    })?;
+    files.sort();
+    Ok(files)
+}
+
+fn valid_root(root: &str) -> Result<&Path, AppError> {
     let root_path = Path::new(root);
     if !root_path.exists() || !root_path.is_dir() {
         return Err(AppError::InvalidInput(format!("Invalid codebase path: {}", root)));
     }
+    Ok(root_path)
+}
 
-    let mut symbols = Vec::new();
-    walk_dir(root_path, root_path, &mut symbols, exclusions, 0)?;
-    Ok(symbols)
+/// Jest picks up `*.test.*`, `*.spec.*` and anything under `__tests__`;
+/// pytest collects `test_*.py` and `*_test.py`.
+fn is_test_file(rel_path: &str, framework: &str) -> bool {
+    let path = Path::new(rel_path);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_lowercase();
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    match framework {
+        "pytest" => ext == "py" && (name.starts_with("test_") || name.ends_with("_test.py")),
+        _ => {
+            matches!(ext, "js" | "jsx" | "ts" | "tsx")
+                && (name.contains(".test.") || name.contains(".spec.") || path.components().any(|c| c.as_os_str() == "__tests__"))
+        }
+    }
 }
 
+/// Calls `visit` with the path, root-relative path and extension of every
+/// source file under `dir`.
 fn walk_dir(
     dir: &Path,
     root: &Path,
-    symbols: &mut Vec<CodeSymbol>,
     exclusions: &[String],
     depth: usize,
+    visit: &mut dyn FnMut(&Path, &str, &str),
 ) -> Result<(), AppError> {
     if depth > MAX_DEPTH {
         return Ok(());
@@ -51,20 +93,18 @@ fn walk_dir(
         }
 
         if path.is_dir() {
-            walk_dir(&path, root, symbols, exclusions, depth + 1)?;
+            walk_dir(&path, root, exclusions, depth + 1, visit)?;
         } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
             if SOURCE_EXTENSIONS.contains(&ext) {
                 // Skip files larger than 1 MB to avoid reading generated/bundled files
                 let too_large = std::fs::metadata(&path)
-                    .map(|m| m.len() > 1_024_000)
+                    .map(|m| m.len() > MAX_FILE_SIZE)
                     .unwrap_or(false);
                 if too_large {
                     continue;
                 }
-                if let Ok(content) = std::fs::read_to_string(&path) {
-                    let rel_path = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
-                    extract_symbols(&content, &rel_path, ext, symbols);
-                }
+                let rel_path = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+                visit(&path, &rel_path, ext);
             }
         }
     }
@@ -85,15 +125,15 @@ fn extract_symbols(content: &str, file_path: &str, ext: &str, symbols: &mut Vec<
 }
 
 fn extract_js_ts_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         // function declarations
         if let Some(name) = extract_after_keyword(trimmed, "function ") {
-            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into(), line: index + 1 });
         }
         // class declarations
         if let Some(name) = extract_after_keyword(trimmed, "class ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         // const arrow functions: const foo = (...) =>
         if (trimmed.starts_with("export const ") || trimmed.starts_with("const "))
@@ -107,7 +147,7 @@ fn extract_js_ts_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeS
             if let Some(pos) = after_const.find(|c: char| !c.is_alphanumeric() && c != '_') {
                 let name = after_const[..pos].to_string();
                 if !name.is_empty() {
-                    symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into() });
+                    symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into(), line: index + 1 });
                 }
             }
         }
@@ -115,7 +155,7 @@ fn extract_js_ts_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeS
 }
 
 fn extract_python_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "def ") {
             let kind = if line.starts_with("    ") || line.starts_with('\t') {
@@ -123,65 +163,65 @@ fn extract_python_symbols(content: &str, file_path: &str, symbols: &mut Vec<Code
             } else {
                 "function"
             };
-            symbols.push(CodeSymbol { name, kind: kind.into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: kind.into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "class ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
     }
 }
 
 fn extract_rust_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "fn ") {
-            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "struct ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "impl ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
     }
 }
 
 fn extract_go_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "func ") {
-            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "function".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "type ") {
             if trimmed.contains(" struct") || trimmed.contains(" interface") {
-                symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+                symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
             }
         }
     }
 }
 
 fn extract_java_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "class ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "interface ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if looks_like_java_method(trimmed) {
             if let Some(name) = extract_method_name_before_paren(trimmed) {
-                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into() });
+                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into(), line: index + 1 });
             }
         }
     }
 }
 
 fn extract_ruby_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "class ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(rest) = trimmed.strip_prefix("def ") {
             let method = rest.trim_start();
@@ -189,24 +229,24 @@ fn extract_ruby_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSy
             let name_part = name_part.split('(').next().unwrap_or("");
             let name = name_part.rsplit('.').next().unwrap_or("").to_string();
             if !name.is_empty() {
-                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into() });
+                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into(), line: index + 1 });
             }
         }
     }
 }
 
 fn extract_csharp_symbols(content: &str, file_path: &str, symbols: &mut Vec<CodeSymbol>) {
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if let Some(name) = extract_after_keyword(trimmed, "class ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if let Some(name) = extract_after_keyword(trimmed, "interface ") {
-            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into() });
+            symbols.push(CodeSymbol { name, kind: "class".into(), file_path: file_path.into(), line: index + 1 });
         }
         if looks_like_csharp_method(trimmed) {
             if let Some(name) = extract_method_name_before_paren(trimmed) {
-                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into() });
+                symbols.push(CodeSymbol { name, kind: "method".into(), file_path: file_path.into(), line: index + 1 });
             }
         }
     }
@@ -268,6 +308,18 @@ fn extract_after_keyword(line: &str, keyword: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file("src/auth/login.test.ts", "jest"));
+        assert!(is_test_file("src/__tests__/login.js", "jest"));
+        assert!(is_test_file("web/Login.spec.tsx", "jest"));
+        assert!(!is_test_file("src/auth/login.ts", "jest"));
+        assert!(!is_test_file("tests/test_login.py", "jest"));
+        assert!(is_test_file("tests/test_login.py", "pytest"));
+        assert!(is_test_file("app/login_test.py", "pytest"));
+        assert!(!is_test_file("app/testing.py", "pytest"));
+    }
+
     // ============ JavaScript/TypeScript Tests ============
     #[test]
     fn test_extract_js_function() {
@@ -0,0 +1,76 @@
+use crate::errors::AppError;
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::spec_parser::{push_table_row, LineIndex, RequirementCollector, TableColumns};
+
+/// Parses a CSV requirements export: one requirement per row, with columns
+/// recognised from the header the same way as in a Markdown requirements
+/// table (id, section, text/description, type, priority).
+pub fn parse_csv(spec_id: &str, content: &str, rules: &ParserRules) -> Result<Vec<Requirement>, AppError> {
+    // Spreadsheet exports often start with a byte order mark
+    let body = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let lines = LineIndex::new(content, content.len() - body.len());
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let header: Vec<String> = reader.headers().map_err(csv_error)?.iter().map(str::to_string).collect();
+    let columns = TableColumns::from_header(&header);
+    if columns.description.is_none() {
+        return Err(AppError::InvalidInput(
+            "CSV has no requirement text column (expected e.g. 'text', 'description' or 'requirement')".into(),
+        ));
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let start = record.position().map_or(0, |p| p.byte() as usize);
+        rows.push((start, record.iter().map(str::to_string).collect::<Vec<_>>()));
+    }
+
+    let mut collector = RequirementCollector::new(spec_id).with_rules(rules);
+    for (i, (start, cells)) in rows.iter().enumerate() {
+        let end = rows.get(i + 1).map_or(body.len(), |(next, _)| *next);
+        let end = *start + body[*start..end].trim_end().len();
+        if let Some(req) = push_table_row(&mut collector, "General", &columns, cells) {
+            req.span = Some(lines.span(*start..end));
+        }
+    }
+    Ok(collector.finish())
+}
+
+fn csv_error(e: csv::Error) -> AppError {
+    AppError::InvalidInput(format!("Invalid CSV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_export() {
+        let content = "\u{feff}ID,Section,Text,Type,Priority\nBIL-1,Billing,Invoices are generated monthly,Functional,Must\nBIL-2,Billing,\"Invoice PDFs render in under 2s, even on mobile\",Non-functional,Could\n,,,,\nBIL-3,Refunds,Refunds can be issued within 30 days\n";
+        let reqs = parse_csv("spec-1", content, ParserRules::builtin()).unwrap();
+        assert_eq!(reqs.len(), 3);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("BIL-1"));
+        assert_eq!(reqs[0].section, "Billing");
+        assert_eq!(reqs[0].priority, "high");
+        assert_eq!(reqs[1].description, "Invoice PDFs render in under 2s, even on mobile");
+        assert_eq!(reqs[1].req_type, "non_functional");
+        assert_eq!(reqs[1].priority, "low");
+        // Short rows are allowed; missing cells fall back to detection
+        assert_eq!(reqs[2].section, "Refunds");
+        assert_eq!(reqs[2].req_type, "functional");
+        let span = reqs[1].span.as_ref().unwrap();
+        assert_eq!((span.start_line, span.end_line), (3, 3));
+        assert!(content[span.start_offset as usize..span.end_offset as usize].starts_with("BIL-2,"));
+    }
+
+    #[test]
+    fn test_csv_without_text_column_is_rejected() {
+        let err = parse_csv("spec-1", "ID,Owner\n1,alice\n", ParserRules::builtin()).unwrap_err();
+        assert!(matches!(err, AppError::InvalidInput(_)));
+    }
+}
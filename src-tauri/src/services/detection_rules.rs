@@ -0,0 +1,407 @@
+use std::sync::OnceLock;
+use regex::{Regex, RegexBuilder};
+use rusqlite::Connection;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::rules::DetectionRules;
+use crate::services::spec_parser::{normalize_priority, normalize_req_type};
+
+/// A project's detection rules, validated and ready for the parser:
+/// keywords lowercased, patterns compiled.
+#[derive(Debug)]
+pub struct ParserRules {
+    section_keywords: Vec<String>,
+    section_patterns: Vec<Regex>,
+    requirement_prefixes: Vec<String>,
+    requirement_patterns: Vec<Regex>,
+    normative_keywords: Vec<String>,
+    priority_keywords: Vec<CompiledPriorityRule>,
+    type_rules: Vec<CompiledTypeRule>,
+}
+
+#[derive(Debug)]
+struct CompiledPriorityRule {
+    words: Vec<String>,
+    priority: String,
+    negatable: bool,
+}
+
+#[derive(Debug)]
+struct CompiledTypeRule {
+    req_type: String,
+    section_keywords: Vec<String>,
+    text_keywords: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl ParserRules {
+    pub fn compile(rules: &DetectionRules) -> Result<Self, AppError> {
+        let priority_keywords = rules
+            .priority_keywords
+            .iter()
+            .filter(|r| !words(&r.keyword).is_empty())
+            .map(|r| {
+                let priority = normalize_priority(r.priority.trim()).ok_or_else(|| {
+                    AppError::InvalidInput(format!("Unknown priority for keyword '{}': {}", r.keyword, r.priority))
+                })?;
+                Ok(CompiledPriorityRule {
+                    words: words(&r.keyword).into_iter().map(fold_word).collect(),
+                    priority,
+                    negatable: r.negatable,
+                })
+            })
+            .collect::<Result<_, AppError>>()?;
+
+        let type_rules = rules
+            .type_rules
+            .iter()
+            .map(|r| {
+                let req_type = type_name(&r.req_type);
+                if req_type.is_empty() {
+                    return Err(AppError::InvalidInput("Requirement type rules need a type name".into()));
+                }
+                Ok(CompiledTypeRule {
+                    req_type,
+                    section_keywords: keywords(&r.section_keywords),
+                    text_keywords: keywords(&r.text_keywords),
+                    patterns: patterns(&r.patterns)?,
+                })
+            })
+            .collect::<Result<_, AppError>>()?;
+
+        Ok(Self {
+            section_keywords: keywords(&rules.section_keywords),
+            section_patterns: patterns(&rules.section_patterns)?,
+            requirement_prefixes: keywords(&rules.requirement_prefixes),
+            requirement_patterns: patterns(&rules.requirement_patterns)?,
+            normative_keywords: keywords(&rules.normative_keywords),
+            priority_keywords,
+            type_rules,
+        })
+    }
+
+    /// The default rule set, used when a project has not configured its own.
+    pub fn builtin() -> &'static ParserRules {
+        static BUILTIN: OnceLock<ParserRules> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            ParserRules::compile(&DetectionRules::default()).expect("built-in detection rules are valid")
+        })
+    }
+
+    /// Headings under which every list item counts as a requirement.
+    pub fn is_requirement_section(&self, section: &str) -> bool {
+        let lower = section.to_lowercase();
+        self.section_keywords.iter().any(|k| lower.contains(k.as_str()))
+            || self.section_patterns.iter().any(|p| p.is_match(section))
+    }
+
+    pub fn looks_like_requirement(&self, text: &str) -> bool {
+        let lower = text.to_lowercase();
+        self.requirement_prefixes.iter().any(|p| lower.starts_with(p.as_str()))
+            || self.requirement_patterns.iter().any(|p| p.is_match(text))
+            // Bold text only counts if it contains enough words to be a real requirement
+            || (text.starts_with("**") && text.contains(' ') && lower.split_whitespace().count() >= 5)
+    }
+
+    /// Whether a normative keyword states something in the text itself, as
+    /// opposed to inside a parenthetical, a note, an example or an
+    /// explanatory clause ("Note that users must be aware ...").
+    pub fn has_normative_keyword(&self, text: &str) -> bool {
+        main_clauses(text).iter().any(|clause| {
+            clause
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|w| self.normative_keywords.iter().any(|k| w == k))
+        })
+    }
+
+    /// Type implied by section or wording, if any.
+    pub fn detect_requirement_type(&self, section: &str, text: &str) -> Option<&str> {
+        let lower_section = section.to_lowercase();
+        let lower_text = text.to_lowercase();
+        self.type_rules
+            .iter()
+            .find(|rule| {
+                rule.section_keywords.iter().any(|k| lower_section.contains(k.as_str()))
+                    || rule.text_keywords.iter().any(|k| lower_text.contains(k.as_str()))
+                    || rule.patterns.iter().any(|p| p.is_match(text))
+            })
+            .map(|rule| rule.req_type.as_str())
+    }
+
+    /// Priority implied by wording, if any, with the keyword as written
+    /// (e.g. `MUST NOT`) so reviewers can see why it was chosen. The keyword
+    /// used first in the text wins; rule order breaks ties.
+    pub fn detect_priority(&self, text: &str) -> Option<(&str, String)> {
+        let original = words(text);
+        let folded: Vec<String> = original.iter().map(|w| fold_word(w)).collect();
+        self.priority_keywords
+            .iter()
+            .filter_map(|rule| {
+                let (offset, keyword) = find_keyword(&original, &folded, rule)?;
+                Some((offset, rule.priority.as_str(), keyword))
+            })
+            .min_by_key(|(offset, _, _)| *offset)
+            .map(|(_, priority, keyword)| (priority, keyword))
+    }
+
+    /// Maps an explicit priority (table column) onto the priority of a
+    /// configured keyword it spells out, otherwise onto a built-in priority.
+    pub fn normalize_priority(&self, value: &str) -> Option<String> {
+        let folded: Vec<String> = words(value).into_iter().map(fold_word).collect();
+        self.priority_keywords
+            .iter()
+            .find(|rule| rule.words == folded)
+            .map(|rule| rule.priority.clone())
+            .or_else(|| normalize_priority(value))
+    }
+
+    /// Maps an explicit type (table column, front matter) onto a configured
+    /// custom type when it names one, otherwise onto a built-in type.
+    pub fn normalize_req_type(&self, value: &str) -> Option<String> {
+        let name = type_name(value);
+        self.type_rules
+            .iter()
+            .find(|rule| rule.req_type == name)
+            .map(|rule| rule.req_type.clone())
+            .or_else(|| normalize_req_type(value))
+    }
+}
+
+/// The rules a project's specs are parsed with: its own, or the defaults.
+pub fn rules_for_project(conn: &Connection, project_id: &str) -> Result<ParserRules, AppError> {
+    let config = queries::get_detection_rules(conn, project_id)?.unwrap_or_default();
+    ParserRules::compile(&config)
+}
+
+/// First use of a priority keyword in the text, with its word offset. Uses
+/// preceded by a negation ("not required") never count; uses followed by one
+/// ("could not", "shouldn't") only count for negatable keywords. The month
+/// "May" is not the keyword "may".
+fn find_keyword(original: &[&str], folded: &[String], rule: &CompiledPriorityRule) -> Option<(usize, String)> {
+    let n = rule.words.len();
+    (0..folded.len()).find_map(|i| {
+        let end = i + n;
+        if end > folded.len() || i > 0 && is_negation(&folded[i - 1]) || is_month(original, i) {
+            return None;
+        }
+        let (head, last) = rule.words.split_at(n - 1);
+        if folded[i..end - 1] != *head {
+            return None;
+        }
+        let word = &folded[end - 1];
+        let contracted = *word != last[0] && word.strip_suffix("n't") == Some(last[0].as_str());
+        if *word != last[0] && !contracted {
+            return None;
+        }
+        let followed_by_not = folded.get(end).is_some_and(|w| w == "not" || w == "never");
+        if (contracted || followed_by_not) && !rule.negatable {
+            return None;
+        }
+        let span = if followed_by_not { end + 1 } else { end };
+        Some((i, original[i..span].join(" ")))
+    })
+}
+
+/// Sentences opening with these are commentary, not requirements.
+const NOTE_MARKERS: &[&str] = &["note", "notes", "nb", "hint", "tip", "example", "eg", "ie", "for example", "for instance"];
+
+/// Clauses opening with these illustrate or explain the sentence around them.
+const ASIDE_MARKERS: &[&str] = &["eg", "ie", "for example", "for instance", "such as", "which", "who", "because", "although", "though"];
+
+/// Lowercased clauses of a text that can state a requirement, leaving out
+/// parenthesised text, note and example sentences, and asides.
+fn main_clauses(text: &str) -> Vec<String> {
+    let mut depth = 0usize;
+    let mut plain = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => plain.push(c),
+            _ => {}
+        }
+    }
+    // Keep abbreviations from ending sentences
+    let plain = plain.to_lowercase().replace("e.g.", "eg").replace("i.e.", "ie").replace("n.b.", "nb");
+
+    plain
+        .split(['.', '!', '?'])
+        .filter(|sentence| !opens_with(sentence, NOTE_MARKERS))
+        .flat_map(|sentence| sentence.split([',', ';', ':', '\u{2014}']))
+        .filter(|clause| !opens_with(clause, ASIDE_MARKERS))
+        .map(str::to_string)
+        .collect()
+}
+
+fn opens_with(clause: &str, markers: &[&str]) -> bool {
+    let words: Vec<&str> = clause.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).take(2).collect();
+    let one = words.first().copied().unwrap_or_default();
+    let two = words.join(" ");
+    markers.iter().any(|m| *m == one || *m == two)
+}
+
+fn is_negation(word: &str) -> bool {
+    word == "not" || word == "never" || word.ends_with("n't")
+}
+
+/// "May" capitalised after the first word, or opening the text before a day
+/// or year, as in "due in May" or "May 2025".
+fn is_month(original: &[&str], i: usize) -> bool {
+    original[i] == "May"
+        && (i > 0 || original.get(1).is_some_and(|next| next.starts_with(|c: char| c.is_ascii_digit())))
+}
+
+/// Words of a text, keeping apostrophes inside contractions.
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '\u{2019}'))
+        .map(|w| w.trim_matches(|c| c == '\'' || c == '\u{2019}'))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn fold_word(word: &str) -> String {
+    word.to_lowercase().replace('\u{2019}', "'")
+}
+
+/// `"Data Privacy"` -> `"data_privacy"`
+fn type_name(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase()
+}
+
+fn keywords(items: &[String]) -> Vec<String> {
+    items.iter().filter(|k| !k.trim().is_empty()).map(|k| k.to_lowercase()).collect()
+}
+
+fn patterns(items: &[String]) -> Result<Vec<Regex>, AppError> {
+    items
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            RegexBuilder::new(p)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| AppError::InvalidInput(format!("Invalid pattern '{}': {}", p, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::rules::{PriorityRule, TypeRule};
+
+    #[test]
+    fn test_builtin_rules() {
+        let rules = ParserRules::builtin();
+        assert!(rules.is_requirement_section("Functional Requirements"));
+        assert!(!rules.is_requirement_section("Background"));
+        assert!(rules.looks_like_requirement("The system shall export reports"));
+        assert_eq!(rules.detect_requirement_type("Performance", "Pages load fast"), Some("non_functional"));
+        assert_eq!(rules.detect_priority("Dark mode is optional"), Some(("low", "optional".to_string())));
+    }
+
+    #[test]
+    fn test_custom_type_and_priority() {
+        let mut config = DetectionRules::default();
+        config.type_rules.insert(0, TypeRule {
+            req_type: "Security".to_string(),
+            patterns: vec![r"\b(encrypt|tls)\w*".to_string()],
+            ..Default::default()
+        });
+        config.priority_keywords.insert(0, PriorityRule {
+            keyword: "blocker".to_string(),
+            priority: "P0".to_string(),
+            negatable: false,
+        });
+        let rules = ParserRules::compile(&config).unwrap();
+        assert_eq!(rules.detect_requirement_type("General", "Data is encrypted at rest"), Some("security"));
+        assert_eq!(rules.detect_priority("Blocker: login must work"), Some(("high", "Blocker".to_string())));
+        assert_eq!(rules.normalize_priority("BLOCKER").as_deref(), Some("high"));
+        assert_eq!(rules.normalize_priority("Could have").as_deref(), Some("low"));
+        assert_eq!(rules.normalize_priority("whenever"), None);
+        assert_eq!(rules.normalize_req_type("security").as_deref(), Some("security"));
+        assert_eq!(rules.normalize_req_type("NFR").as_deref(), Some("non_functional"));
+    }
+
+    fn priority(text: &str) -> Option<(&'static str, String)> {
+        ParserRules::builtin().detect_priority(text)
+    }
+
+    #[test]
+    fn test_rfc_2119_keywords() {
+        assert_eq!(priority("The client MUST send a token"), Some(("high", "MUST".to_string())));
+        assert_eq!(priority("Tokens SHALL NOT be logged"), Some(("high", "SHALL NOT".to_string())));
+        assert_eq!(priority("Servers SHOULD cache responses"), Some(("medium", "SHOULD".to_string())));
+        assert_eq!(priority("Clients shouldn't retry"), Some(("medium", "shouldn't".to_string())));
+        assert_eq!(priority("Clients MAY batch requests"), Some(("low", "MAY".to_string())));
+        assert_eq!(priority("Could have: dark mode"), Some(("low", "Could".to_string())));
+        assert_eq!(priority("Won\u{2019}t have: offline sync"), Some(("low", "Won\u{2019}t".to_string())));
+    }
+
+    #[test]
+    fn test_earliest_priority_keyword_wins() {
+        assert_eq!(priority("Clients MAY cache tokens but MUST NOT log them"), Some(("low", "MAY".to_string())));
+        assert_eq!(priority("Clients MUST NOT log tokens but MAY cache them"), Some(("high", "MUST NOT".to_string())));
+        assert_eq!(priority("Exports should be fast; they must be complete"), Some(("medium", "should".to_string())));
+    }
+
+    #[test]
+    fn test_month_may_is_not_a_keyword() {
+        assert_eq!(priority("Quarterly reports are archived every May"), None);
+        assert_eq!(priority("May 2025 invoices use the old tax rate"), None);
+        assert_eq!(priority("Reports due in May should list every refund"), Some(("medium", "should".to_string())));
+        assert_eq!(priority("May include a cover page"), Some(("low", "May".to_string())));
+    }
+
+    #[test]
+    fn test_priority_keywords_need_whole_words_and_no_negation() {
+        assert_eq!(priority("Serve mustard with every order"), None);
+        assert_eq!(priority("The import could not be retried"), None);
+        assert_eq!(priority("Logging is not optional"), None);
+        assert_eq!(priority("A login is not required, but users may sign in"), Some(("low", "may".to_string())));
+    }
+
+    #[test]
+    fn test_normative_keywords_in_main_clauses() {
+        let rules = ParserRules::builtin();
+        assert!(rules.has_normative_keyword("The system shall send an invoice."));
+        assert!(rules.has_normative_keyword("If a payment fails, the system must retry it (see the retry policy)."));
+        assert!(rules.has_normative_keyword("Exports run nightly. Failed exports must be reported."));
+    }
+
+    #[test]
+    fn test_normative_keywords_in_asides_are_ignored() {
+        let rules = ParserRules::builtin();
+        assert!(!rules.has_normative_keyword("Note that users must be aware of the retention period."));
+        assert!(!rules.has_normative_keyword("NB: the admin must rotate keys yearly."));
+        assert!(!rules.has_normative_keyword("Reports are archived (they must be kept for 7 years)."));
+        assert!(!rules.has_normative_keyword("Some fields are optional, e.g. the phone number must not be required."));
+        assert!(!rules.has_normative_keyword("For example, a user must confirm their e-mail first."));
+        assert!(!rules.has_normative_keyword("Logs are rotated daily, which must not lose entries."));
+    }
+
+    #[test]
+    fn test_section_patterns() {
+        let config = DetectionRules {
+            section_keywords: Vec::new(),
+            section_patterns: vec![r"^REQ\s+\d+".to_string()],
+            ..Default::default()
+        };
+        let rules = ParserRules::compile(&config).unwrap();
+        assert!(rules.is_requirement_section("req 12 Billing"));
+        assert!(!rules.is_requirement_section("Requirements"));
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let config = DetectionRules {
+            requirement_patterns: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(ParserRules::compile(&config), Err(AppError::InvalidInput(_))));
+
+        let mut config = DetectionRules::default();
+        config.priority_keywords[0].priority = "urgent-ish".to_string();
+        assert!(ParserRules::compile(&config).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+use crate::models::spec::SpecMetadata;
+use crate::services::spec_parser::{normalize_priority, normalize_req_type};
+
+/// Splits a leading front-matter block off a spec, returning its metadata and
+/// the remaining body. Accepts a YAML block fenced by `---` lines or a bare
+/// JSON object at the very start of the file. Content without (valid) front
+/// matter is returned unchanged with empty metadata.
+pub fn split_front_matter(content: &str) -> (SpecMetadata, &str) {
+    let text = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    if text.starts_with('{') {
+        let mut stream = serde_json::Deserializer::from_str(text).into_iter::<Value>();
+        if let Some(Ok(value @ Value::Object(_))) = stream.next() {
+            let body = &text[stream.byte_offset()..];
+            return (metadata_from_value(&value), body);
+        }
+        return (SpecMetadata::default(), content);
+    }
+
+    let Some(rest) = text.strip_prefix("---") else {
+        return (SpecMetadata::default(), content);
+    };
+    let Some(rest) = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n')) else {
+        return (SpecMetadata::default(), content);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return match serde_yaml::from_str::<Value>(yaml) {
+                Ok(value @ Value::Object(_)) => (metadata_from_value(&value), body),
+                _ => (SpecMetadata::default(), content),
+            };
+        }
+        offset += line.len();
+    }
+    (SpecMetadata::default(), content)
+}
+
+fn metadata_from_value(value: &Value) -> SpecMetadata {
+    let field = |keys: &[&str]| keys.iter().find_map(|k| value.get(*k).and_then(scalar_string));
+    SpecMetadata {
+        owner: field(&["owner"]),
+        version: field(&["version"]),
+        status: field(&["status"]),
+        component: field(&["component"]),
+        default_priority: field(&["default_priority", "priority"]).and_then(|p| normalize_priority(&p)),
+        default_req_type: field(&["default_req_type", "req_type", "type"]).and_then(|t| normalize_req_type(&t)),
+        tags: value.get("tags").map(tag_list).unwrap_or_default(),
+    }
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    let s = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!s.is_empty()).then_some(s)
+}
+
+/// Tags may be a list or a comma-separated string.
+fn tag_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(scalar_string).collect(),
+        Value::String(s) => s
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let content = "---\nowner: Jane\nversion: 1.2\nstatus: approved\ncomponent: billing\npriority: Must\ntags: [payments, invoices]\n---\n# Billing\n";
+        let (meta, body) = split_front_matter(content);
+        assert_eq!(meta.owner.as_deref(), Some("Jane"));
+        assert_eq!(meta.version.as_deref(), Some("1.2"));
+        assert_eq!(meta.status.as_deref(), Some("approved"));
+        assert_eq!(meta.component.as_deref(), Some("billing"));
+        assert_eq!(meta.default_priority.as_deref(), Some("high"));
+        assert_eq!(meta.tags, vec!["payments", "invoices"]);
+        assert_eq!(body, "# Billing\n");
+    }
+
+    #[test]
+    fn test_json_front_matter() {
+        let content = "{\"status\": \"draft\", \"tags\": \"auth, sso\"}\n# Login\n";
+        let (meta, body) = split_front_matter(content);
+        assert_eq!(meta.status.as_deref(), Some("draft"));
+        assert_eq!(meta.tags, vec!["auth", "sso"]);
+        assert_eq!(body.trim_start(), "# Login\n");
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        let content = "# Spec\n\n---\n\nText\n";
+        let (meta, body) = split_front_matter(content);
+        assert_eq!(meta, SpecMetadata::default());
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_unterminated_front_matter_is_ignored() {
+        let content = "---\nstatus: draft\n# Spec\n";
+        let (meta, body) = split_front_matter(content);
+        assert_eq!(meta, SpecMetadata::default());
+        assert_eq!(body, content);
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::watch;
+use crate::errors::AppError;
+
+/// Test generation batches in flight, keyed by project ID. A project runs
+/// at most one batch at a time.
+#[derive(Default)]
+pub struct GenerationJobs {
+    running: Mutex<HashMap<String, watch::Sender<bool>>>,
+}
+
+impl GenerationJobs {
+    /// Registers a batch for the project. The batch stays registered until
+    /// the returned job is dropped.
+    pub fn start(&self, project_id: &str) -> Result<GenerationJob<'_>, AppError> {
+        let mut running = self.running.lock().map_err(|e| AppError::General(e.to_string()))?;
+        if running.contains_key(project_id) {
+            return Err(AppError::InvalidInput("Tests are already being generated for this project".into()));
+        }
+        let (sender, cancelled) = watch::channel(false);
+        running.insert(project_id.to_string(), sender);
+        Ok(GenerationJob { jobs: self, project_id: project_id.to_string(), cancelled })
+    }
+
+    /// Asks the project's running batch to stop. Returns false when nothing
+    /// is running.
+    pub fn cancel(&self, project_id: &str) -> Result<bool, AppError> {
+        let running = self.running.lock().map_err(|e| AppError::General(e.to_string()))?;
+        Ok(running.get(project_id).map(|sender| sender.send_replace(true)).is_some())
+    }
+}
+
+pub struct GenerationJob<'a> {
+    jobs: &'a GenerationJobs,
+    project_id: String,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl GenerationJob<'_> {
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    /// Resolves once the batch is cancelled.
+    pub async fn cancelled(&self) {
+        let mut cancelled = self.cancelled.clone();
+        let _ = cancelled.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Drop for GenerationJob<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut running) = self.jobs.running.lock() {
+            running.remove(&self.project_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_one_batch_per_project() {
+        let jobs = GenerationJobs::default();
+        let job = jobs.start("p1").unwrap();
+        assert!(jobs.start("p1").is_err());
+        assert!(jobs.start("p2").is_ok());
+
+        assert!(!job.is_cancelled());
+        assert!(jobs.cancel("p1").unwrap());
+        assert!(job.is_cancelled());
+        job.cancelled().await;
+
+        drop(job);
+        assert!(!jobs.cancel("p1").unwrap());
+        assert!(jobs.start("p1").is_ok());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::models::spec::{GherkinExamples, GherkinScenario, GherkinStep, Requirement};
+use crate::services::detection_rules::ParserRules;
+use crate::services::spec_parser::{is_external_id, RequirementCollector};
+
+const STEP_KEYWORDS: &[&str] = &["Given", "When", "Then", "And", "But", "*"];
+
+/// A scenario being read, flushed into a requirement when the next block starts.
+struct PendingScenario {
+    name: String,
+    /// Number of tags inherited from the feature and rule, which come first
+    inherited_tags: usize,
+    scenario: GherkinScenario,
+}
+
+/// Turns each `Scenario` / `Scenario Outline` of a Cucumber feature file into
+/// a requirement, keeping its steps and Examples tables as structured data.
+/// Background steps are prepended to every scenario they apply to.
+pub fn parse_feature(spec_id: &str, content: &str, rules: &ParserRules) -> Vec<Requirement> {
+    let mut collector = RequirementCollector::new(spec_id).with_rules(rules);
+    let mut feature_name = String::from("General");
+    let mut feature_tags: Vec<String> = Vec::new();
+    let mut rule_name: Option<String> = None;
+    let mut rule_tags: Vec<String> = Vec::new();
+    let mut pending_tags: Vec<String> = Vec::new();
+    let mut feature_background: Vec<GherkinStep> = Vec::new();
+    let mut rule_background: Vec<GherkinStep> = Vec::new();
+    let mut in_background = false;
+    let mut current: Option<PendingScenario> = None;
+    let mut in_examples = false;
+    let mut doc_string: Option<(String, usize, Vec<String>)> = None; // (fence, indent, lines)
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some((fence, indent, lines)) = doc_string.as_mut() {
+            if trimmed == fence.as_str() {
+                let text = lines.join("\n");
+                doc_string = None;
+                let steps = if in_background {
+                    active_background(&mut feature_background, &mut rule_background, rule_name.is_some())
+                } else {
+                    match current.as_mut() {
+                        Some(pending) => &mut pending.scenario.steps,
+                        None => continue,
+                    }
+                };
+                if let Some(step) = steps.last_mut() {
+                    step.doc_string = Some(text);
+                }
+            } else {
+                lines.push(dedent(line, *indent).to_string());
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with("\"\"\"") || trimmed.starts_with("```") {
+            let fence = if trimmed.starts_with("\"\"\"") { "\"\"\"" } else { "```" };
+            let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+            doc_string = Some((fence.to_string(), indent, Vec::new()));
+            continue;
+        }
+
+        if trimmed.starts_with('@') {
+            pending_tags.extend(trimmed.split_whitespace().filter(|t| t.starts_with('@')).map(str::to_string));
+            continue;
+        }
+
+        if let Some((keyword, name)) = split_keyword(trimmed) {
+            match keyword {
+                "Feature" => {
+                    flush_scenario(&mut collector, current.take(), &section_name(&feature_name, &rule_name));
+                    feature_name = name.to_string();
+                    feature_tags = std::mem::take(&mut pending_tags);
+                    feature_background.clear();
+                    rule_name = None;
+                    rule_tags.clear();
+                    rule_background.clear();
+                    in_background = false;
+                }
+                "Rule" => {
+                    flush_scenario(&mut collector, current.take(), &section_name(&feature_name, &rule_name));
+                    rule_name = Some(name.to_string());
+                    rule_tags = std::mem::take(&mut pending_tags);
+                    rule_background.clear();
+                    in_background = false;
+                }
+                "Background" => {
+                    flush_scenario(&mut collector, current.take(), &section_name(&feature_name, &rule_name));
+                    in_background = true;
+                }
+                "Scenario" | "Example" | "Scenario Outline" | "Scenario Template" => {
+                    flush_scenario(&mut collector, current.take(), &section_name(&feature_name, &rule_name));
+                    in_background = false;
+                    in_examples = false;
+                    let mut tags = feature_tags.clone();
+                    tags.extend(rule_tags.iter().cloned());
+                    let inherited_tags = tags.len();
+                    tags.append(&mut pending_tags);
+                    let mut steps = feature_background.clone();
+                    steps.extend(rule_background.iter().cloned());
+                    let keyword = if keyword.starts_with("Scenario ") { "Scenario Outline" } else { "Scenario" };
+                    current = Some(PendingScenario {
+                        name: name.to_string(),
+                        inherited_tags,
+                        scenario: GherkinScenario {
+                            keyword: keyword.to_string(),
+                            tags,
+                            steps,
+                            examples: Vec::new(),
+                        },
+                    });
+                }
+                "Examples" | "Scenarios" => {
+                    pending_tags.clear();
+                    if let Some(pending) = current.as_mut() {
+                        in_examples = true;
+                        pending.scenario.examples.push(GherkinExamples {
+                            name: name.to_string(),
+                            ..Default::default()
+                        });
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if trimmed.starts_with('|') {
+            let cells = split_table_row(trimmed);
+            if in_examples {
+                if let Some(examples) = current.as_mut().and_then(|p| p.scenario.examples.last_mut()) {
+                    if examples.header.is_empty() {
+                        examples.header = cells;
+                    } else {
+                        examples.rows.push(cells);
+                    }
+                }
+            } else {
+                let steps = if in_background {
+                    Some(active_background(&mut feature_background, &mut rule_background, rule_name.is_some()))
+                } else {
+                    current.as_mut().map(|p| &mut p.scenario.steps)
+                };
+                if let Some(step) = steps.and_then(|s| s.last_mut()) {
+                    step.table.push(cells);
+                }
+            }
+            continue;
+        }
+
+        if let Some(step) = parse_step(trimmed) {
+            if in_background {
+                active_background(&mut feature_background, &mut rule_background, rule_name.is_some()).push(step);
+            } else if let Some(pending) = current.as_mut() {
+                in_examples = false;
+                pending.scenario.steps.push(step);
+            }
+        }
+        // Anything else is free-form description text
+    }
+
+    flush_scenario(&mut collector, current, &section_name(&feature_name, &rule_name));
+    collector.finish()
+}
+
+fn active_background<'a>(
+    feature: &'a mut Vec<GherkinStep>,
+    rule: &'a mut Vec<GherkinStep>,
+    in_rule: bool,
+) -> &'a mut Vec<GherkinStep> {
+    if in_rule { rule } else { feature }
+}
+
+fn section_name(feature: &str, rule: &Option<String>) -> String {
+    match rule {
+        Some(rule) => format!("{} / {}", feature, rule),
+        None => feature.to_string(),
+    }
+}
+
+fn flush_scenario(collector: &mut RequirementCollector, pending: Option<PendingScenario>, section: &str) {
+    let Some(pending) = pending else {
+        return;
+    };
+    if pending.name.is_empty() && pending.scenario.steps.is_empty() {
+        return;
+    }
+    let description = if pending.name.is_empty() {
+        pending.scenario.steps.iter().map(|s| format!("{} {}", s.keyword, s.text)).collect::<Vec<_>>().join(" ")
+    } else {
+        pending.name
+    };
+    // A tag such as `@REQ-AUTH-001` links the scenario to a requirement ID;
+    // the scenario's own tags take precedence over inherited ones
+    let (inherited, own) = pending.scenario.tags.split_at(pending.inherited_tags);
+    let external_id = own
+        .iter()
+        .chain(inherited)
+        .map(|t| t.trim_start_matches('@'))
+        .find(|t| is_external_id(t))
+        .map(str::to_string);
+    let req = collector.push(section, external_id, description, None, None);
+    req.scenario = Some(pending.scenario);
+}
+
+/// Splits `Keyword: name` block headers; returns `None` for steps and text.
+fn split_keyword(line: &str) -> Option<(&str, &str)> {
+    let (keyword, name) = line.split_once(':')?;
+    let keyword = keyword.trim();
+    matches!(
+        keyword,
+        "Feature" | "Rule" | "Background" | "Scenario" | "Example" | "Scenario Outline"
+            | "Scenario Template" | "Examples" | "Scenarios"
+    )
+    .then(|| (keyword, name.trim()))
+}
+
+fn parse_step(line: &str) -> Option<GherkinStep> {
+    STEP_KEYWORDS.iter().find_map(|keyword| {
+        let rest = line.strip_prefix(keyword)?;
+        rest.starts_with(' ').then(|| GherkinStep {
+            keyword: keyword.to_string(),
+            text: rest.trim().to_string(),
+            ..Default::default()
+        })
+    })
+}
+
+fn split_table_row(line: &str) -> Vec<String> {
+    let inner = line.trim().trim_start_matches('|').trim_end_matches('|');
+    inner.split('|').map(|cell| cell.trim().to_string()).collect()
+}
+
+/// A doc string line without the opening fence's indentation; anything
+/// indented further is part of the content.
+fn dedent(line: &str, indent: usize) -> &str {
+    let start = line
+        .char_indices()
+        .take(indent)
+        .take_while(|(_, c)| c.is_whitespace())
+        .last()
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    &line[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEATURE: &str = r#"@auth
+Feature: Login
+
+  Background:
+    Given the login page is open
+
+  @REQ-AUTH-001
+  Scenario: Successful login
+    When the user submits valid credentials
+    Then the dashboard is shown
+
+  Scenario Outline: Lockout after failed attempts
+    When the user fails to log in <attempts> times
+    Then the account is <state>
+
+    Examples:
+      | attempts | state    |
+      | 3        | active   |
+      | 5        | locked   |
+"#;
+
+    #[test]
+    fn test_parse_scenarios() {
+        let reqs = parse_feature("spec-1", FEATURE, ParserRules::builtin());
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].section, "Login");
+        assert_eq!(reqs[0].description, "Successful login");
+        assert_eq!(reqs[0].external_id.as_deref(), Some("REQ-AUTH-001"));
+
+        let scenario = reqs[0].scenario.as_ref().unwrap();
+        assert_eq!(scenario.tags, vec!["@auth", "@REQ-AUTH-001"]);
+        let keywords: Vec<&str> = scenario.steps.iter().map(|s| s.keyword.as_str()).collect();
+        assert_eq!(keywords, vec!["Given", "When", "Then"]);
+    }
+
+    #[test]
+    fn test_parse_scenario_outline_examples() {
+        let reqs = parse_feature("spec-1", FEATURE, ParserRules::builtin());
+        let scenario = reqs[1].scenario.as_ref().unwrap();
+        assert_eq!(scenario.keyword, "Scenario Outline");
+        assert_eq!(scenario.examples.len(), 1);
+        assert_eq!(scenario.examples[0].header, vec!["attempts", "state"]);
+        assert_eq!(scenario.examples[0].rows, vec![vec!["3", "active"], vec!["5", "locked"]]);
+    }
+
+    #[test]
+    fn test_step_tables_and_doc_strings() {
+        let content = r#"Feature: Import
+  Scenario: Import users
+    Given these users:
+      | name  |
+      | alice |
+    When the payload is posted
+      """
+      {"dry_run": true}
+      """
+    Then 1 user is created
+"#;
+        let reqs = parse_feature("spec-1", content, ParserRules::builtin());
+        let steps = &reqs[0].scenario.as_ref().unwrap().steps;
+        assert_eq!(steps[0].table, vec![vec!["name"], vec!["alice"]]);
+        assert_eq!(steps[1].doc_string.as_deref(), Some("{\"dry_run\": true}"));
+        assert_eq!(steps[2].text, "1 user is created");
+    }
+
+    #[test]
+    fn test_doc_strings_keep_their_indentation() {
+        let content = "Feature: Import\n  Scenario: Import users\n    When the payload is posted\n      ```json\n      {\n        \"users\": [\n          \"alice\"\n        ]\n      }\n      ```\n";
+        let reqs = parse_feature("spec-1", content, ParserRules::builtin());
+        let steps = &reqs[0].scenario.as_ref().unwrap().steps;
+        assert_eq!(steps[0].doc_string.as_deref(), Some("{\n  \"users\": [\n    \"alice\"\n  ]\n}"));
+    }
+
+    #[test]
+    fn test_rule_tags_apply_to_its_scenarios() {
+        let content = r#"@billing
+Feature: Refunds
+
+  @REQ-REF
+  Rule: Refunds need approval
+
+    @REQ-REF-1
+    Scenario: Manager approves a refund
+      Then the refund is paid
+
+  Rule: Small refunds are automatic
+
+    Scenario: Refund under 10 EUR
+      Then the refund is paid
+"#;
+        let reqs = parse_feature("spec-1", content, ParserRules::builtin());
+        assert_eq!(reqs[0].scenario.as_ref().unwrap().tags, vec!["@billing", "@REQ-REF", "@REQ-REF-1"]);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("REQ-REF-1"));
+        assert_eq!(reqs[1].scenario.as_ref().unwrap().tags, vec!["@billing"]);
+    }
+}
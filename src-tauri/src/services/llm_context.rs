@@ -0,0 +1,388 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use crate::models::spec::Requirement;
+use crate::services::codebase_scanner::{CodeSymbol, MAX_FILE_SIZE};
+use crate::services::words::{tokenize, MODAL_VERBS, STOP_WORDS};
+
+/// Rough size of a token in code and English prose
+const CHARS_PER_TOKEN: usize = 4;
+/// Longest source snippet taken for a single symbol
+const MAX_SNIPPET_LINES: usize = 60;
+const MAX_EXAMPLES: usize = 2;
+const MAX_LISTED_SYMBOLS: usize = 30;
+
+/// The code shown to the model alongside a requirement.
+#[derive(Debug, Default)]
+pub struct CodeContext {
+    /// Source of the symbols most relevant to the requirement, best first
+    pub snippets: Vec<Snippet>,
+    /// Further symbols, by name only, that didn't fit the budget
+    pub symbols: Vec<CodeSymbol>,
+    /// Existing tests whose style, imports and helpers to follow
+    pub examples: Vec<Snippet>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pub file_path: String,
+    /// 1-based line of the first line in `code`
+    pub start_line: usize,
+    pub code: String,
+}
+
+/// Picks the code to send with a requirement. Symbols are ranked by how
+/// many words of their name and path appear in the requirement; the best
+/// are included as source (doc comments, signature and body) until
+/// `token_budget` runs out, a quarter of which goes to existing tests.
+/// Reads files, so call it off the async runtime.
+pub fn gather(
+    files: &SourceFiles,
+    requirement: &Requirement,
+    symbols: &[CodeSymbol],
+    test_files: &[String],
+    token_budget: usize,
+) -> CodeContext {
+    let words = requirement_words(requirement);
+    let budget = token_budget.saturating_mul(CHARS_PER_TOKEN);
+    let mut example_budget = budget / 4;
+    let mut source_budget = budget - example_budget;
+    let mut context = CodeContext::default();
+
+    let ranked = rank_symbols(&words, symbols);
+    if ranked.is_empty() {
+        context.symbols = symbols.iter().take(MAX_LISTED_SYMBOLS).cloned().collect();
+    }
+    // Line ranges already shown, so a method inside a shown class isn't repeated
+    let mut shown: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+    for symbol in ranked {
+        let covered = shown
+            .get(symbol.file_path.as_str())
+            .is_some_and(|ranges| ranges.iter().any(|&(start, end)| (start..=end).contains(&symbol.line)));
+        if covered {
+            continue;
+        }
+        let snippet = files.content(&symbol.file_path).and_then(|content| extract_snippet(&content, symbol));
+        match snippet {
+            Some(snippet) if snippet.code.len() <= source_budget => {
+                source_budget -= snippet.code.len();
+                let end = snippet.start_line + snippet.code.lines().count().saturating_sub(1);
+                shown.entry(symbol.file_path.as_str()).or_default().push((snippet.start_line, end));
+                context.snippets.push(snippet);
+            }
+            _ if context.symbols.len() < MAX_LISTED_SYMBOLS => context.symbols.push(symbol.clone()),
+            _ => {}
+        }
+    }
+
+    // Tests next to the code being shown come first, then those named alike
+    let snippet_words: HashSet<String> = context.snippets.iter().flat_map(|s| path_words(&s.file_path)).collect();
+    let mut examples: Vec<(usize, &String)> = test_files
+        .iter()
+        .map(|path| {
+            let score = path_words(path)
+                .iter()
+                .map(|word| 2 * usize::from(snippet_words.contains(word)) + usize::from(words.contains(word)))
+                .sum();
+            (score, path)
+        })
+        .collect();
+    examples.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())).then(a.1.cmp(b.1)));
+    for (_, path) in examples {
+        if context.examples.len() == MAX_EXAMPLES || example_budget == 0 {
+            break;
+        }
+        let Some(content) = files.content(path) else {
+            continue;
+        };
+        let code = truncate_lines(&content, example_budget);
+        if code.trim().is_empty() {
+            continue;
+        }
+        example_budget -= code.len();
+        context.examples.push(Snippet { file_path: path.clone(), start_line: 1, code });
+    }
+    context
+}
+
+/// Symbols that share at least one word with the requirement, best first.
+/// A word in the name counts three times as much as one in the path.
+fn rank_symbols<'a>(words: &HashSet<String>, symbols: &'a [CodeSymbol]) -> Vec<&'a CodeSymbol> {
+    let mut scored: Vec<(usize, &CodeSymbol)> = symbols
+        .iter()
+        .map(|symbol| {
+            let name_hits = split_words(&symbol.name).iter().filter(|w| words.contains(*w)).count();
+            let path_hits = path_words(&symbol.file_path).iter().filter(|w| words.contains(*w)).count();
+            (3 * name_hits + path_hits, symbol)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.file_path.cmp(&b.1.file_path))
+            .then(a.1.line.cmp(&b.1.line))
+    });
+    scored.into_iter().map(|(_, symbol)| symbol).collect()
+}
+
+fn requirement_words(requirement: &Requirement) -> HashSet<String> {
+    let mut text = format!("{} {}", requirement.description, requirement.section);
+    for tag in &requirement.tags {
+        text.push(' ');
+        text.push_str(tag);
+    }
+    split_words(&text).into_iter().collect()
+}
+
+fn path_words(path: &str) -> Vec<String> {
+    let path = path.replace('\\', "/");
+    let without_ext = path.rsplit_once('.').map_or(path.as_str(), |(stem, _)| stem);
+    split_words(without_ext)
+        .into_iter()
+        .filter(|w| !matches!(w.as_str(), "src" | "lib" | "test" | "tests" | "spec" | "index"))
+        .collect()
+}
+
+/// Lowercase word stems of prose or identifiers: `getUserById` and
+/// "get the user by ID" both give `get`, `user`; short and stop words are
+/// dropped.
+fn split_words(text: &str) -> Vec<String> {
+    // Identifier parts become words of their own before tokenizing
+    let mut spaced = String::with_capacity(text.len());
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if c.is_uppercase() && prev_lower {
+            spaced.push(' ');
+        }
+        spaced.push(if matches!(c, '_' | '-' | '\'') { ' ' } else { c });
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    tokenize(&spaced)
+        .into_iter()
+        .filter(|w| w.len() >= 3 && !STOP_WORDS.contains(&w.as_str()) && !MODAL_VERBS.contains(&w.as_str()))
+        .map(|w| stem(&w).to_string())
+        .collect()
+}
+
+/// Strips common English endings so "tokens" and "token", "expired" and
+/// "expire", or "logged" and "log" meet.
+fn stem(word: &str) -> &str {
+    let (stripped, suffix) = ["ing", "ed", "es", "s"]
+        .iter()
+        .filter_map(|suffix| word.strip_suffix(suffix).map(|stripped| (stripped, *suffix)))
+        .find(|(stripped, _)| stripped.len() >= 3 && !stripped.ends_with('s'))
+        .unwrap_or((word, ""));
+    // The consonant doubled before "-ed" and "-ing" ("logg", "runn"), but not
+    // a double that belongs to the word ("bill", "buzz")
+    let bytes = stripped.as_bytes();
+    let n = bytes.len();
+    if matches!(suffix, "ed" | "ing")
+        && n >= 4
+        && bytes[n - 1] == bytes[n - 2]
+        && bytes[n - 1].is_ascii_alphabetic()
+        && !b"aeioulsz".contains(&bytes[n - 1])
+    {
+        return &stripped[..n - 1];
+    }
+    match stripped.strip_suffix('e') {
+        Some(root) if root.len() >= 3 => root,
+        _ => stripped,
+    }
+}
+
+/// The declaration at `symbol.line` with the comments, docstrings or
+/// attributes above it and its body: to the matching brace, or for Python
+/// and Ruby while lines stay indented below it.
+fn extract_snippet(content: &str, symbol: &CodeSymbol) -> Option<Snippet> {
+    let lines: Vec<&str> = content.lines().collect();
+    let decl = symbol.line.checked_sub(1).filter(|&i| i < lines.len())?;
+
+    let mut start = decl;
+    while start > 0 {
+        let above = lines[start - 1].trim_start();
+        let is_preamble = ["//", "/*", "*", "#", "@"].iter().any(|p| above.starts_with(p));
+        if !is_preamble || above.starts_with("#!") {
+            break;
+        }
+        start -= 1;
+    }
+
+    let ext = Path::new(&symbol.file_path).extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let end = match ext {
+        "py" | "rb" => indented_block_end(&lines, decl, ext == "rb"),
+        _ => braced_block_end(&lines, decl),
+    }
+    .min(start + MAX_SNIPPET_LINES - 1);
+
+    Some(Snippet {
+        file_path: symbol.file_path.clone(),
+        start_line: start + 1,
+        code: lines[start..=end].join("\n"),
+    })
+}
+
+fn indented_block_end(lines: &[&str], decl: usize, ruby: bool) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let decl_indent = indent(lines[decl]);
+    let mut end = decl;
+    for (i, line) in lines.iter().enumerate().skip(decl + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent(line) <= decl_indent {
+            if ruby && line.trim() == "end" {
+                end = i;
+            }
+            break;
+        }
+        end = i;
+    }
+    end
+}
+
+fn braced_block_end(lines: &[&str], decl: usize) -> usize {
+    let mut depth = 0usize;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(decl) {
+        for c in line.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if opened && depth == 0 {
+            return i;
+        }
+        // One-line declarations: `const add = (a, b) => a + b;`, trait methods
+        if !opened && line.trim_end().ends_with(';') {
+            return i;
+        }
+    }
+    lines.len() - 1
+}
+
+/// Whole lines of `content` that fit in `max_chars`.
+fn truncate_lines(content: &str, max_chars: usize) -> String {
+    let mut code = String::new();
+    for line in content.lines() {
+        if code.len() + line.len() + 1 > max_chars {
+            break;
+        }
+        code.push_str(line);
+        code.push('\n');
+    }
+    code
+}
+
+/// Codebase files, read at most once per generation batch and shared by
+/// its requirements.
+pub struct SourceFiles {
+    root: PathBuf,
+    cache: Mutex<HashMap<String, Option<Arc<str>>>>,
+}
+
+impl SourceFiles {
+    pub fn new(root: &str) -> Self {
+        Self { root: PathBuf::from(root), cache: Mutex::new(HashMap::new()) }
+    }
+
+    fn content(&self, rel_path: &str) -> Option<Arc<str>> {
+        if let Some(content) = self.cache.lock().ok()?.get(rel_path) {
+            return content.clone();
+        }
+        // Read without the lock so other requirements aren't held up
+        let path = self.root.join(rel_path);
+        let content = std::fs::metadata(&path)
+            .ok()
+            .filter(|meta| meta.len() <= MAX_FILE_SIZE)
+            .and_then(|_| std::fs::read_to_string(&path).ok())
+            .map(Arc::from);
+        self.cache.lock().ok()?.insert(rel_path.to_string(), content.clone());
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::spec_parser::parse_spec;
+
+    fn symbol(name: &str, file_path: &str, line: usize) -> CodeSymbol {
+        CodeSymbol { name: name.into(), kind: "function".into(), file_path: file_path.into(), line }
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("getUserById"), vec!["get", "user"]);
+        assert_eq!(split_words("reset_password_tokens"), vec!["reset", "password", "token"]);
+        assert_eq!(split_words("Users shall be logged out"), vec!["user", "log", "out"]);
+        assert_eq!(split_words("running billed jobs"), vec!["run", "bill", "job"]);
+        assert_eq!(split_words("HTTPClient"), vec!["httpclient"]);
+        assert_eq!(split_words("expired"), split_words("expire"));
+    }
+
+    #[test]
+    fn test_rank_symbols() {
+        let words: HashSet<String> = split_words("Reset the password with an emailed token").into_iter().collect();
+        let symbols = vec![
+            symbol("formatDate", "src/utils/date.ts", 1),
+            symbol("sendEmail", "src/mail.ts", 1),
+            symbol("resetPassword", "src/auth/password.ts", 10),
+            symbol("hash", "src/auth/password.ts", 3),
+        ];
+        let ranked: Vec<&str> = rank_symbols(&words, &symbols).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(ranked, vec!["resetPassword", "sendEmail", "hash"]);
+    }
+
+    #[test]
+    fn test_extract_braced_snippet() {
+        let content = "import x from 'y';\n\n/**\n * Signs a user in.\n */\nexport function login(user) {\n  if (!user) {\n    return null;\n  }\n  return token(user);\n}\n\nfunction other() {}\n";
+        let snippet = extract_snippet(content, &symbol("login", "src/auth.js", 6)).unwrap();
+        assert_eq!(snippet.start_line, 3);
+        assert!(snippet.code.starts_with("/**"));
+        assert!(snippet.code.ends_with("return token(user);\n}"));
+
+        let arrow = extract_snippet("const add = (a, b) => a + b;\nconst b = 1;\n", &symbol("add", "m.ts", 1)).unwrap();
+        assert_eq!(arrow.code, "const add = (a, b) => a + b;");
+    }
+
+    #[test]
+    fn test_extract_indented_snippet() {
+        let content = "@app.route('/login')\ndef login(user):\n    \"\"\"Signs a user in.\"\"\"\n\n    return token(user)\n\ndef other():\n    pass\n";
+        let snippet = extract_snippet(content, &symbol("login", "app/auth.py", 2)).unwrap();
+        assert_eq!(snippet.start_line, 1);
+        assert!(snippet.code.ends_with("return token(user)"));
+        assert!(!snippet.code.contains("other"));
+
+        let ruby = "class Session\n  def login(user)\n    token(user)\n  end\n\n  def logout\n  end\nend\n";
+        let snippet = extract_snippet(ruby, &symbol("login", "app/session.rb", 2)).unwrap();
+        assert_eq!(snippet.code, "  def login(user)\n    token(user)\n  end");
+    }
+
+    #[test]
+    fn test_gather_skips_empty_example_tests() {
+        let root = std::env::temp_dir().join(format!("llm-context-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("tests")).unwrap();
+        std::fs::write(root.join("tests/login.test.ts"), "").unwrap();
+        std::fs::write(root.join("tests/logout.test.ts"), "test('logs out', () => {});\n").unwrap();
+        let requirement = parse_spec("spec-1", "## Requirements\n\n- Users can log in\n").remove(0);
+        let test_files = vec!["tests/login.test.ts".to_string(), "tests/logout.test.ts".to_string()];
+
+        let files = SourceFiles::new(root.to_str().unwrap());
+        let context = gather(&files, &requirement, &[], &test_files, 1000);
+        let examples: Vec<&str> = context.examples.iter().map(|e| e.file_path.as_str()).collect();
+        assert_eq!(examples, vec!["tests/logout.test.ts"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_truncate_lines() {
+        assert_eq!(truncate_lines("one\ntwo\nthree\n", 9), "one\ntwo\n");
+        assert_eq!(truncate_lines("a very long line\n", 4), "");
+    }
+}
@@ -1,94 +1,111 @@
 use crate::errors::AppError;
 use crate::models::spec::Requirement;
-use crate::services::codebase_scanner::CodeSymbol;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize)]
-struct ClaudeRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
+use crate::services::llm_context::{CodeContext, Snippet};
+use crate::services::llm_provider::{retry_delay, LlmProvider};
 
-#[derive(Deserialize)]
-struct ClaudeResponse {
-    content: Vec<ContentBlock>,
+/// Streams a test from the provider, passing the code received so far to
+/// `on_code` after every chunk. An error from `on_code` stops generation.
+/// Rate-limited and transient failures are retried up to `max_retries` times,
+/// each retry starting the code over.
+pub async fn generate_test_with_llm<P, F>(
+    provider: &P,
+    requirement: &Requirement,
+    framework: &str,
+    context: &CodeContext,
+    max_retries: u32,
+    on_code: F,
+) -> Result<String, AppError>
+where
+    P: LlmProvider,
+    F: FnMut(&str) -> Result<(), AppError> + Send,
+{
+    let prompt = build_prompt(requirement, framework, &build_context(context));
+    complete_code(provider, &prompt, max_retries, on_code).await
 }
 
-#[derive(Deserialize)]
-struct ContentBlock {
-    text: Option<String>,
+/// A test that didn't get as far as checking its requirement.
+pub struct FailedAttempt<'a> {
+    pub code: &'a str,
+    /// What the test runner printed
+    pub output: &'a str,
 }
 
-pub async fn generate_test_with_llm(
-    api_key: &str,
+/// Asks the provider to fix a test that fails to run, streaming like
+/// `generate_test_with_llm`.
+pub async fn repair_test_with_llm<P, F>(
+    provider: &P,
     requirement: &Requirement,
     framework: &str,
-    symbols: &[CodeSymbol],
-) -> Result<String, AppError> {
-    let context = build_context(symbols);
-    let prompt = build_prompt(requirement, framework, &context);
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .map_err(AppError::Http)?;
-    let request = ClaudeRequest {
-        model: "claude-sonnet-4-20250514".to_string(),
-        max_tokens: 2048,
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: prompt,
-        }],
-    };
-
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(AppError::General(format!(
-            "Claude API error ({}): {}",
-            status, body
-        )));
-    }
+    context: &CodeContext,
+    failed: &FailedAttempt<'_>,
+    max_retries: u32,
+    on_code: F,
+) -> Result<String, AppError>
+where
+    P: LlmProvider,
+    F: FnMut(&str) -> Result<(), AppError> + Send,
+{
+    let prompt = build_repair_prompt(requirement, framework, &build_context(context), failed);
+    complete_code(provider, &prompt, max_retries, on_code).await
+}
 
-    let claude_response: ClaudeResponse = response.json().await?;
-    let test_code = claude_response
-        .content
-        .into_iter()
-        .filter_map(|block| block.text)
-        .collect::<Vec<_>>()
-        .join("\n");
+async fn complete_code<P, F>(provider: &P, prompt: &str, max_retries: u32, mut on_code: F) -> Result<String, AppError>
+where
+    P: LlmProvider,
+    F: FnMut(&str) -> Result<(), AppError> + Send,
+{
+    let mut attempt = 0;
+    let test_code = loop {
+        let mut received = String::new();
+        let result = provider
+            .complete_stream(prompt, |text| {
+                received.push_str(text);
+                on_code(partial_code(&received))
+            })
+            .await;
+        match result {
+            Err(e) if attempt < max_retries => {
+                let delay = retry_delay(&e, attempt).ok_or(e)?;
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => break result?,
+        }
+    };
 
     // Extract code block if wrapped in markdown
     let code = extract_code_block(&test_code).unwrap_or(test_code);
     Ok(code)
 }
 
-fn build_context(symbols: &[CodeSymbol]) -> String {
-    if symbols.is_empty() {
+fn build_context(context: &CodeContext) -> String {
+    if context.snippets.is_empty() && context.symbols.is_empty() && context.examples.is_empty() {
         return String::from("No codebase context available.");
     }
 
-    let mut context = String::from("Codebase symbols:\n");
-    for sym in symbols.iter().take(30) {
-        context.push_str(&format!("- {} {} (in {})\n", sym.kind, sym.name, sym.file_path));
+    let mut text = String::new();
+    if !context.snippets.is_empty() {
+        text.push_str("Relevant source code (paths relative to the project root):\n\n");
+        push_snippets(&mut text, &context.snippets);
+    }
+    if !context.symbols.is_empty() {
+        text.push_str("Other codebase symbols:\n");
+        for sym in &context.symbols {
+            text.push_str(&format!("- {} {} (in {}:{})\n", sym.kind, sym.name, sym.file_path, sym.line));
+        }
+        text.push('\n');
+    }
+    if !context.examples.is_empty() {
+        text.push_str("Existing tests in this project:\n\n");
+        push_snippets(&mut text, &context.examples);
+    }
+    text
+}
+
+fn push_snippets(text: &mut String, snippets: &[Snippet]) {
+    for snippet in snippets {
+        text.push_str(&format!("{}:{}\n```\n{}\n```\n\n", snippet.file_path, snippet.start_line, snippet.code.trim_end()));
     }
-    context
 }
 
 fn build_prompt(requirement: &Requirement, framework: &str, context: &str) -> String {
@@ -101,6 +118,7 @@ fn build_prompt(requirement: &Requirement, framework: &str, context: &str) -> St
     format!(
         r#"Generate a test for the following requirement. Output ONLY the test code, no explanations.
 
+Requirement ID: {}
 Requirement: {}
 Section: {}
 Type: {}
@@ -115,7 +133,10 @@ Generate a comprehensive test that:
 2. Includes meaningful assertions (not just placeholders)
 3. Has a traceability comment linking to the requirement
 4. Covers the main happy path and at least one edge case
-5. Uses realistic mock data where needed"#,
+5. Uses realistic mock data where needed
+6. Imports and calls the code shown above from its actual file paths, with its real signatures
+7. Follows the style, imports and helpers of the existing tests, if any are shown"#,
+        requirement.external_id.as_deref().unwrap_or(&requirement.id),
         requirement.description,
         requirement.section,
         requirement.req_type,
@@ -125,6 +146,35 @@ Generate a comprehensive test that:
     )
 }
 
+fn build_repair_prompt(requirement: &Requirement, framework: &str, context: &str, failed: &FailedAttempt<'_>) -> String {
+    format!(
+        r#"The test below was written for a requirement but fails before it can check it. Fix only what the test output shows is wrong (syntax, imports, fixtures, calls that don't match the real code) and keep what the test asserts. Output ONLY the corrected test code, no explanations.
+
+Requirement ID: {}
+Requirement: {}
+
+Test framework: {}
+
+{}
+
+Test code:
+```
+{}
+```
+
+Test output:
+```
+{}
+```"#,
+        requirement.external_id.as_deref().unwrap_or(&requirement.id),
+        requirement.description,
+        framework,
+        context,
+        failed.code.trim_end(),
+        failed.output,
+    )
+}
+
 fn extract_code_block(text: &str) -> Option<String> {
     // Try to find ```typescript, ```javascript, ```python, or generic ``` blocks
     let patterns = ["```typescript", "```javascript", "```python", "```js", "```ts", "```py", "```"];
@@ -139,3 +189,121 @@ fn extract_code_block(text: &str) -> Option<String> {
     }
     None
 }
+
+/// The code inside a still-arriving response: everything after the opening
+/// fence line, up to the closing fence if it has arrived yet.
+fn partial_code(text: &str) -> &str {
+    let Some(fence) = text.find("```") else {
+        return text;
+    };
+    let Some(line_end) = text[fence..].find('\n') else {
+        return "";
+    };
+    let code = &text[fence + line_end + 1..];
+    code.find("```").map_or(code, |end| &code[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::llm_provider::MockProvider;
+
+    #[tokio::test]
+    async fn test_generation_with_mock_provider() {
+        let requirement = crate::services::spec_parser::parse_spec("spec-1", "## Requirements\n- [REQ-7] Users shall log in\n").remove(0);
+        let mut partials = Vec::new();
+        let code = generate_test_with_llm(&MockProvider, &requirement, "pytest", &CodeContext::default(), 0, |partial| {
+            partials.push(partial.to_string());
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(code.starts_with("# Traces to: REQ-7"));
+        assert!(code.contains("\"Users shall log in\""));
+        assert!(partials.len() > 1);
+        assert_eq!(partials.last().map(|p| p.trim()), Some(code.as_str()));
+        assert_eq!(code, generate_test_with_llm(&MockProvider, &requirement, "pytest", &CodeContext::default(), 0, |_| Ok(())).await.unwrap());
+    }
+
+    /// Rate-limited on the first `failures` calls, then answers like the mock.
+    struct FlakyProvider {
+        failures: u32,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl LlmProvider for FlakyProvider {
+        fn name(&self) -> &str {
+            "Flaky"
+        }
+
+        async fn complete(&self, prompt: &str) -> Result<String, AppError> {
+            self.complete_stream(prompt, |_| Ok(())).await
+        }
+
+        async fn complete_stream<F>(&self, prompt: &str, on_text: F) -> Result<String, AppError>
+        where
+            F: FnMut(&str) -> Result<(), AppError> + Send,
+        {
+            if self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < self.failures {
+                return Err(AppError::Api {
+                    provider: self.name().to_string(),
+                    status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+                    retry_after: Some(std::time::Duration::ZERO),
+                    body: String::new(),
+                });
+            }
+            MockProvider.complete_stream(prompt, on_text).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_generation_is_retried() {
+        let requirement = crate::services::spec_parser::parse_spec("spec-1", "## Requirements\n- [REQ-7] Users shall log in\n").remove(0);
+        let flaky = |failures| FlakyProvider { failures, calls: Default::default() };
+
+        let provider = flaky(2);
+        assert!(generate_test_with_llm(&provider, &requirement, "jest", &CodeContext::default(), 2, |_| Ok(())).await.is_ok());
+        assert_eq!(provider.calls.into_inner(), 3);
+
+        let provider = flaky(3);
+        let result = generate_test_with_llm(&provider, &requirement, "jest", &CodeContext::default(), 2, |_| Ok(())).await;
+        assert!(matches!(result, Err(AppError::Api { .. })));
+    }
+
+    #[test]
+    fn test_context_shows_source_and_examples() {
+        let context = CodeContext {
+            snippets: vec![Snippet { file_path: "src/auth.ts".into(), start_line: 4, code: "export function login() {}\n".into() }],
+            symbols: Vec::new(),
+            examples: vec![Snippet { file_path: "src/auth.test.ts".into(), start_line: 1, code: "import { login } from './auth';".into() }],
+        };
+        let text = build_context(&context);
+        assert!(text.contains("src/auth.ts:4\n```\nexport function login() {}\n```"));
+        assert!(text.contains("Existing tests in this project:\n\nsrc/auth.test.ts:1\n```\nimport { login }"));
+        assert!(!text.contains("Other codebase symbols"));
+        assert_eq!(build_context(&CodeContext::default()), "No codebase context available.");
+    }
+
+    #[tokio::test]
+    async fn test_repair_sends_code_and_output() {
+        let requirement = crate::services::spec_parser::parse_spec("spec-1", "## Requirements\n- [REQ-7] Users shall log in\n").remove(0);
+        let failed = FailedAttempt { code: "import { login } from './nope';\n", output: "Cannot find module './nope'" };
+        let prompt = build_repair_prompt(&requirement, "jest", "No codebase context available.", &failed);
+        assert!(prompt.contains("Requirement ID: REQ-7"));
+        assert!(prompt.contains("```\nimport { login } from './nope';\n```"));
+        assert!(prompt.contains("```\nCannot find module './nope'\n```"));
+
+        let code = repair_test_with_llm(&MockProvider, &requirement, "jest", &CodeContext::default(), &failed, 0, |_| Ok(()))
+            .await
+            .unwrap();
+        assert!(code.starts_with("// Traces to: REQ-7"));
+    }
+
+    #[test]
+    fn test_partial_code() {
+        assert_eq!(partial_code("Here you go:\n```py"), "");
+        assert_eq!(partial_code("```python\ndef test_a():\n"), "def test_a():\n");
+        assert_eq!(partial_code("```python\ndef test_a():\n    pass\n```\nDone"), "def test_a():\n    pass\n");
+        assert_eq!(partial_code("def test_a():"), "def test_a():");
+    }
+}
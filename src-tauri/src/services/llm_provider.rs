@@ -0,0 +1,633 @@
+use std::future::Future;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::errors::AppError;
+
+pub const PROVIDERS: &[&str] = &["anthropic", "openai", "mock"];
+
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const MAX_CONCURRENCY: usize = 16;
+const MAX_RETRIES: u32 = 10;
+const MAX_REPAIR_ATTEMPTS: u32 = 5;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Which LLM generates tests and how it is called. Empty `base_url` and
+/// `model` fall back to the provider's defaults.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LlmSettings {
+    /// "anthropic", "openai" (any OpenAI-compatible chat completions server,
+    /// e.g. Ollama, llama.cpp server or vLLM) or "mock"
+    pub provider: String,
+    pub base_url: String,
+    pub model: String,
+    pub max_tokens: u32,
+    /// Provider default when unset
+    pub temperature: Option<f32>,
+    /// Longest wait to connect or for more of the response; a streamed
+    /// response can take longer than this as a whole
+    pub timeout_secs: u64,
+    /// Requests in flight at once during batch generation
+    pub concurrency: usize,
+    /// Retries for rate-limited, overloaded or timed-out requests
+    pub max_retries: u32,
+    /// Rough token budget for source code and example tests sent with each
+    /// requirement
+    pub context_tokens: usize,
+    /// Repairs of a test that fails to run, in self-heal mode
+    pub repair_attempts: u32,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            provider: "anthropic".to_string(),
+            base_url: String::new(),
+            model: String::new(),
+            max_tokens: 2048,
+            temperature: None,
+            timeout_secs: 60,
+            concurrency: 4,
+            max_retries: 3,
+            context_tokens: 6000,
+            repair_attempts: 2,
+        }
+    }
+}
+
+impl LlmSettings {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !PROVIDERS.contains(&self.provider.as_str()) {
+            return Err(AppError::InvalidInput(format!("Unsupported LLM provider: {}", self.provider)));
+        }
+        if self.max_tokens == 0 {
+            return Err(AppError::InvalidInput("Max tokens must be greater than 0".into()));
+        }
+        if self.temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
+            return Err(AppError::InvalidInput("Temperature must be between 0 and 2".into()));
+        }
+        if self.timeout_secs == 0 {
+            return Err(AppError::InvalidInput("Timeout must be greater than 0".into()));
+        }
+        if !(1..=MAX_CONCURRENCY).contains(&self.concurrency) {
+            return Err(AppError::InvalidInput(format!("Concurrency must be between 1 and {}", MAX_CONCURRENCY)));
+        }
+        if self.max_retries > MAX_RETRIES {
+            return Err(AppError::InvalidInput(format!("Max retries cannot exceed {}", MAX_RETRIES)));
+        }
+        if self.repair_attempts > MAX_REPAIR_ATTEMPTS {
+            return Err(AppError::InvalidInput(format!("Repair attempts cannot exceed {}", MAX_REPAIR_ATTEMPTS)));
+        }
+        if self.context_tokens == 0 {
+            return Err(AppError::InvalidInput("Context tokens must be greater than 0".into()));
+        }
+        let base_url = self.base_url.trim();
+        if !base_url.is_empty() && !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(AppError::InvalidInput(format!("Invalid base URL: {}", base_url)));
+        }
+        Ok(())
+    }
+}
+
+/// How long to wait before retrying a failed request, or `None` if the error
+/// won't go away by itself. Rate limits (429) and overloaded or failing
+/// servers (5xx) wait for `retry-after` when the provider sends one, otherwise
+/// back off exponentially; timeouts and dropped connections also back off.
+pub fn retry_delay(error: &AppError, attempt: u32) -> Option<Duration> {
+    let backoff = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt)).min(RETRY_MAX_DELAY);
+    match error {
+        AppError::Api { status, retry_after, .. }
+            if *status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() =>
+        {
+            Some(retry_after.map_or(backoff, |delay| delay.min(RETRY_MAX_DELAY)))
+        }
+        AppError::Http(e) if e.is_timeout() || e.is_connect() => Some(backoff),
+        _ => None,
+    }
+}
+
+/// A model that turns a prompt into a completion.
+pub trait LlmProvider {
+    /// Shown in errors, e.g. "Anthropic API error (429): ..."
+    fn name(&self) -> &str;
+
+    fn complete(&self, prompt: &str) -> impl Future<Output = Result<String, AppError>> + Send;
+
+    /// Like `complete`, but hands each piece of text to `on_text` as it
+    /// arrives. An error from `on_text` abandons the request and is returned.
+    fn complete_stream<F>(&self, prompt: &str, on_text: F) -> impl Future<Output = Result<String, AppError>> + Send
+    where
+        F: FnMut(&str) -> Result<(), AppError> + Send;
+}
+
+/// The provider configured in settings.
+pub enum Provider {
+    Anthropic(AnthropicProvider),
+    OpenAiCompatible(OpenAiCompatibleProvider),
+    Mock(MockProvider),
+}
+
+impl Provider {
+    /// Builds the configured provider. Anthropic needs an API key; local
+    /// OpenAI-compatible servers usually don't, but need a model name.
+    pub fn from_settings(api_key: &str, settings: &LlmSettings) -> Result<Self, AppError> {
+        settings.validate()?;
+        let api_key = api_key.trim();
+        let base_url = |default: &str| {
+            let url = settings.base_url.trim();
+            if url.is_empty() { default } else { url }.trim_end_matches('/').to_string()
+        };
+        // Not a deadline for the whole request: local models can stream for
+        // minutes, and a stalled stream is caught by the read timeout
+        let timeout = Duration::from_secs(settings.timeout_secs);
+        let config = |model: String, base_url: String| -> Result<ProviderConfig, AppError> {
+            Ok(ProviderConfig {
+                client: reqwest::Client::builder()
+                    .connect_timeout(timeout)
+                    .read_timeout(timeout)
+                    .build()
+                    .map_err(AppError::Http)?,
+                base_url,
+                model,
+                max_tokens: settings.max_tokens,
+                temperature: settings.temperature,
+            })
+        };
+        let model = settings.model.trim();
+
+        match settings.provider.as_str() {
+            "anthropic" => {
+                if api_key.is_empty() {
+                    return Err(AppError::InvalidInput("API key is required for LLM mode. Set it in Settings.".into()));
+                }
+                let model = if model.is_empty() { ANTHROPIC_DEFAULT_MODEL } else { model };
+                Ok(Provider::Anthropic(AnthropicProvider {
+                    config: config(model.to_string(), base_url(ANTHROPIC_BASE_URL))?,
+                    api_key: api_key.to_string(),
+                }))
+            }
+            "openai" => {
+                if model.is_empty() {
+                    return Err(AppError::InvalidInput("A model name is required for OpenAI-compatible providers".into()));
+                }
+                Ok(Provider::OpenAiCompatible(OpenAiCompatibleProvider {
+                    config: config(model.to_string(), base_url(OPENAI_BASE_URL))?,
+                    api_key: Some(api_key.to_string()).filter(|k| !k.is_empty()),
+                }))
+            }
+            _ => Ok(Provider::Mock(MockProvider)),
+        }
+    }
+}
+
+impl LlmProvider for Provider {
+    fn name(&self) -> &str {
+        match self {
+            Provider::Anthropic(p) => p.name(),
+            Provider::OpenAiCompatible(p) => p.name(),
+            Provider::Mock(p) => p.name(),
+        }
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, AppError> {
+        match self {
+            Provider::Anthropic(p) => p.complete(prompt).await,
+            Provider::OpenAiCompatible(p) => p.complete(prompt).await,
+            Provider::Mock(p) => p.complete(prompt).await,
+        }
+    }
+
+    async fn complete_stream<F>(&self, prompt: &str, on_text: F) -> Result<String, AppError>
+    where
+        F: FnMut(&str) -> Result<(), AppError> + Send,
+    {
+        match self {
+            Provider::Anthropic(p) => p.complete_stream(prompt, on_text).await,
+            Provider::OpenAiCompatible(p) => p.complete_stream(prompt, on_text).await,
+            Provider::Mock(p) => p.complete_stream(prompt, on_text).await,
+        }
+    }
+}
+
+struct ProviderConfig {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+// ─── Anthropic ──────────────────────────────────────────────────
+
+pub struct AnthropicProvider {
+    config: ProviderConfig,
+    api_key: String,
+}
+
+#[derive(Serialize)]
+struct AnthropicRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    messages: Vec<Message<'a>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContentBlock {
+    text: Option<String>,
+}
+
+/// One server-sent event of a streamed message; only text deltas and errors
+/// matter here.
+#[derive(Deserialize)]
+struct AnthropicEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    delta: Option<AnthropicContentBlock>,
+    error: Option<serde_json::Value>,
+}
+
+/// An error event sent mid-stream. Overload and rate limit errors get the
+/// status they would have had as a response so they are retried alike.
+fn stream_error(provider: &str, error: serde_json::Value) -> AppError {
+    let status = match error.get("type").and_then(|t| t.as_str()) {
+        Some("overloaded_error") => reqwest::StatusCode::from_u16(529).ok(),
+        Some("rate_limit_error") => Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
+        Some("api_error") => Some(reqwest::StatusCode::INTERNAL_SERVER_ERROR),
+        _ => None,
+    };
+    match status {
+        Some(status) => AppError::Api { provider: provider.to_string(), status, retry_after: None, body: error.to_string() },
+        None => AppError::General(format!("{} API error: {}", provider, error)),
+    }
+}
+
+impl AnthropicProvider {
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
+        let request = AnthropicRequest {
+            model: &self.config.model,
+            max_tokens: self.config.max_tokens,
+            temperature: self.config.temperature,
+            messages: vec![Message { role: "user", content: prompt }],
+            stream,
+        };
+        self.config
+            .client
+            .post(format!("{}/messages", self.config.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("content-type", "application/json")
+            .json(&request)
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "Anthropic"
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, AppError> {
+        let response = self.request(prompt, false).send().await?;
+        let response: AnthropicResponse = check_status(self.name(), response).await?.json().await?;
+        Ok(response.content.into_iter().filter_map(|block| block.text).collect::<Vec<_>>().join("\n"))
+    }
+
+    async fn complete_stream<F>(&self, prompt: &str, mut on_text: F) -> Result<String, AppError>
+    where
+        F: FnMut(&str) -> Result<(), AppError> + Send,
+    {
+        let response = check_status(self.name(), self.request(prompt, true).send().await?).await?;
+        let mut text = String::new();
+        read_events(response, |data| {
+            let event: AnthropicEvent = serde_json::from_str(data)?;
+            match event.kind.as_str() {
+                "content_block_delta" => {
+                    if let Some(delta) = event.delta.and_then(|d| d.text) {
+                        text.push_str(&delta);
+                        on_text(&delta)?;
+                    }
+                }
+                "error" => return Err(stream_error(self.name(), event.error.unwrap_or_default())),
+                _ => {}
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(text)
+    }
+}
+
+// ─── OpenAI-compatible ──────────────────────────────────────────
+
+/// Chat completions API as served by OpenAI, Ollama, llama.cpp server, vLLM
+/// and others.
+pub struct OpenAiCompatibleProvider {
+    config: ProviderConfig,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    messages: Vec<Message<'a>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatChunk {
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChunkChoice {
+    delta: ChatMessage,
+}
+
+impl OpenAiCompatibleProvider {
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
+        let request = ChatRequest {
+            model: &self.config.model,
+            max_tokens: self.config.max_tokens,
+            temperature: self.config.temperature,
+            messages: vec![Message { role: "user", content: prompt }],
+            stream,
+        };
+        let builder = self
+            .config
+            .client
+            .post(format!("{}/chat/completions", self.config.base_url))
+            .header("content-type", "application/json")
+            .json(&request);
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+}
+
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        "OpenAI-compatible"
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, AppError> {
+        let response = self.request(prompt, false).send().await?;
+        let response: ChatResponse = check_status(self.name(), response).await?.json().await?;
+        response
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .ok_or_else(|| AppError::General(format!("{} API returned no completion", self.name())))
+    }
+
+    async fn complete_stream<F>(&self, prompt: &str, mut on_text: F) -> Result<String, AppError>
+    where
+        F: FnMut(&str) -> Result<(), AppError> + Send,
+    {
+        let response = check_status(self.name(), self.request(prompt, true).send().await?).await?;
+        let mut text = String::new();
+        read_events(response, |data| {
+            if data == "[DONE]" {
+                return Ok(());
+            }
+            let chunk: ChatChunk = serde_json::from_str(data)?;
+            for delta in chunk.choices.into_iter().filter_map(|choice| choice.delta.content) {
+                text.push_str(&delta);
+                on_text(&delta)?;
+            }
+            Ok(())
+        })
+        .await?;
+        if text.is_empty() {
+            return Err(AppError::General(format!("{} API returned no completion", self.name())));
+        }
+        Ok(text)
+    }
+}
+
+async fn check_status(provider: &str, response: reqwest::Response) -> Result<reqwest::Response, AppError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()));
+    let body = response.text().await.unwrap_or_default();
+    Err(AppError::Api { provider: provider.to_string(), status, retry_after, body })
+}
+
+/// A `Retry-After` value: delay seconds, or an HTTP date (RFC 9110) that
+/// is turned into the time left until then.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Reads a server-sent event stream, passing the payload of each `data:`
+/// line to `on_data` as soon as the line is complete.
+async fn read_events<F>(mut response: reqwest::Response, mut on_data: F) -> Result<(), AppError>
+where
+    F: FnMut(&str) -> Result<(), AppError>,
+{
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            event_data(&line).map_or(Ok(()), &mut on_data)?;
+        }
+    }
+    event_data(&buffer).map_or(Ok(()), &mut on_data)
+}
+
+fn event_data(line: &[u8]) -> Option<&str> {
+    let line = std::str::from_utf8(line).ok()?.trim_end_matches(['\r', '\n']);
+    line.strip_prefix("data:").map(str::trim_start)
+}
+
+// ─── Mock ───────────────────────────────────────────────────────
+
+/// Answers without a network call: a skeleton test for the requirement in
+/// the prompt, identical for identical prompts. For offline demos and tests.
+pub struct MockProvider;
+
+impl LlmProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String, AppError> {
+        let field = |label: &str| {
+            prompt
+                .lines()
+                .find_map(|line| line.strip_prefix(label))
+                .map(str::trim)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let (id, requirement) = (field("Requirement ID:"), field("Requirement:"));
+        let quoted = serde_json::to_string(&requirement)?;
+        let code = if field("Test framework:").starts_with("pytest") {
+            format!(
+                "# Traces to: {id}\ndef test_requirement():\n    requirement = {quoted}\n    assert requirement\n"
+            )
+        } else {
+            format!(
+                "// Traces to: {id}\ndescribe({quoted}, () => {{\n  it(\"is satisfied\", () => {{\n    expect(true).toBe(true);\n  }});\n}});\n"
+            )
+        };
+        Ok(format!("```\n{}```", code))
+    }
+
+    async fn complete_stream<F>(&self, prompt: &str, mut on_text: F) -> Result<String, AppError>
+    where
+        F: FnMut(&str) -> Result<(), AppError> + Send,
+    {
+        let text = self.complete(prompt).await?;
+        for line in text.split_inclusive('\n') {
+            on_text(line)?;
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(provider: &str) -> LlmSettings {
+        LlmSettings { provider: provider.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_provider_selection() {
+        assert!(matches!(Provider::from_settings("", &settings("mock")), Ok(Provider::Mock(_))));
+        assert!(Provider::from_settings("", &settings("anthropic")).is_err());
+        assert!(matches!(Provider::from_settings("sk-ant", &settings("anthropic")), Ok(Provider::Anthropic(_))));
+
+        // Local servers need no key, but do need a model
+        assert!(Provider::from_settings("", &settings("openai")).is_err());
+        let local = LlmSettings { model: "llama3".into(), base_url: "http://localhost:11434/v1/".into(), ..settings("openai") };
+        match Provider::from_settings("", &local) {
+            Ok(Provider::OpenAiCompatible(p)) => {
+                assert_eq!(p.config.base_url, "http://localhost:11434/v1");
+                assert!(p.api_key.is_none());
+            }
+            _ => panic!("expected an OpenAI-compatible provider"),
+        }
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        assert!(settings("gemini").validate().is_err());
+        assert!(LlmSettings { temperature: Some(3.0), ..Default::default() }.validate().is_err());
+        assert!(LlmSettings { base_url: "localhost:8080".into(), ..Default::default() }.validate().is_err());
+        assert!(LlmSettings { concurrency: 0, ..Default::default() }.validate().is_err());
+        assert!(LlmSettings { max_retries: 50, ..Default::default() }.validate().is_err());
+        assert!(LlmSettings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after("30", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("1.5", now), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_retry_after("Sun, 01 Mar 2026 12:00:45 GMT", now), Some(Duration::from_secs(45)));
+        // A date in the past means now
+        assert_eq!(parse_retry_after("Sun, 01 Mar 2026 11:59:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("-5", now), None);
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let api_error = |status: u16, retry_after: Option<Duration>| AppError::Api {
+            provider: "Anthropic".into(),
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            retry_after,
+            body: String::new(),
+        };
+        // retry-after wins over backoff, within reason
+        assert_eq!(retry_delay(&api_error(429, Some(Duration::from_secs(7))), 0), Some(Duration::from_secs(7)));
+        assert_eq!(retry_delay(&api_error(429, Some(Duration::from_secs(3600))), 0), Some(RETRY_MAX_DELAY));
+        assert_eq!(retry_delay(&api_error(529, None), 0), Some(Duration::from_secs(1)));
+        assert_eq!(retry_delay(&api_error(503, None), 3), Some(Duration::from_secs(8)));
+        assert_eq!(retry_delay(&api_error(500, None), 30), Some(RETRY_MAX_DELAY));
+        assert_eq!(retry_delay(&api_error(401, None), 0), None);
+        assert_eq!(retry_delay(&AppError::General("bad".into()), 0), None);
+
+        assert!(matches!(
+            stream_error("Anthropic", serde_json::json!({"type": "overloaded_error", "message": "Overloaded"})),
+            AppError::Api { .. }
+        ));
+        assert!(matches!(stream_error("Anthropic", serde_json::json!({"type": "invalid_request_error"})), AppError::General(_)));
+    }
+
+    #[test]
+    fn test_event_data() {
+        assert_eq!(event_data(b"data: {\"a\":1}\r\n"), Some("{\"a\":1}"));
+        assert_eq!(event_data(b"data:[DONE]\n"), Some("[DONE]"));
+        assert_eq!(event_data(b"event: content_block_delta\n"), None);
+        assert_eq!(event_data(b"\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream_matches_completion() {
+        let prompt = "Requirement ID: REQ-1\nRequirement: Users can log in\nTest framework: pytest";
+        let mut streamed = String::new();
+        let text = MockProvider
+            .complete_stream(prompt, |chunk| {
+                streamed.push_str(chunk);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(streamed, text);
+        assert_eq!(text, MockProvider.complete(prompt).await.unwrap());
+    }
+}
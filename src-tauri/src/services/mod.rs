@@ -1,7 +1,32 @@
 pub mod spec_parser;
+pub mod detection_rules;
+pub mod gherkin_parser;
+pub mod rst_parser;
+pub mod asciidoc_parser;
+pub mod text_blocks;
+pub mod words;
+pub mod reqif_parser;
+pub mod csv_parser;
+pub mod spec_format;
+pub mod thresholds;
+pub mod front_matter;
+pub mod requirement_diff;
+pub mod requirement_lint;
+pub mod requirement_conflicts;
+pub mod requirement_status;
+pub mod requirement_edit;
+pub mod tags;
+pub mod search;
+pub mod spec_sync;
+pub mod spec_sources;
+pub mod spec_watcher;
 pub mod template_generator;
 pub mod llm_generator;
+pub mod llm_provider;
+pub mod llm_context;
+pub mod generation_jobs;
 pub mod test_runner;
+pub mod test_repair;
 pub mod alignment;
 pub mod codebase_scanner;
 pub mod git_service;
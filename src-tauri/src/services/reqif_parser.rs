@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+use roxmltree::{Document, Node, ParsingOptions};
+use crate::errors::AppError;
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::spec_parser::{push_table_row, LineIndex, RequirementCollector, TableColumns};
+
+/// Parses a ReqIF export. Every SpecObject with text becomes a requirement,
+/// in specification order; objects with only a heading (`ReqIF.ChapterName`)
+/// name the section of the objects below them, and objects nested under a
+/// requirement become its acceptance criteria. Attributes are recognised by
+/// name like requirements table columns: `ReqIF.ForeignID`, `ReqIF.Text`,
+/// `Type`, `Priority`.
+pub fn parse_reqif(spec_id: &str, content: &str, rules: &ParserRules) -> Result<Vec<Requirement>, AppError> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let doc = Document::parse_with_options(content, options)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid ReqIF: {}", e)))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "REQ-IF" {
+        return Err(AppError::InvalidInput(format!(
+            "Not a ReqIF document: root element is <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    let model = ReqIfModel::read(root);
+    let mut reader = ObjectReader {
+        model: &model,
+        collector: RequirementCollector::new(spec_id).with_rules(rules),
+        lines: LineIndex::new(content, 0),
+        placed: HashSet::new(),
+    };
+
+    for spec in elements(root, "SPECIFICATION") {
+        let section = spec.attribute("LONG-NAME").unwrap_or("General");
+        for child in children(spec, "CHILDREN").flat_map(|c| children(c, "SPEC-HIERARCHY")) {
+            reader.read_hierarchy(child, section, None);
+        }
+    }
+    // Objects no specification refers to, in document order
+    for (id, object) in &model.objects {
+        if !reader.placed.contains(id) {
+            let cells = model.cells(*object);
+            reader.push_object(*object, &cells, "General", None);
+        }
+    }
+
+    Ok(reader.collector.finish())
+}
+
+/// Attribute and enumeration names of a ReqIF document, and its SpecObjects.
+struct ReqIfModel<'a, 'input> {
+    /// Attribute definition ID -> column in `header`
+    attributes: HashMap<&'a str, usize>,
+    header: Vec<String>,
+    columns: TableColumns,
+    heading: Option<usize>,
+    /// Enum value ID -> display name
+    enum_values: HashMap<&'a str, &'a str>,
+    /// In document order
+    objects: Vec<(&'a str, Node<'a, 'input>)>,
+    /// Object ID -> object, for resolving hierarchy references
+    object_index: HashMap<&'a str, Node<'a, 'input>>,
+}
+
+impl<'a, 'input> ReqIfModel<'a, 'input> {
+    fn read(root: Node<'a, 'input>) -> Self {
+        let mut attributes = HashMap::new();
+        let mut header: Vec<String> = Vec::new();
+        let mut enum_values = HashMap::new();
+        let mut objects = Vec::new();
+
+        for node in root.descendants().filter(Node::is_element) {
+            let tag = node.tag_name().name();
+            let id = node.attribute("IDENTIFIER");
+            if tag.starts_with("ATTRIBUTE-DEFINITION-") && !tag.ends_with("-REF") {
+                if let (Some(id), Some(name)) = (id, node.attribute("LONG-NAME")) {
+                    let column = column_name(name);
+                    let index = header.iter().position(|h| *h == column).unwrap_or_else(|| {
+                        header.push(column);
+                        header.len() - 1
+                    });
+                    attributes.insert(id, index);
+                }
+            } else if tag == "ENUM-VALUE" {
+                if let (Some(id), Some(name)) = (id, node.attribute("LONG-NAME")) {
+                    enum_values.insert(id, name);
+                }
+            } else if tag == "SPEC-OBJECT" {
+                if let Some(id) = id {
+                    objects.push((id, node));
+                }
+            }
+        }
+
+        let mut columns = TableColumns::from_header(&header);
+        // Some tools only fill in the object's name
+        if columns.description.is_none() {
+            columns.description = header.iter().position(|h| h == "name");
+        }
+        let heading = header.iter().position(|h| h == "heading");
+        let object_index = objects.iter().copied().collect();
+        Self {
+            attributes,
+            header,
+            columns,
+            heading,
+            enum_values,
+            objects,
+            object_index,
+        }
+    }
+
+    /// The object's attribute values laid out as a table row.
+    fn cells(&self, object: Node) -> Vec<String> {
+        let mut cells = vec![String::new(); self.header.len()];
+        let values = children(object, "VALUES").flat_map(|v| v.children().filter(Node::is_element));
+        for value in values {
+            let definition = children(value, "DEFINITION")
+                .flat_map(|d| d.children().filter(Node::is_element))
+                .find_map(|r| r.text())
+                .map(str::trim);
+            let Some(&index) = definition.and_then(|d| self.attributes.get(d)) else {
+                continue;
+            };
+            cells[index] = self.value_text(value);
+        }
+        cells
+    }
+
+    fn value_text(&self, value: Node) -> String {
+        match value.tag_name().name() {
+            "ATTRIBUTE-VALUE-XHTML" => {
+                let mut text = String::new();
+                for the_value in children(value, "THE-VALUE") {
+                    xhtml_text(the_value, &mut text);
+                }
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            "ATTRIBUTE-VALUE-ENUMERATION" => children(value, "VALUES")
+                .flat_map(|v| children(v, "ENUM-VALUE-REF"))
+                .filter_map(|r| r.text())
+                .map(|id| self.enum_values.get(id.trim()).copied().unwrap_or(id.trim()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => value.attribute("THE-VALUE").unwrap_or_default().trim().to_string(),
+        }
+    }
+}
+
+struct ObjectReader<'m, 'a, 'input, 'c> {
+    model: &'m ReqIfModel<'a, 'input>,
+    collector: RequirementCollector<'c>,
+    lines: LineIndex,
+    placed: HashSet<&'a str>,
+}
+
+impl<'a> ObjectReader<'_, 'a, '_, '_> {
+    fn read_hierarchy(&mut self, hierarchy: Node<'a, '_>, section: &str, parent_id: Option<String>) {
+        let object = children(hierarchy, "OBJECT")
+            .flat_map(|o| children(o, "SPEC-OBJECT-REF"))
+            .find_map(|r| r.text())
+            .and_then(|id| self.model.object_index.get_key_value(id.trim()))
+            .map(|(id, node)| (*id, *node));
+
+        let mut child_section = section.to_string();
+        let mut child_parent = parent_id.clone();
+        if let Some((id, node)) = object {
+            self.placed.insert(id);
+            let cells = self.model.cells(node);
+            match self.push_object(node, &cells, section, parent_id) {
+                Some(req_id) => child_parent = Some(req_id),
+                None => {
+                    let heading = self.model.heading.map(|i| &cells[i]);
+                    if let Some(heading) = heading.filter(|h| !h.is_empty()) {
+                        child_section = heading.clone();
+                    }
+                }
+            }
+        }
+        for child in children(hierarchy, "CHILDREN").flat_map(|c| children(c, "SPEC-HIERARCHY")) {
+            self.read_hierarchy(child, &child_section, child_parent.clone());
+        }
+    }
+
+    /// Adds the object as a requirement if it has text; returns its ID.
+    fn push_object(&mut self, object: Node, cells: &[String], section: &str, parent_id: Option<String>) -> Option<String> {
+        let req = push_table_row(&mut self.collector, section, &self.model.columns, cells)?;
+        req.parent_id = parent_id;
+        req.span = Some(self.lines.span(object.range()));
+        Some(req.id.clone())
+    }
+}
+
+/// ReqIF standard attribute names and common tool names, mapped onto the
+/// requirements table headers `TableColumns` understands.
+fn column_name(long_name: &str) -> String {
+    let lower = long_name.trim().to_lowercase();
+    let name = lower.strip_prefix("reqif.").unwrap_or(&lower);
+    let column = match name {
+        "foreignid" | "foreign id" | "identifier" => "id",
+        "text" | "object text" => "text",
+        "chaptername" | "chapter name" | "object heading" | "heading" => "heading",
+        "category" => "type",
+        n if n.ends_with(" type") => "type",
+        other => other,
+    };
+    column.to_string()
+}
+
+/// Appends the text of an XHTML fragment. Inline markup can split a word
+/// (`re<b>quire</b>ment`), so only block elements separate text.
+fn xhtml_text(node: Node, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        } else if is_xhtml_block(child) {
+            text.push(' ');
+            xhtml_text(child, text);
+            text.push(' ');
+        } else {
+            xhtml_text(child, text);
+        }
+    }
+}
+
+/// XHTML elements that start a new line of text.
+fn is_xhtml_block(node: Node) -> bool {
+    const BLOCKS: &[&str] = &[
+        "address", "blockquote", "br", "dd", "div", "dl", "dt", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "li",
+        "ol", "p", "pre", "table", "td", "th", "tr", "ul",
+    ];
+    node.is_element() && BLOCKS.contains(&node.tag_name().name())
+}
+
+fn children<'a, 'input>(node: Node<'a, 'input>, tag: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |n| n.tag_name().name() == tag)
+}
+
+fn elements<'a, 'input>(node: Node<'a, 'input>, tag: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.descendants().filter(move |n| n.tag_name().name() == tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQIF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="dt-prio" LONG-NAME="Priority">
+          <SPECIFIED-VALUES>
+            <ENUM-VALUE IDENTIFIER="ev-high" LONG-NAME="High"/>
+            <ENUM-VALUE IDENTIFIER="ev-low" LONG-NAME="Low"/>
+          </SPECIFIED-VALUES>
+        </DATATYPE-DEFINITION-ENUMERATION>
+      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="t-req" LONG-NAME="Requirement">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-id" LONG-NAME="ReqIF.ForeignID"/>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-head" LONG-NAME="ReqIF.ChapterName"/>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="ad-text" LONG-NAME="ReqIF.Text"/>
+            <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="ad-prio" LONG-NAME="Priority"/>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="o-head">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="Billing">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-head</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+          </VALUES>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="o-1">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="BIL-1">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-id</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE><xhtml:div>Invoices are generated <xhtml:b>monthly</xhtml:b></xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+            <ATTRIBUTE-VALUE-ENUMERATION>
+              <DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>ad-prio</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+              <VALUES><ENUM-VALUE-REF>ev-high</ENUM-VALUE-REF></VALUES>
+            </ATTRIBUTE-VALUE-ENUMERATION>
+          </VALUES>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="o-2">
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE><xhtml:div><xhtml:p>Invoices list every line item</xhtml:p><xhtml:p>in the order's <xhtml:b>curr</xhtml:b>ency</xhtml:p></xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="o-3">
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE><xhtml:div>Dark mode is optional</xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="s-1" LONG-NAME="System Requirements">
+          <CHILDREN>
+            <SPEC-HIERARCHY IDENTIFIER="h-1">
+              <OBJECT><SPEC-OBJECT-REF>o-head</SPEC-OBJECT-REF></OBJECT>
+              <CHILDREN>
+                <SPEC-HIERARCHY IDENTIFIER="h-2">
+                  <OBJECT><SPEC-OBJECT-REF>o-1</SPEC-OBJECT-REF></OBJECT>
+                  <CHILDREN>
+                    <SPEC-HIERARCHY IDENTIFIER="h-3">
+                      <OBJECT><SPEC-OBJECT-REF>o-2</SPEC-OBJECT-REF></OBJECT>
+                    </SPEC-HIERARCHY>
+                  </CHILDREN>
+                </SPEC-HIERARCHY>
+              </CHILDREN>
+            </SPEC-HIERARCHY>
+          </CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>
+"#;
+
+    #[test]
+    fn test_parse_reqif() {
+        let reqs = parse_reqif("spec-1", REQIF, ParserRules::builtin()).unwrap();
+        assert_eq!(reqs.len(), 3);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("BIL-1"));
+        assert_eq!(reqs[0].description, "Invoices are generated monthly");
+        assert_eq!(reqs[0].section, "Billing");
+        assert_eq!(reqs[0].priority, "high");
+        assert_eq!(reqs[1].description, "Invoices list every line item in the order's currency");
+        assert_eq!(reqs[1].parent_id.as_deref(), Some(reqs[0].id.as_str()));
+        // Not part of any specification
+        assert_eq!(reqs[2].section, "General");
+        assert_eq!(reqs[2].priority, "low");
+        let span = reqs[0].span.as_ref().unwrap();
+        assert!(REQIF[span.start_offset as usize..span.end_offset as usize].starts_with("<SPEC-OBJECT IDENTIFIER=\"o-1\">"));
+    }
+
+    #[test]
+    fn test_rejects_non_reqif_xml() {
+        assert!(parse_reqif("spec-1", "<html></html>", ParserRules::builtin()).is_err());
+        assert!(parse_reqif("spec-1", "<REQ-IF>", ParserRules::builtin()).is_err());
+    }
+}
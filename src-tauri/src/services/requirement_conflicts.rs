@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use crate::models::lint::{DuplicateCluster, RequirementConflict, RequirementOverlaps};
+use crate::models::spec::Requirement;
+use crate::services::requirement_diff::similarity;
+use crate::services::thresholds::{quantity_at, Quantity};
+use crate::services::words::{jaccard, tokenize, DUPLICATE_THRESHOLD, MODAL_VERBS, STOP_WORDS};
+
+/// Similarity of what two requirements are about (modal verbs and numbers
+/// left out) from which differing obligations or limits count as a conflict.
+const SAME_SUBJECT_THRESHOLD: f64 = 0.75;
+
+/// Words that forbid on their own; "not" and "no" only forbid after a modal.
+const PROHIBITIONS: &[&str] = &["never", "cannot", "can't", "won't", "mustn't", "shan't", "shouldn't"];
+
+/// Finds clusters of near-duplicate requirements and pairs that contradict
+/// each other: one requires what the other forbids, or they set different
+/// limits for the same thing ("within 200ms" vs "within 500ms").
+pub fn find_overlaps(requirements: &[Requirement]) -> RequirementOverlaps {
+    let profiles: Vec<Profile> = requirements.iter().map(Profile::new).collect();
+    let mut overlaps = RequirementOverlaps::default();
+    let mut links: Vec<(usize, usize, f64)> = Vec::new();
+
+    for (i, a) in profiles.iter().enumerate() {
+        for (j, b) in profiles.iter().enumerate().skip(i + 1) {
+            if a.req.parent_id.as_deref() == Some(b.req.id.as_str())
+                || b.req.parent_id.as_deref() == Some(a.req.id.as_str())
+            {
+                continue;
+            }
+            if let Some((kind, message)) = conflict(a, b) {
+                overlaps.conflicts.push(RequirementConflict {
+                    kind: kind.to_string(),
+                    message,
+                    first: a.req.clone(),
+                    second: b.req.clone(),
+                });
+                continue;
+            }
+            let score = similarity(&a.req.description, &b.req.description);
+            if score >= DUPLICATE_THRESHOLD {
+                links.push((i, j, score));
+            }
+        }
+    }
+
+    overlaps.duplicates = clusters(requirements, &links);
+    overlaps
+}
+
+/// A requirement's text broken down for comparison.
+struct Profile<'a> {
+    req: &'a Requirement,
+    /// What the requirement is about: words without modal verbs, negations
+    /// and quantities
+    subject: HashSet<String>,
+    prohibits: bool,
+    quantities: Vec<Quantity>,
+}
+
+impl<'a> Profile<'a> {
+    fn new(req: &'a Requirement) -> Self {
+        let tokens = tokenize(&req.description);
+        let mut subject = HashSet::new();
+        let mut quantities = Vec::new();
+        let mut prohibits = false;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = tokens[i].as_str();
+            let after_modal = i > 0 && MODAL_VERBS.contains(&tokens[i - 1].as_str());
+            if PROHIBITIONS.contains(&token) || (after_modal && (token == "not" || token == "no")) {
+                prohibits = true;
+            } else if let Some((quantity, consumed)) = quantity_at(&tokens, i) {
+                quantities.push(quantity);
+                i += consumed;
+                continue;
+            } else if !STOP_WORDS.contains(&token) && !MODAL_VERBS.contains(&token) {
+                subject.insert(token.to_string());
+            }
+            i += 1;
+        }
+
+        Profile {
+            req,
+            subject,
+            prohibits,
+            quantities,
+        }
+    }
+}
+
+/// Why two requirements about the same thing contradict each other, if they do.
+fn conflict(a: &Profile, b: &Profile) -> Option<(&'static str, String)> {
+    let modal = a.prohibits != b.prohibits;
+    let limits = !a.quantities.is_empty() && !b.quantities.is_empty();
+    if !(modal || limits) || jaccard(&a.subject, &b.subject) < SAME_SUBJECT_THRESHOLD {
+        return None;
+    }
+    if modal {
+        return Some(("modal", "One requirement requires what the other forbids".to_string()));
+    }
+    a.quantities.iter().find_map(|qa| {
+        let same_unit: Vec<&Quantity> = b.quantities.iter().filter(|qb| qb.unit == qa.unit).collect();
+        if same_unit.is_empty() || same_unit.iter().any(|qb| (qb.value - qa.value).abs() < f64::EPSILON) {
+            return None;
+        }
+        Some(("numeric", format!("Limits differ: {} vs {}", qa.text, same_unit[0].text)))
+    })
+}
+
+/// Groups linked pairs into clusters, in the order their first requirement
+/// appears.
+fn clusters(requirements: &[Requirement], links: &[(usize, usize, f64)]) -> Vec<DuplicateCluster> {
+    let mut parent: Vec<usize> = (0..requirements.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for &(i, j, _) in links {
+        let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+        if ri != rj {
+            parent[ri.max(rj)] = ri.min(rj);
+        }
+    }
+
+    let mut lowest: HashMap<usize, f64> = HashMap::new();
+    for &(i, _, score) in links {
+        let r = root(&mut parent, i);
+        let entry = lowest.entry(r).or_insert(score);
+        *entry = entry.min(score);
+    }
+
+    let mut members: HashMap<usize, Vec<Requirement>> = HashMap::new();
+    for (i, req) in requirements.iter().enumerate() {
+        let r = root(&mut parent, i);
+        if lowest.contains_key(&r) {
+            members.entry(r).or_default().push(req.clone());
+        }
+    }
+    let mut roots: Vec<usize> = members.keys().copied().collect();
+    roots.sort_unstable();
+    roots
+        .into_iter()
+        .map(|r| DuplicateCluster {
+            requirements: members.remove(&r).unwrap_or_default(),
+            similarity: lowest[&r],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::spec_parser::parse_spec;
+
+    fn reqs(spec_id: &str, items: &[&str]) -> Vec<Requirement> {
+        let content = format!("## Requirements\n\n{}\n", items.iter().map(|i| format!("- {}\n", i)).collect::<String>());
+        parse_spec(spec_id, &content)
+    }
+
+    #[test]
+    fn test_duplicates_across_specs_are_clustered() {
+        let mut all = reqs("spec-1", &["The system shall email the invoice to the customer", "The system shall archive invoices"]);
+        all.extend(reqs("spec-2", &["The system shall email the invoice to a customer."]));
+        all.extend(reqs("spec-3", &["The system shall email an invoice to the customer"]));
+
+        let overlaps = find_overlaps(&all);
+        assert_eq!(overlaps.duplicates.len(), 1);
+        let ids: Vec<&str> = overlaps.duplicates[0].requirements.iter().map(|r| r.spec_id.as_str()).collect();
+        assert_eq!(ids, vec!["spec-1", "spec-2", "spec-3"]);
+        assert!(overlaps.duplicates[0].similarity >= DUPLICATE_THRESHOLD);
+        assert!(overlaps.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_contradicting_modal_verbs() {
+        let mut all = reqs("spec-1", &["Users shall be able to delete paid invoices"]);
+        all.extend(reqs("spec-2", &["Users must not be able to delete paid invoices"]));
+
+        let overlaps = find_overlaps(&all);
+        assert_eq!(overlaps.conflicts.len(), 1);
+        assert_eq!(overlaps.conflicts[0].kind, "modal");
+        assert!(overlaps.duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_contradicting_numeric_limits() {
+        let mut all = reqs("spec-1", &["Search results shall load within 200ms", "Exports shall finish within 2 seconds"]);
+        all.extend(reqs("spec-2", &["Search results shall load within 500 ms", "Exports shall finish within 2000 ms"]));
+
+        let overlaps = find_overlaps(&all);
+        assert_eq!(overlaps.conflicts.len(), 1);
+        let conflict = &overlaps.conflicts[0];
+        assert_eq!(conflict.kind, "numeric");
+        assert_eq!(conflict.message, "Limits differ: 200ms vs 500 ms");
+        assert_eq!(conflict.first.spec_id, "spec-1");
+    }
+
+    #[test]
+    fn test_unrelated_requirements_do_not_overlap() {
+        let all = reqs("spec-1", &["Invoices shall be retained for 7 years", "Passwords shall be at least 12 characters"]);
+        let overlaps = find_overlaps(&all);
+        assert!(overlaps.duplicates.is_empty());
+        assert!(overlaps.conflicts.is_empty());
+    }
+}
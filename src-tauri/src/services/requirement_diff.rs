@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+use crate::models::spec::{Requirement, RequirementChange, RequirementChanges};
+use crate::services::spec_parser::{identity_key, requirement_key};
+use crate::services::words::{jaccard, tokenize};
+
+// Minimum word overlap for an edited requirement to be treated as the same
+// requirement rather than a removal plus an addition.
+const SAME_SECTION_THRESHOLD: f64 = 0.5;
+const CROSS_SECTION_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Default)]
+pub struct RequirementMerge {
+    /// Parsed requirements matched to an existing row; they carry that row's ID.
+    pub to_update: Vec<Requirement>,
+    /// Parsed requirements with no existing counterpart.
+    pub to_insert: Vec<Requirement>,
+    /// IDs of active requirements that no longer appear in the spec.
+    pub to_retire: Vec<String>,
+}
+
+/// Reconciles a fresh parse with the requirements already stored for a spec
+/// (including retired ones), so that matched requirements keep their IDs and
+/// therefore their generated tests and results.
+pub fn merge_requirements(existing: &[Requirement], parsed: Vec<Requirement>) -> RequirementMerge {
+    let mut by_key: HashMap<String, VecDeque<&Requirement>> = HashMap::new();
+    // Active rows first so a retired duplicate is only revived when needed
+    let ordered = existing.iter().filter(|r| r.retired_at.is_none())
+        .chain(existing.iter().filter(|r| r.retired_at.is_some()));
+    for req in ordered {
+        by_key
+            .entry(identity_key(req.external_id.as_deref(), &req.section, &req.description))
+            .or_default()
+            .push_back(req);
+    }
+
+    let mut merge = RequirementMerge::default();
+    // Parsed ID -> final ID, to repoint sub-requirements at their merged parent
+    let mut resolved: HashMap<String, String> = HashMap::new();
+    let mut matched: HashSet<&str> = HashSet::new();
+    let mut unmatched = Vec::new();
+
+    for mut req in parsed {
+        let key = identity_key(req.external_id.as_deref(), &req.section, &req.description);
+        match by_key.get_mut(&key).and_then(|q| q.pop_front()) {
+            Some(prev) => {
+                resolved.insert(req.id.clone(), prev.id.clone());
+                req.id = prev.id.clone();
+                req.change_status = if prev.retired_at.is_some() {
+                    "new"
+                } else if requirement_key(&prev.section, &prev.description)
+                    == requirement_key(&req.section, &req.description)
+                    && prev.scenario == req.scenario
+                {
+                    "unchanged"
+                } else {
+                    "modified"
+                }
+                .to_string();
+                matched.insert(prev.id.as_str());
+                merge.to_update.push(req);
+            }
+            None => unmatched.push(req),
+        }
+    }
+
+    // Text changed: pair with the most similar remaining active requirement
+    let mut candidates: Vec<&Requirement> = existing
+        .iter()
+        .filter(|r| r.retired_at.is_none() && !matched.contains(r.id.as_str()))
+        .collect();
+    let taken_ids: HashSet<&str> = existing.iter().map(|r| r.id.as_str()).collect();
+
+    for mut req in unmatched {
+        let best = candidates
+            .iter()
+            .enumerate()
+            // Two different explicit IDs are never the same requirement
+            .filter(|(_, prev)| prev.external_id.is_none() || req.external_id.is_none())
+            .filter_map(|(i, prev)| {
+                let score = similarity(&prev.description, &req.description);
+                let threshold = if prev.section == req.section {
+                    SAME_SECTION_THRESHOLD
+                } else {
+                    CROSS_SECTION_THRESHOLD
+                };
+                (score >= threshold).then_some((i, score))
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+        if let Some((i, _)) = best {
+            let prev = candidates.remove(i);
+            resolved.insert(req.id.clone(), prev.id.clone());
+            req.id = prev.id.clone();
+            req.change_status = "modified".to_string();
+            merge.to_update.push(req);
+        } else {
+            // A modified requirement keeps its original ID, which may equal the
+            // derived ID of newly re-added text
+            if taken_ids.contains(req.id.as_str()) {
+                let fresh = Uuid::new_v4().to_string();
+                resolved.insert(req.id.clone(), fresh.clone());
+                req.id = fresh;
+            }
+            req.change_status = "new".to_string();
+            merge.to_insert.push(req);
+        }
+    }
+
+    for req in merge.to_update.iter_mut().chain(merge.to_insert.iter_mut()) {
+        if let Some(final_id) = req.parent_id.as_ref().and_then(|p| resolved.get(p)) {
+            req.parent_id = Some(final_id.clone());
+        }
+    }
+
+    merge.to_retire = candidates.into_iter().map(|r| r.id.clone()).collect();
+    merge
+}
+
+/// Compares two parses of a spec (e.g. two revisions), pairing requirements
+/// the same way a re-parse does.
+pub fn diff_requirements(before: Vec<Requirement>, after: Vec<Requirement>) -> RequirementChanges {
+    let merge = merge_requirements(&before, after);
+    let mut before: HashMap<String, Requirement> = before.into_iter().map(|r| (r.id.clone(), r)).collect();
+    let mut changes = RequirementChanges {
+        added: merge.to_insert,
+        ..Default::default()
+    };
+    for after in merge.to_update {
+        match before.remove(&after.id) {
+            Some(prev) if after.change_status == "modified" => {
+                changes.modified.push(RequirementChange { before: prev, after })
+            }
+            _ => changes.unchanged += 1,
+        }
+    }
+    changes.removed = merge.to_retire.iter().filter_map(|id| before.remove(id)).collect();
+    changes
+}
+
+/// Jaccard similarity over lowercase word sets.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let words = |s: &str| -> HashSet<String> { tokenize(s).into_iter().collect() };
+    jaccard(&words(a), &words(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::spec_parser::parse_spec;
+
+    fn stored(reqs: Vec<Requirement>) -> Vec<Requirement> {
+        reqs.into_iter()
+            .map(|mut r| {
+                r.change_status = "new".to_string();
+                r
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unchanged_requirements_keep_ids() {
+        let content = "## Requirements\n\n- The system shall export reports\n- The system shall import specs\n";
+        let existing = stored(parse_spec("s", content));
+        let merge = merge_requirements(&existing, parse_spec("s", content));
+        assert_eq!(merge.to_update.len(), 2);
+        assert!(merge.to_insert.is_empty());
+        assert!(merge.to_retire.is_empty());
+        assert!(merge.to_update.iter().all(|r| r.change_status == "unchanged"));
+    }
+
+    #[test]
+    fn test_edited_requirement_is_modified_not_replaced() {
+        let before = "## Requirements\n\n- The system shall export reports as CSV\n";
+        let after = "## Requirements\n\n- The system shall export reports as CSV and JSON\n";
+        let existing = stored(parse_spec("s", before));
+        let merge = merge_requirements(&existing, parse_spec("s", after));
+        assert_eq!(merge.to_update.len(), 1);
+        assert_eq!(merge.to_update[0].id, existing[0].id);
+        assert_eq!(merge.to_update[0].change_status, "modified");
+        assert!(merge.to_retire.is_empty());
+    }
+
+    #[test]
+    fn test_removed_requirement_is_retired_and_new_one_inserted() {
+        let before = "## Requirements\n\n- The system shall export reports\n";
+        let after = "## Requirements\n\n- Users must be able to delete their account\n";
+        let existing = stored(parse_spec("s", before));
+        let merge = merge_requirements(&existing, parse_spec("s", after));
+        assert_eq!(merge.to_retire, vec![existing[0].id.clone()]);
+        assert_eq!(merge.to_insert.len(), 1);
+        assert_eq!(merge.to_insert[0].change_status, "new");
+    }
+
+    #[test]
+    fn test_retired_requirement_is_revived() {
+        let content = "## Requirements\n\n- The system shall export reports\n";
+        let mut existing = stored(parse_spec("s", content));
+        existing[0].retired_at = Some("2024-01-01T00:00:00Z".to_string());
+        let merge = merge_requirements(&existing, parse_spec("s", content));
+        assert_eq!(merge.to_update.len(), 1);
+        assert_eq!(merge.to_update[0].id, existing[0].id);
+        assert!(merge.to_insert.is_empty());
+    }
+
+    #[test]
+    fn test_tagged_requirement_matched_by_external_id() {
+        let before = "- [REQ-7] Reports export as CSV\n";
+        let after = "- [REQ-7] Audit logs are retained for ninety days\n";
+        let existing = stored(parse_spec("s", before));
+        let merge = merge_requirements(&existing, parse_spec("s", after));
+        assert_eq!(merge.to_update.len(), 1);
+        assert_eq!(merge.to_update[0].id, existing[0].id);
+        assert_eq!(merge.to_update[0].change_status, "modified");
+    }
+
+    #[test]
+    fn test_sub_requirements_follow_modified_parent() {
+        let before = "## Requirements\n\n- The system shall export reports as CSV\n  - Columns are quoted\n";
+        let after = "## Requirements\n\n- The system shall export reports as CSV and JSON\n  - Columns are quoted\n";
+        let existing = stored(parse_spec("s", before));
+        let merge = merge_requirements(&existing, parse_spec("s", after));
+        assert_eq!(merge.to_update.len(), 2);
+        let child = &merge.to_update[0];
+        assert_eq!(child.id, existing[1].id);
+        assert_eq!(child.change_status, "unchanged");
+        assert_eq!(child.parent_id.as_deref(), Some(existing[0].id.as_str()));
+        assert_eq!(merge.to_update[1].id, existing[0].id);
+        assert_eq!(merge.to_update[1].change_status, "modified");
+    }
+
+    #[test]
+    fn test_diff_requirements() {
+        let before = "## Requirements\n\n- The system shall export reports as CSV\n- The system shall import specs\n- Users must be able to log in\n";
+        let after = "## Requirements\n\n- The system shall export reports as CSV and JSON\n- Users must be able to log in\n- Users must be able to delete their account\n";
+        let changes = diff_requirements(parse_spec("s", before), parse_spec("s", after));
+        assert_eq!(changes.unchanged, 1);
+        assert_eq!(changes.modified.len(), 1);
+        assert_eq!(changes.modified[0].before.description, "The system shall export reports as CSV");
+        assert_eq!(changes.modified[0].after.description, "The system shall export reports as CSV and JSON");
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0].description, "Users must be able to delete their account");
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.removed[0].description, "The system shall import specs");
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("export reports", "Export reports"), 1.0);
+        assert_eq!(similarity("export", "import"), 0.0);
+        assert_eq!(similarity("", ""), 0.0);
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use uuid::Uuid;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::spec::{
+    CreateRequirementRequest, Requirement, RequirementEdit, RequirementFields, UpdateRequirementRequest,
+};
+use crate::services::detection_rules::{self, ParserRules};
+use crate::services::spec_parser::{normalize_priority, RequirementCollector};
+use crate::services::{spec_sync, tags, thresholds};
+
+/// Adds a requirement by hand. Type and priority are detected from the text
+/// when not given, as for parsed requirements. Callers are expected to wrap
+/// this in a transaction.
+pub fn create_requirement(
+    conn: &Connection,
+    request: &CreateRequirementRequest,
+    edited_by: &str,
+) -> Result<Requirement, AppError> {
+    let spec = queries::get_spec(conn, &request.spec_id)?;
+    let rules = detection_rules::rules_for_project(conn, &spec.project_id)?;
+    let section = non_empty(&request.section, "Section")?;
+    let description = non_empty(&request.description, "Description")?;
+    let req_type = request.req_type.as_deref().map(|t| normalize_type(&rules, t)).transpose()?;
+    let priority = request.priority.as_deref().map(normalize_priority_input).transpose()?;
+    let external_id = request.external_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+    if let Some(external_id) = external_id {
+        let taken = queries::get_requirements_for_project(conn, &spec.project_id)?
+            .iter()
+            .any(|r| r.external_id.as_deref().is_some_and(|id| id.eq_ignore_ascii_case(external_id)));
+        if taken {
+            return Err(AppError::InvalidInput(format!("Duplicate requirement IDs in project: {}", external_id)));
+        }
+    }
+
+    let mut collector = RequirementCollector::new(&spec.id).with_rules(&rules).with_defaults(&spec.metadata);
+    collector.push(&section, external_id.map(str::to_string), description, req_type, priority);
+    let mut requirement = collector.finish().remove(0);
+    // Content-derived IDs belong to parsed requirements
+    requirement.id = Uuid::new_v4().to_string();
+    requirement.manually_edited = true;
+
+    queries::insert_requirements(conn, std::slice::from_ref(&requirement))?;
+    tags::link_inline_tags(conn, &spec.project_id, [&requirement])?;
+    record_edit(conn, &requirement.id, "create", None, Some((&requirement).into()), edited_by)?;
+    spec_sync::refresh_lint_findings(conn, &spec.id)?;
+    queries::get_requirement(conn, &requirement.id)
+}
+
+/// Changes a requirement's description, section, type or priority by hand.
+/// `#tag` tokens in a new description are added to the requirement's tags.
+pub fn update_requirement(
+    conn: &Connection,
+    requirement_id: &str,
+    request: &UpdateRequirementRequest,
+    edited_by: &str,
+) -> Result<Requirement, AppError> {
+    let before = queries::get_requirement(conn, requirement_id)?;
+    if before.retired_at.is_some() {
+        return Err(AppError::InvalidInput("Cannot edit a deleted requirement".into()));
+    }
+    let spec = queries::get_spec(conn, &before.spec_id)?;
+    let rules = detection_rules::rules_for_project(conn, &spec.project_id)?;
+
+    let mut after = before.clone();
+    let mut new_tags = Vec::new();
+    if let Some(section) = &request.section {
+        after.section = non_empty(section, "Section")?;
+    }
+    if let Some(description) = &request.description {
+        let (description, tags) = tags::extract_inline_tags(&non_empty(description, "Description")?);
+        after.description = description;
+        new_tags = tags;
+    }
+    if let Some(req_type) = &request.req_type {
+        after.req_type = normalize_type(&rules, req_type)?;
+    }
+    if let Some(priority) = &request.priority {
+        after.priority = normalize_priority_input(priority)?;
+        after.priority_keyword = None;
+    }
+    after.threshold = if after.req_type == "non_functional" {
+        thresholds::parse_threshold(&after.description)
+    } else {
+        None
+    };
+
+    for name in &new_tags {
+        let tag = queries::get_or_create_tag(conn, &spec.project_id, name)?;
+        queries::add_requirement_tag(conn, requirement_id, &tag.id, "manual")?;
+    }
+
+    let (old_fields, new_fields) = (RequirementFields::from(&before), RequirementFields::from(&after));
+    if old_fields == new_fields {
+        return queries::get_requirement(conn, requirement_id);
+    }
+    queries::update_requirement_fields(conn, &after)?;
+    record_edit(conn, requirement_id, "edit", Some(old_fields), Some(new_fields), edited_by)?;
+    spec_sync::refresh_lint_findings(conn, &after.spec_id)?;
+    queries::get_requirement(conn, requirement_id)
+}
+
+/// Deletes a requirement and its acceptance criteria. They are retired
+/// rather than removed, so their history stays and re-parsing the spec does
+/// not bring them back.
+pub fn delete_requirement(conn: &Connection, requirement_id: &str, edited_by: &str) -> Result<(), AppError> {
+    let requirement = queries::get_requirement(conn, requirement_id)?;
+    if requirement.retired_at.is_some() {
+        return Err(AppError::NotFound(format!("Requirement not found: {}", requirement_id)));
+    }
+    let active = queries::get_requirements_for_spec(conn, &requirement.spec_id)?;
+
+    let mut doomed = vec![&requirement];
+    let mut i = 0;
+    while i < doomed.len() {
+        let parent = doomed[i].id.clone();
+        let children: Vec<&Requirement> = active
+            .iter()
+            .filter(|r| r.parent_id.as_deref() == Some(parent.as_str()) && !doomed.iter().any(|d| d.id == r.id))
+            .collect();
+        doomed.extend(children);
+        i += 1;
+    }
+    for req in doomed {
+        queries::retire_requirement_manually(conn, &req.id)?;
+        record_edit(conn, &req.id, "delete", Some(req.into()), None, edited_by)?;
+    }
+    spec_sync::refresh_lint_findings(conn, &requirement.spec_id)
+}
+
+fn record_edit(
+    conn: &Connection,
+    requirement_id: &str,
+    action: &str,
+    before: Option<RequirementFields>,
+    after: Option<RequirementFields>,
+    edited_by: &str,
+) -> Result<(), AppError> {
+    queries::insert_requirement_edit(
+        conn,
+        &RequirementEdit {
+            id: Uuid::new_v4().to_string(),
+            requirement_id: requirement_id.to_string(),
+            action: action.to_string(),
+            before,
+            after,
+            edited_by: edited_by.to_string(),
+            edited_at: Utc::now().to_rfc3339(),
+        },
+    )
+}
+
+fn non_empty(value: &str, field: &str) -> Result<String, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::InvalidInput(format!("{} cannot be empty", field)));
+    }
+    Ok(value.to_string())
+}
+
+fn normalize_type(rules: &ParserRules, value: &str) -> Result<String, AppError> {
+    rules
+        .normalize_req_type(value)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown requirement type: {}", value)))
+}
+
+fn normalize_priority_input(value: &str) -> Result<String, AppError> {
+    normalize_priority(value).ok_or_else(|| AppError::InvalidInput(format!("Unknown priority: {}", value)))
+}
@@ -0,0 +1,282 @@
+use chrono::Utc;
+use uuid::Uuid;
+use crate::models::lint::LintFinding;
+use crate::models::spec::Requirement;
+use crate::services::requirement_diff::similarity;
+use crate::services::words::{tokenize, DUPLICATE_THRESHOLD, MODAL_VERBS};
+
+/// Vague words and phrases that cannot be verified as written.
+const AMBIGUOUS_TERMS: &[&str] = &[
+    "fast", "quick", "quickly", "slow", "user-friendly", "user friendly", "easy", "easily",
+    "simple", "intuitive", "efficient", "efficiently", "flexible", "robust", "seamless",
+    "seamlessly", "appropriate", "adequate", "reasonable", "sufficient", "as soon as possible",
+    "asap", "etc.", "and so on", "if possible", "where possible", "as needed", "several", "many",
+    "few", "various", "minimal", "optimal", "state-of-the-art", "normally", "usually",
+    "typically", "tbd",
+];
+
+const BE_VERBS: &[&str] = &["be", "is", "are", "was", "were", "been", "being"];
+
+/// Common past participles not ending in "-ed".
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "sent", "shown", "made", "done", "given", "written", "kept", "built", "held", "known",
+    "seen", "taken", "chosen", "found", "paid", "sold", "told", "hidden", "drawn", "thrown",
+    "brought", "bought", "caught", "taught", "sought", "left", "lost", "met", "read", "run",
+    "set", "shut", "spent", "split", "put", "cut", "hit", "won", "broken", "frozen", "spoken",
+    "stolen", "driven", "forgotten", "gotten",
+];
+
+/// Words ending in "-ed" that are not participles, and participles that are
+/// normally used as plain adjectives ("staff shall be experienced").
+const NOT_PARTICIPLES: &[&str] = &[
+    "embed", "hundred", "naked", "wicked", "sacred", "rugged", "kindred", "advanced", "detailed",
+    "dedicated", "experienced", "sophisticated", "complicated", "qualified", "skilled", "interested",
+    "varied", "supposed",
+];
+
+/// Checks the wording of a spec's requirements: ambiguous terms, non-functional
+/// requirements without a measurable value, compound requirements, passive
+/// voice without an actor, and duplicates within the spec.
+pub fn lint_requirements(requirements: &[Requirement]) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for (i, req) in requirements.iter().enumerate() {
+        let words = tokenize(&req.description);
+
+        for term in AMBIGUOUS_TERMS {
+            let phrase = tokenize(term);
+            if phrase_positions(&words, &phrase).any(|i| !is_count(&words, i)) {
+                findings.push(finding(
+                    req,
+                    "ambiguous_term",
+                    "warning",
+                    format!("\"{}\" is ambiguous; replace it with a verifiable criterion", term),
+                    Some(term.to_string()),
+                ));
+            }
+        }
+
+        if req.req_type == "non_functional" && !is_measurable(req, requirements) {
+            findings.push(finding(
+                req,
+                "unmeasurable",
+                "warning",
+                "Non-functional requirement has no measurable criterion (e.g. a limit, rate or duration)".into(),
+                None,
+            ));
+        }
+
+        if let Some(excerpt) = compound_excerpt(&req.description, &words) {
+            findings.push(finding(
+                req,
+                "compound",
+                "warning",
+                "Requirement combines several obligations; split it so each can be tested on its own".into(),
+                Some(excerpt),
+            ));
+        }
+
+        if let Some(excerpt) = passive_excerpt(&words) {
+            findings.push(finding(
+                req,
+                "passive_voice",
+                "info",
+                "Passive voice without an actor; state who or what performs the action".into(),
+                Some(excerpt),
+            ));
+        }
+
+        if let Some(original) = requirements[..i]
+            .iter()
+            .find(|other| similarity(&other.description, &req.description) >= DUPLICATE_THRESHOLD)
+        {
+            let label = original.external_id.clone().unwrap_or_else(|| original.description.clone());
+            findings.push(finding(
+                req,
+                "duplicate",
+                "warning",
+                format!("Duplicates \"{}\"", label),
+                Some(original.description.clone()),
+            ));
+        }
+    }
+    findings
+}
+
+fn finding(req: &Requirement, rule: &str, severity: &str, message: String, excerpt: Option<String>) -> LintFinding {
+    LintFinding {
+        id: Uuid::new_v4().to_string(),
+        spec_id: req.spec_id.clone(),
+        requirement_id: req.id.clone(),
+        rule: rule.to_string(),
+        severity: severity.to_string(),
+        message,
+        excerpt,
+        created_at: Utc::now().to_rfc3339(),
+    }
+}
+
+fn phrase_positions<'a>(words: &'a [String], phrase: &'a [String]) -> impl Iterator<Item = usize> + 'a {
+    words
+        .windows(phrase.len().max(1))
+        .enumerate()
+        .filter(move |(_, w)| !phrase.is_empty() && *w == phrase)
+        .map(|(i, _)| i)
+}
+
+/// "many" or "few" asking for or giving a count ("how many", "as many as
+/// 5"), which is not vague.
+fn is_count(words: &[String], i: usize) -> bool {
+    if !matches!(words[i].as_str(), "many" | "few") {
+        return false;
+    }
+    if i > 0 && words[i - 1] == "how" {
+        return true;
+    }
+    let mut rest = words[i + 1..].iter();
+    let next = match rest.next() {
+        Some(w) if w == "as" || w == "than" => rest.next(),
+        w => w,
+    };
+    next.is_some_and(|w| w.parse::<f64>().is_ok())
+}
+
+/// A number in the requirement itself or in one of its sub-items (acceptance
+/// criteria often carry the figures).
+fn is_measurable(req: &Requirement, requirements: &[Requirement]) -> bool {
+    let has_digit = |text: &str| text.chars().any(|c| c.is_ascii_digit());
+    has_digit(&req.description)
+        || requirements
+            .iter()
+            .any(|r| r.parent_id.as_deref() == Some(req.id.as_str()) && has_digit(&r.description))
+}
+
+/// "and/or", or a conjunction between two modal verbs ("shall X and shall Y"):
+/// two requirements sharing a sentence.
+fn compound_excerpt(text: &str, words: &[String]) -> Option<String> {
+    if text.to_lowercase().contains("and/or") {
+        return Some("and/or".to_string());
+    }
+    let first_modal = words.iter().position(|w| MODAL_VERBS.contains(&w.as_str()))?;
+    words.iter().enumerate().skip(first_modal + 1).find_map(|(i, w)| {
+        if w != "and" && w != "or" {
+            return None;
+        }
+        let next = words.get(i + 1)?;
+        MODAL_VERBS.contains(&next.as_str()).then(|| format!("{} {}", w, next))
+    })
+}
+
+/// A form of "to be" followed by a past participle, with no "by" naming the
+/// actor anywhere after it.
+fn passive_excerpt(words: &[String]) -> Option<String> {
+    words.iter().enumerate().find_map(|(i, w)| {
+        if !BE_VERBS.contains(&w.as_str()) {
+            return None;
+        }
+        // Allow one adverb in between: "be automatically archived"
+        let mut j = i + 1;
+        if words.get(j).is_some_and(|w| w.ends_with("ly")) {
+            j += 1;
+        }
+        let participle = words.get(j)?;
+        if !is_participle(participle) || words[j + 1..].iter().any(|w| w == "by") {
+            return None;
+        }
+        Some(words[i..=j].join(" "))
+    })
+}
+
+fn is_participle(word: &str) -> bool {
+    let regular = word.len() > 4 && word.ends_with("ed") && !word.ends_with("eed") && !NOT_PARTICIPLES.contains(&word);
+    regular || IRREGULAR_PARTICIPLES.contains(&word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::spec_parser::parse_spec;
+
+    fn rules_for(description: &str) -> Vec<String> {
+        let content = format!("## Requirements\n\n- {}\n", description);
+        lint_requirements(&parse_spec("spec-1", &content))
+            .into_iter()
+            .map(|f| f.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_ambiguous_terms() {
+        let content = "## Requirements\n\n- The dashboard shall be user-friendly and fast\n- Exports include CSV, PDF, etc.\n- Breakfast orders are listed by the kitchen screen\n";
+        let findings = lint_requirements(&parse_spec("spec-1", content));
+        let terms: Vec<&str> = findings
+            .iter()
+            .filter(|f| f.rule == "ambiguous_term")
+            .filter_map(|f| f.excerpt.as_deref())
+            .collect();
+        assert_eq!(terms, vec!["fast", "user-friendly", "etc."]);
+    }
+
+    #[test]
+    fn test_counted_many_and_few_are_not_ambiguous() {
+        assert!(!rules_for("The cart shows how many items it holds").contains(&"ambiguous_term".to_string()));
+        assert!(!rules_for("Carts hold as many as 50 items").contains(&"ambiguous_term".to_string()));
+        assert!(!rules_for("Orders with as few as 2 items ship free").contains(&"ambiguous_term".to_string()));
+        assert!(rules_for("Carts hold many items").contains(&"ambiguous_term".to_string()));
+    }
+
+    #[test]
+    fn test_unmeasurable_non_functional() {
+        let content = "## Non-Functional Requirements\n\n- Search results shall load promptly\n- Pages shall load within 200 ms\n- Reports shall render promptly\n  - p95 render time under 2 seconds\n";
+        let reqs = parse_spec("spec-1", content);
+        assert!(reqs.iter().all(|r| r.req_type == "non_functional"));
+        let flagged: Vec<String> = lint_requirements(&reqs)
+            .into_iter()
+            .filter(|f| f.rule == "unmeasurable")
+            .map(|f| f.requirement_id)
+            .collect();
+        assert_eq!(flagged, vec![reqs[0].id.clone()]);
+    }
+
+    #[test]
+    fn test_compound_requirements() {
+        assert!(rules_for("Users can export and/or print invoices").contains(&"compound".to_string()));
+        assert!(rules_for("The system shall log in users and shall send a welcome email").contains(&"compound".to_string()));
+        assert!(!rules_for("Admins and auditors shall view the audit log").contains(&"compound".to_string()));
+    }
+
+    #[test]
+    fn test_passive_voice_without_actor() {
+        let content = "## Requirements\n\n- Invoices shall be automatically generated monthly\n- Invoices are approved by a finance manager\n- The system shall send reminders\n";
+        let findings = lint_requirements(&parse_spec("spec-1", content));
+        let passive: Vec<&str> = findings
+            .iter()
+            .filter(|f| f.rule == "passive_voice")
+            .filter_map(|f| f.excerpt.as_deref())
+            .collect();
+        assert_eq!(passive, vec!["be automatically generated"]);
+        assert!(findings.iter().all(|f| f.rule != "passive_voice" || f.severity == "info"));
+    }
+
+    #[test]
+    fn test_adjectives_and_ed_words_are_not_passive() {
+        assert!(!rules_for("Support staff shall be experienced").contains(&"passive_voice".to_string()));
+        assert!(rules_for("Payments shall be refunded").contains(&"passive_voice".to_string()));
+        for word in ["speed", "exceed", "need", "embed", "hundred"] {
+            assert!(!is_participle(word), "{}", word);
+        }
+        assert!(is_participle("exceeded"));
+    }
+
+    #[test]
+    fn test_duplicates_flag_the_later_requirement() {
+        let content = "## Billing\n\n- The system shall email the invoice to the customer\n- The system shall email the invoice to the customer.\n- The system shall archive invoices\n";
+        let reqs = parse_spec("spec-1", content);
+        let duplicates: Vec<LintFinding> = lint_requirements(&reqs)
+            .into_iter()
+            .filter(|f| f.rule == "duplicate")
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].requirement_id, reqs[1].id);
+        assert_eq!(duplicates[0].excerpt.as_deref(), Some(reqs[0].description.as_str()));
+    }
+}
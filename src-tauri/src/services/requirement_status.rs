@@ -0,0 +1,99 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use uuid::Uuid;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::spec::{Requirement, RequirementStatusChange};
+
+pub const STATUSES: &[&str] = &["draft", "approved", "deprecated", "waived"];
+
+/// Whether a requirement with this status is left out of coverage.
+pub fn is_excluded(status: &str) -> bool {
+    matches!(status, "waived" | "deprecated")
+}
+
+/// Statuses a requirement may move to from `from`. Deprecation is final
+/// except for sending the requirement back to draft.
+fn allowed_transitions(from: &str) -> &'static [&'static str] {
+    match from {
+        "draft" => &["approved", "deprecated", "waived"],
+        "approved" => &["draft", "deprecated", "waived"],
+        "waived" => &["draft", "approved", "deprecated"],
+        "deprecated" => &["draft"],
+        _ => &["draft"],
+    }
+}
+
+/// Checks a status change; waiving a requirement needs a reason.
+pub fn validate_transition(from: &str, to: &str, reason: Option<&str>) -> Result<(), AppError> {
+    if !STATUSES.contains(&to) {
+        return Err(AppError::InvalidInput(format!("Unknown requirement status: {}", to)));
+    }
+    if !allowed_transitions(from).contains(&to) {
+        return Err(AppError::InvalidInput(format!("Cannot change requirement status from {} to {}", from, to)));
+    }
+    if to == "waived" && reason.is_none_or(|r| r.trim().is_empty()) {
+        return Err(AppError::InvalidInput("A reason is required to waive a requirement".into()));
+    }
+    Ok(())
+}
+
+/// Moves a requirement to a new status and records who did it and why.
+/// Callers are expected to wrap this in a transaction.
+pub fn set_status(
+    conn: &Connection,
+    requirement_id: &str,
+    status: &str,
+    changed_by: &str,
+    reason: Option<&str>,
+) -> Result<Requirement, AppError> {
+    let requirement = queries::get_requirement(conn, requirement_id)?;
+    validate_transition(&requirement.status, status, reason)?;
+
+    queries::update_requirement_status(conn, requirement_id, status)?;
+    queries::insert_requirement_status_change(
+        conn,
+        &RequirementStatusChange {
+            id: Uuid::new_v4().to_string(),
+            requirement_id: requirement_id.to_string(),
+            from_status: requirement.status,
+            to_status: status.to_string(),
+            changed_by: changed_by.to_string(),
+            reason: reason.map(str::trim).filter(|r| !r.is_empty()).map(str::to_string),
+            changed_at: Utc::now().to_rfc3339(),
+        },
+    )?;
+    queries::get_requirement(conn, requirement_id)
+}
+
+/// The OS account running the app, used as the author of changes when the
+/// caller names none.
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|u| !u.trim().is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        assert!(validate_transition("draft", "approved", None).is_ok());
+        assert!(validate_transition("approved", "deprecated", None).is_ok());
+        assert!(validate_transition("deprecated", "draft", None).is_ok());
+        assert!(validate_transition("deprecated", "approved", None).is_err());
+        assert!(validate_transition("draft", "draft", None).is_err());
+        assert!(validate_transition("draft", "retired", None).is_err());
+    }
+
+    #[test]
+    fn test_waiving_needs_a_reason() {
+        assert!(validate_transition("approved", "waived", None).is_err());
+        assert!(validate_transition("approved", "waived", Some("  ")).is_err());
+        assert!(validate_transition("approved", "waived", Some("Covered by vendor certification")).is_ok());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::text_blocks::{collect_requirements, split_lines, strip_inline_markup, Line, PendingBlock, TextBlock};
+
+const ADORNMENT_CHARS: &str = "=-~^\"'`#*+:._";
+
+const BULLETS: &[char] = &['-', '*', '+', '•', '‣', '⁃'];
+
+/// Parses a reStructuredText (Sphinx) spec. Section titles are read from
+/// their underlines, bullet and enumerated lists nest by indentation, and
+/// directives, comments and literal blocks are skipped.
+pub fn parse_rst(spec_id: &str, content: &str, rules: &ParserRules) -> Vec<Requirement> {
+    collect_requirements(spec_id, content, rules, rst_blocks(content))
+}
+
+/// List item being read: marker indent, indent of its text, and whether a
+/// blank line has been seen since its last line.
+struct OpenItem {
+    indent: usize,
+    text_indent: usize,
+    after_blank: bool,
+}
+
+fn rst_blocks(content: &str) -> Vec<TextBlock> {
+    let lines = split_lines(content);
+    let mut blocks = Vec::new();
+    let mut pending: Option<PendingBlock> = None;
+    let mut open_item: Option<OpenItem> = None;
+    // Marker indents of the open lists, outermost first
+    let mut list_indents: Vec<usize> = Vec::new();
+    // Lines indented deeper than this belong to a skipped directive or literal block
+    let mut skip_deeper_than: Option<usize> = None;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(limit) = skip_deeper_than {
+            if line.is_blank() || line.indent() > limit {
+                i += 1;
+                continue;
+            }
+            skip_deeper_than = None;
+        }
+        i += 1;
+
+        if line.is_blank() {
+            // Items continue across blank lines; paragraphs and `::` openers end
+            let item_continues = pending.as_ref().is_some_and(|p| p.depth.is_some() && !ends_literal(p));
+            match open_item.as_mut() {
+                Some(item) if item_continues => item.after_blank = true,
+                _ => skip_deeper_than = flush(&mut pending, &mut open_item, &mut blocks).or(skip_deeper_than),
+            }
+            continue;
+        }
+        let trimmed = line.text.trim();
+        let indent = line.indent();
+
+        if let Some((title, consumed)) = section_title(&lines[i - 1..]) {
+            flush(&mut pending, &mut open_item, &mut blocks);
+            list_indents.clear();
+            blocks.push(TextBlock::Heading(strip_inline_markup(title)));
+            i += consumed - 1;
+            continue;
+        }
+        if trimmed == ".." || trimmed.starts_with(".. ") {
+            flush(&mut pending, &mut open_item, &mut blocks);
+            skip_deeper_than = Some(indent);
+            continue;
+        }
+        if indent == 0 && is_adornment(trimmed) {
+            // Transition
+            flush(&mut pending, &mut open_item, &mut blocks);
+            continue;
+        }
+        if let Some(text) = strip_list_marker(trimmed) {
+            if let Some(skip) = flush(&mut pending, &mut open_item, &mut blocks) {
+                // A literal block follows its `::` opener, so this item starts after it
+                if indent > skip {
+                    skip_deeper_than = Some(skip);
+                    continue;
+                }
+            }
+            while list_indents.last().is_some_and(|&l| l >= indent) {
+                list_indents.pop();
+            }
+            let depth = list_indents.len();
+            list_indents.push(indent);
+            open_item = Some(OpenItem {
+                indent,
+                text_indent: indent + trimmed.len() - text.len(),
+                after_blank: false,
+            });
+            pending = Some(PendingBlock::item(depth, line, text));
+            continue;
+        }
+
+        let continues = match (&pending, &open_item) {
+            (Some(_), Some(item)) => indent >= item.text_indent || (!item.after_blank && indent > item.indent),
+            (Some(_), None) => true,
+            _ => false,
+        };
+        if continues {
+            if let Some(p) = pending.as_mut() {
+                p.push_line(line);
+            }
+            if let Some(item) = open_item.as_mut() {
+                item.after_blank = false;
+            }
+            continue;
+        }
+
+        if let Some(skip) = flush(&mut pending, &mut open_item, &mut blocks) {
+            if indent > skip {
+                skip_deeper_than = Some(skip);
+                continue;
+            }
+        }
+        while list_indents.last().is_some_and(|&l| l >= indent) {
+            list_indents.pop();
+        }
+        pending = Some(PendingBlock::paragraph(line));
+    }
+    flush(&mut pending, &mut open_item, &mut blocks);
+
+    blocks
+}
+
+/// Emits the pending block. When it ends with `::` the indented block after
+/// it is literal text; returns the indent deeper than which lines are skipped.
+fn flush(
+    pending: &mut Option<PendingBlock>,
+    open_item: &mut Option<OpenItem>,
+    blocks: &mut Vec<TextBlock>,
+) -> Option<usize> {
+    let item = open_item.take();
+    let mut block = pending.take()?;
+    let literal = ends_literal(&block);
+    if let Some(last) = block.lines.last_mut() {
+        // `Example::` reads as `Example:`; a lone ` ::` disappears
+        if let Some(text) = last.strip_suffix(" ::") {
+            *last = text.to_string();
+        } else if last.ends_with("::") {
+            last.pop();
+        }
+    }
+    blocks.push(block.into_block());
+    literal.then(|| item.map_or(0, |i| i.indent))
+}
+
+fn ends_literal(block: &PendingBlock) -> bool {
+    block.lines.last().is_some_and(|l| l.ends_with("::"))
+}
+
+/// Title text and number of lines used, for an underlined or over-and-underlined title.
+fn section_title<'a>(lines: &[Line<'a>]) -> Option<(&'a str, usize)> {
+    let first = lines.first()?.text.trim();
+    let second = lines.get(1)?.text.trim();
+    if is_adornment(first) {
+        let third = lines.get(2)?.text.trim();
+        let title = second;
+        return (!title.is_empty() && is_adornment(third) && third.starts_with(&first[..1])).then_some((title, 3));
+    }
+    let title_width = first.chars().count();
+    (lines[0].indent() == 0
+        && lines[1].indent() == 0
+        && is_adornment(second)
+        && second.chars().count() >= title_width.min(3))
+        .then_some((first, 2))
+}
+
+/// A line made of one repeated punctuation character, e.g. `=====`.
+fn is_adornment(text: &str) -> bool {
+    let mut chars = text.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    text.len() >= 2 && ADORNMENT_CHARS.contains(first) && chars.all(|c| c == first)
+}
+
+/// Text after a bullet (`-`, `*`, `+`) or enumerator (`1.`, `1)`, `(1)`, `#.`).
+fn strip_list_marker(text: &str) -> Option<&str> {
+    let mut chars = text.chars();
+    let first = chars.next()?;
+    let rest = if BULLETS.contains(&first) {
+        chars.as_str()
+    } else {
+        let (inner, parenthesized) = match text.strip_prefix('(') {
+            Some(inner) => (inner, true),
+            None => (text, false),
+        };
+        let number_len = if inner.starts_with('#') {
+            1
+        } else {
+            inner.chars().take_while(|c| c.is_ascii_digit()).count()
+        };
+        if number_len == 0 {
+            return None;
+        }
+        let after = &inner[number_len..];
+        match (parenthesized, after.chars().next()) {
+            (_, Some(')')) | (false, Some('.')) => &after[1..],
+            _ => return None,
+        }
+    };
+    if rest.is_empty() {
+        Some(rest)
+    } else if rest.starts_with(char::is_whitespace) {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<Requirement> {
+        parse_rst("spec-1", content, ParserRules::builtin())
+    }
+
+    #[test]
+    fn test_parse_rst_sections_and_lists() {
+        let content = r#"==============
+Billing System
+==============
+
+Functional Requirements
+=======================
+
+- The system shall generate invoices
+  every month
+- Users can download invoices as **PDF**
+
+  #. PDFs include the company logo
+  #. PDFs are A4
+
+Background
+----------
+
+* Legacy billing ran on a mainframe
+
+Notes
+-----
+
+The system must retain invoices for seven years.
+
+.. note::
+
+   The system shall ignore this directive body.
+
+Example::
+
+    - The system shall not be read from a literal block
+"#;
+        let reqs = parse(content);
+        let descriptions: Vec<&str> = reqs.iter().map(|r| r.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "The system shall generate invoices every month",
+                "Users can download invoices as PDF",
+                "PDFs include the company logo",
+                "PDFs are A4",
+                "The system must retain invoices for seven years.",
+            ]
+        );
+        assert_eq!(reqs[0].section, "Functional Requirements");
+        assert_eq!(reqs[2].parent_id.as_deref(), Some(reqs[1].id.as_str()));
+        assert_eq!(reqs[3].parent_id.as_deref(), Some(reqs[1].id.as_str()));
+        assert_eq!(reqs[4].section, "Notes");
+    }
+
+    #[test]
+    fn test_rst_spans_and_external_ids() {
+        let content = "Requirements\n============\n\n- [BIL-1] Invoices are\n  generated monthly\n";
+        let reqs = parse(content);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("BIL-1"));
+        let span = reqs[0].span.as_ref().unwrap();
+        assert_eq!(&content[span.start_offset as usize..span.end_offset as usize], "- [BIL-1] Invoices are\n  generated monthly");
+        assert_eq!((span.start_line, span.end_line), (4, 5));
+    }
+
+    #[test]
+    fn test_strip_list_marker() {
+        assert_eq!(strip_list_marker("- item"), Some("item"));
+        assert_eq!(strip_list_marker("12. item"), Some("item"));
+        assert_eq!(strip_list_marker("(3) item"), Some("item"));
+        assert_eq!(strip_list_marker("#. item"), Some("item"));
+        assert_eq!(strip_list_marker("-item"), None);
+        assert_eq!(strip_list_marker("2024 was a year"), None);
+    }
+}
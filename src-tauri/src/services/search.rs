@@ -0,0 +1,112 @@
+use rusqlite::Connection;
+use crate::db::queries::{self, MATCH_END, MATCH_START};
+use crate::errors::AppError;
+use crate::models::search::{SearchHit, SearchResults};
+
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// Searches a project's specs, requirements and generated tests. Every word
+/// must match, the last one as a prefix so results follow typing.
+pub fn search(conn: &Connection, project_id: &str, query: &str, limit: usize) -> Result<SearchResults, AppError> {
+    let fts_query = fts_query(query).ok_or_else(|| AppError::InvalidInput("Search query cannot be empty".into()))?;
+    let hits = |entity_type: &str| -> Result<Vec<SearchHit>, AppError> {
+        Ok(queries::search_documents(conn, project_id, entity_type, &fts_query, limit)?
+            .into_iter()
+            .map(|hit| SearchHit { title: mark_up(&hit.title), snippet: mark_up(&hit.snippet), ..hit })
+            .collect())
+    };
+    Ok(SearchResults {
+        specs: hits("spec")?,
+        requirements: hits("requirement")?,
+        tests: hits("test")?,
+    })
+}
+
+/// Turns free text into an FTS5 query: each word quoted so punctuation and
+/// operators ("AND", "-", "*") are taken literally.
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<&str> = query.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| !w.is_empty()).collect();
+    let (last, rest) = words.split_last()?;
+    let mut terms: Vec<String> = rest.iter().map(|w| format!("\"{}\"", w)).collect();
+    terms.push(format!("\"{}\"*", last));
+    Some(terms.join(" "))
+}
+
+/// Escapes highlighted text for HTML and turns match markers into `<mark>`.
+fn mark_up(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            MATCH_START => html.push_str("<mark>"),
+            MATCH_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::CreateProjectRequest;
+    use crate::models::test::GeneratedTest;
+    use crate::services::spec_sync;
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("login flow").as_deref(), Some("\"login\" \"flow\"*"));
+        assert_eq!(fts_query("REQ-12 AND \"x").as_deref(), Some("\"REQ\" \"12\" \"AND\" \"x\"*"));
+        assert_eq!(fts_query("  -- "), None);
+    }
+
+    #[test]
+    fn test_repaired_tests_replace_earlier_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::run_migrations(&conn).unwrap();
+        let project = queries::create_project(
+            &conn,
+            &CreateProjectRequest { name: "project".to_string(), codebase_path: "/tmp".to_string() },
+        )
+        .unwrap();
+        let content = "## Requirements\n\n- Users shall log in\n";
+        let parsed = spec_sync::save_spec(&conn, &project.id, "auth.md", content).unwrap();
+        let first = GeneratedTest {
+            id: "test-1".to_string(),
+            requirement_id: parsed.requirements[0].id.clone(),
+            framework: "jest".to_string(),
+            code: "test('loginFlow', () => {});".to_string(),
+            generation_mode: "llm".to_string(),
+            file_path: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            version: 1,
+            previous_version_id: None,
+            repair_outcome: None,
+        };
+        let repaired = GeneratedTest {
+            id: "test-2".to_string(),
+            version: 2,
+            previous_version_id: Some(first.id.clone()),
+            ..first.clone()
+        };
+        queries::insert_generated_test(&conn, &first).unwrap();
+        queries::insert_generated_test(&conn, &repaired).unwrap();
+
+        let ids: Vec<String> = search(&conn, &project.id, "loginFlow", DEFAULT_LIMIT)
+            .unwrap()
+            .tests
+            .into_iter()
+            .map(|hit| hit.entity_id)
+            .collect();
+        assert_eq!(ids, vec!["test-2"]);
+    }
+
+    #[test]
+    fn test_mark_up() {
+        let text = format!("if a < b {}login{}", MATCH_START, MATCH_END);
+        assert_eq!(mark_up(&text), "if a &lt; b <mark>login</mark>");
+    }
+}
@@ -0,0 +1,29 @@
+use crate::errors::AppError;
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::{asciidoc_parser, csv_parser, gherkin_parser, reqif_parser, rst_parser, spec_parser};
+
+/// Parses a spec with the parser matching its file extension, falling back
+/// to Markdown for anything unrecognised. Only structured exports (ReqIF,
+/// CSV) can be rejected as malformed; prose formats always parse.
+pub fn parse_spec_file(
+    spec_id: &str,
+    filename: &str,
+    content: &str,
+    rules: &ParserRules,
+) -> Result<Vec<Requirement>, AppError> {
+    let ext = std::path::Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let requirements = match ext.as_str() {
+        "feature" => gherkin_parser::parse_feature(spec_id, content, rules),
+        "rst" | "rest" => rst_parser::parse_rst(spec_id, content, rules),
+        "adoc" | "asciidoc" | "asc" => asciidoc_parser::parse_asciidoc(spec_id, content, rules),
+        "reqif" => reqif_parser::parse_reqif(spec_id, content, rules)?,
+        "csv" => csv_parser::parse_csv(spec_id, content, rules)?,
+        _ => spec_parser::parse_spec_with_rules(spec_id, content, rules),
+    };
+    Ok(requirements)
+}
@@ -1,134 +1,536 @@
-use pulldown_cmark::{Parser, Event, Tag, TagEnd, HeadingLevel};
+use std::collections::HashMap;
+use std::ops::Range;
+use pulldown_cmark::{Parser, Options, Event, Tag, TagEnd};
 use uuid::Uuid;
-use crate::models::spec::Requirement;
+use crate::models::spec::{Requirement, SourceSpan, SpecMetadata};
+use crate::services::detection_rules::ParserRules;
+use crate::services::{front_matter, tags, thresholds};
 
+// Namespace for content-derived requirement IDs; must never change, or every
+// requirement would get a new identity on the next re-parse.
+const REQUIREMENT_NAMESPACE: Uuid = Uuid::from_u128(0x5c1d_7a2e_4b0f_4e59_9a63_0d8e_2f41_b7c6);
+
+/// Accumulates parsed requirements, assigning each a stable ID. Shared by
+/// every spec format parser so IDs are derived the same way everywhere.
+pub struct RequirementCollector<'a> {
+    spec_id: &'a str,
+    rules: &'a ParserRules,
+    requirements: Vec<Requirement>,
+    occurrences: HashMap<String, usize>,
+    default_req_type: Option<String>,
+    default_priority: Option<String>,
+}
+
+impl<'a> RequirementCollector<'a> {
+    pub fn new(spec_id: &'a str) -> Self {
+        Self {
+            spec_id,
+            rules: ParserRules::builtin(),
+            requirements: Vec::new(),
+            occurrences: HashMap::new(),
+            default_req_type: None,
+            default_priority: None,
+        }
+    }
+
+    /// Classifies requirements with a project's own detection rules.
+    pub fn with_rules(mut self, rules: &'a ParserRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> &'a ParserRules {
+        self.rules
+    }
+
+    /// Uses the spec's front-matter defaults for requirements whose wording
+    /// doesn't imply a type or priority.
+    pub fn with_defaults(mut self, metadata: &SpecMetadata) -> Self {
+        self.default_req_type = metadata.default_req_type.clone();
+        self.default_priority = metadata.default_priority.clone();
+        self
+    }
+
+    /// Adds a requirement, classifying type and priority from the text unless
+    /// given, and returns it so format-specific fields can be filled in.
+    /// Inline `#tag` tokens become the requirement's tags; the ID is derived
+    /// from the text as written, tags included, as it was before tags existed.
+    pub fn push(
+        &mut self,
+        section: &str,
+        external_id: Option<String>,
+        description: String,
+        req_type: Option<String>,
+        priority: Option<String>,
+    ) -> &mut Requirement {
+        let key = identity_key(external_id.as_deref(), section, &description);
+        let (description, tags) = tags::extract_inline_tags(&description);
+        let req_type = req_type
+            .or_else(|| self.rules.detect_requirement_type(section, &description).map(str::to_string))
+            .or_else(|| self.default_req_type.clone())
+            .unwrap_or_else(|| "functional".to_string());
+        let (priority, priority_keyword) = match priority {
+            Some(priority) => (priority, None),
+            None => match self.rules.detect_priority(&description) {
+                Some((detected, keyword)) => (detected.to_string(), Some(keyword)),
+                None => (
+                    self.default_priority.clone().unwrap_or_else(|| "medium".to_string()),
+                    None,
+                ),
+            },
+        };
+        let threshold = if req_type == "non_functional" { thresholds::parse_threshold(&description) } else { None };
+        let occurrence = self.occurrences.entry(key.clone()).or_insert(0);
+        let id = stable_requirement_id(self.spec_id, &key, *occurrence);
+        *occurrence += 1;
+        self.requirements.push(Requirement {
+            id,
+            spec_id: self.spec_id.to_string(),
+            parent_id: None,
+            external_id,
+            section: section.to_string(),
+            description,
+            req_type,
+            priority,
+            priority_keyword,
+            change_status: "new".to_string(),
+            status: "draft".to_string(),
+            manually_edited: false,
+            retired_at: None,
+            scenario: None,
+            span: None,
+            threshold,
+            tags,
+        });
+        self.requirements.last_mut().expect("requirement was just pushed")
+    }
+
+    pub fn finish(self) -> Vec<Requirement> {
+        self.requirements
+    }
+}
+
+/// Column layout of a requirements table (Markdown table, CSV export),
+/// detected from its header row.
+#[derive(Default)]
+pub struct TableColumns {
+    pub id: Option<usize>,
+    pub description: Option<usize>,
+    pub section: Option<usize>,
+    pub req_type: Option<usize>,
+    pub priority: Option<usize>,
+}
+
+impl TableColumns {
+    pub fn from_header(cells: &[String]) -> Self {
+        let mut columns = TableColumns::default();
+        for (i, cell) in cells.iter().enumerate() {
+            let lower = cell.trim().to_lowercase();
+            if matches!(lower.as_str(), "id" | "#" | "ref" | "req id" | "requirement id" | "req. id") {
+                columns.id.get_or_insert(i);
+            } else if lower.contains("priority") || lower.contains("moscow") {
+                columns.priority.get_or_insert(i);
+            } else if lower == "type" || lower.contains("category") {
+                columns.req_type.get_or_insert(i);
+            } else if lower == "section" || lower == "area" {
+                columns.section.get_or_insert(i);
+            } else if lower.contains("requirement")
+                || lower.contains("description")
+                || lower.contains("statement")
+                || lower.contains("criteria")
+                || lower == "text"
+            {
+                columns.description.get_or_insert(i);
+            }
+        }
+        columns
+    }
+}
+
+/// Parses with the built-in rules; specs are parsed with their project's.
+#[cfg(test)]
 pub fn parse_spec(spec_id: &str, content: &str) -> Vec<Requirement> {
-    let parser = Parser::new(content);
-    let mut requirements = Vec::new();
+    parse_spec_with_rules(spec_id, content, ParserRules::builtin())
+}
+
+pub fn parse_spec_with_rules(spec_id: &str, content: &str, rules: &ParserRules) -> Vec<Requirement> {
+    let (metadata, body) = front_matter::split_front_matter(content);
+    // The body is a suffix of the content; spans are reported against the whole file
+    let lines = LineIndex::new(content, content.len() - body.len());
+    let parser = Parser::new_ext(body, Options::ENABLE_TABLES).into_offset_iter();
+    let mut collector = RequirementCollector::new(spec_id).with_rules(rules).with_defaults(&metadata);
     let mut current_section = String::from("General");
     let mut in_heading = false;
     let mut heading_text = String::new();
-    let mut in_list_item = false;
-    let mut list_item_text = String::new();
+    // Open list items, outermost first
+    let mut items: Vec<ListItem> = Vec::new();
     let mut is_requirement_section = false;
+    // (level, is requirement-like) for each open heading, outermost first
+    let mut heading_stack: Vec<(usize, bool)> = Vec::new();
+    let mut in_paragraph = false;
+    let mut paragraph_text = String::new();
+    let mut paragraph_range = 0..0;
+    // Body of a numbered heading (`### 3.2.1 Login`) not already captured as a requirement
+    let mut heading_body: Option<HeadingBody> = None;
+    let mut in_table_head = false;
+    let mut in_cell = false;
+    let mut cell_text = String::new();
+    let mut row_cells: Vec<String> = Vec::new();
+    let mut row_range = 0..0;
+    let mut table_columns: Option<TableColumns> = None;
 
-    for event in parser {
+    for (event, range) in parser {
         match event {
             Event::Start(Tag::Heading { level, .. }) => {
+                flush_heading_body(&mut collector, &current_section, heading_body.take(), &lines);
                 in_heading = true;
                 heading_text.clear();
-                // Top-level headings reset section tracking
-                if matches!(level, HeadingLevel::H1 | HeadingLevel::H2 | HeadingLevel::H3) {
-                    // will set section when heading ends
+                let depth = level as usize;
+                while heading_stack.last().is_some_and(|(l, _)| *l >= depth) {
+                    heading_stack.pop();
                 }
             }
-            Event::End(TagEnd::Heading(_)) => {
+            Event::End(TagEnd::Heading(level)) => {
                 in_heading = false;
                 current_section = heading_text.trim().to_string();
-                is_requirement_section = is_requirement_like_section(&current_section);
+                is_requirement_section = rules.is_requirement_section(&current_section);
+                let in_requirement_context = is_requirement_section
+                    || heading_stack.iter().any(|(_, req_like)| *req_like);
+                heading_stack.push((level as usize, is_requirement_section));
+                if in_requirement_context && is_numbered_heading(&current_section) {
+                    heading_body = Some(HeadingBody::default());
+                }
             }
-            Event::Start(Tag::Item) => {
-                in_list_item = true;
-                list_item_text.clear();
+            Event::Start(Tag::Item) => items.push(ListItem {
+                start: range.start,
+                end: range.start,
+                ..Default::default()
+            }),
+            // A nested list closes the parent's own text, so emit the parent
+            // first and let its sub-items point back at it
+            Event::Start(Tag::List(_)) if !items.is_empty() => {
+                let depth = items.len() - 1;
+                if !items[depth].done {
+                    let parent_id = parent_requirement_id(&items[..depth]);
+                    let item = &mut items[depth];
+                    item.done = true;
+                    item.requirement_id = emit_list_item(
+                        &mut collector,
+                        &current_section,
+                        is_requirement_section,
+                        item,
+                        parent_id,
+                        &lines,
+                    );
+                }
             }
             Event::End(TagEnd::Item) => {
-                in_list_item = false;
-                let text = list_item_text.trim().to_string();
-                if !text.is_empty() && (is_requirement_section || looks_like_requirement(&text)) {
-                    let req_type = classify_requirement_type(&current_section, &text);
-                    let priority = classify_priority(&text);
-                    requirements.push(Requirement {
-                        id: Uuid::new_v4().to_string(),
-                        spec_id: spec_id.to_string(),
-                        section: current_section.clone(),
-                        description: text,
-                        req_type,
-                        priority,
-                    });
+                if let Some(item) = items.pop() {
+                    if !item.done {
+                        let parent_id = parent_requirement_id(&items);
+                        emit_list_item(
+                            &mut collector,
+                            &current_section,
+                            is_requirement_section,
+                            &item,
+                            parent_id,
+                            &lines,
+                        );
+                    }
+                }
+            }
+            Event::Start(Tag::Paragraph) if items.is_empty() => {
+                in_paragraph = true;
+                paragraph_text.clear();
+                paragraph_range = range;
+            }
+            Event::End(TagEnd::Paragraph) if in_paragraph => {
+                in_paragraph = false;
+                let (external_id, text) = split_external_id(paragraph_text.trim());
+                if text.is_empty() {
+                    continue;
+                }
+                if external_id.is_some() || rules.looks_like_requirement(&text) || rules.has_normative_keyword(&text) {
+                    let req = collector.push(&current_section, external_id, text, None, None);
+                    req.span = Some(lines.span(paragraph_range.clone()));
+                    // The heading's requirement is stated explicitly; don't also add the body
+                    heading_body = None;
+                } else if let Some(body) = heading_body.as_mut() {
+                    body.text.push(text);
+                    body.start.get_or_insert(paragraph_range.start);
+                    body.end = paragraph_range.end;
+                }
+            }
+            Event::Start(Tag::TableHead) => {
+                in_table_head = true;
+                row_cells.clear();
+            }
+            Event::End(TagEnd::TableHead) => {
+                in_table_head = false;
+                let columns = TableColumns::from_header(&row_cells);
+                table_columns = columns.description.is_some().then_some(columns);
+            }
+            Event::Start(Tag::TableRow) => {
+                row_cells.clear();
+                row_range = range;
+            }
+            Event::End(TagEnd::TableRow) if !in_table_head => {
+                if let Some(columns) = &table_columns {
+                    if let Some(req) = push_table_row(&mut collector, &current_section, columns, &row_cells) {
+                        req.span = Some(lines.span(row_range.clone()));
+                    }
                 }
             }
-            Event::Text(text) => {
+            Event::End(TagEnd::Table) => table_columns = None,
+            Event::Start(Tag::TableCell) => {
+                in_cell = true;
+                cell_text.clear();
+            }
+            Event::End(TagEnd::TableCell) => {
+                in_cell = false;
+                row_cells.push(cell_text.trim().to_string());
+            }
+            Event::Text(text) | Event::Code(text) => {
                 if in_heading {
                     heading_text.push_str(&text);
-                } else if in_list_item {
-                    list_item_text.push_str(&text);
+                } else if in_cell {
+                    cell_text.push_str(&text);
+                } else if let Some(item) = items.last_mut() {
+                    if !item.done {
+                        item.text.push_str(&text);
+                        item.end = range.end;
+                    }
+                } else if in_paragraph {
+                    paragraph_text.push_str(&text);
                 }
             }
-            Event::Code(code) => {
-                if in_heading {
-                    heading_text.push_str(&code);
-                } else if in_list_item {
-                    list_item_text.push_str(&code);
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(item) = items.last_mut() {
+                    item.text.push(' ');
+                } else if in_paragraph {
+                    paragraph_text.push(' ');
                 }
             }
             _ => {}
         }
     }
+    flush_heading_body(&mut collector, &current_section, heading_body, &lines);
 
-    requirements
-}
-
-fn is_requirement_like_section(section: &str) -> bool {
-    let lower = section.to_lowercase();
-    lower.contains("requirement")
-        || lower.contains("user stor")
-        || lower.contains("feature")
-        || lower.contains("functional")
-        || lower.contains("specification")
-        || lower.contains("capability")
-        || lower.contains("constraint")
-        || lower.contains("acceptance criteria")
-        || lower.contains("use case")
-}
-
-fn looks_like_requirement(text: &str) -> bool {
-    let lower = text.to_lowercase();
-    // Explicit requirement patterns
-    lower.starts_with("as a ")
-        || lower.starts_with("the system shall ")
-        || lower.starts_with("the system must ")
-        || lower.starts_with("the application shall ")
-        || lower.starts_with("the application must ")
-        || lower.starts_with("shall ")
-        || lower.starts_with("must ")
-        || lower.contains("**shall**")
-        || lower.contains("**must**")
-        // Bold text only counts if it contains enough words to be a real requirement
-        || (text.starts_with("**") && text.contains(' ') && lower.split_whitespace().count() >= 5)
-}
-
-fn classify_requirement_type(section: &str, text: &str) -> String {
-    let lower_section = section.to_lowercase();
-    let lower_text = text.to_lowercase();
-
-    if lower_section.contains("non-functional")
-        || lower_section.contains("performance")
-        || lower_section.contains("security")
-        || lower_section.contains("scalability")
-        || lower_text.contains("performance")
-        || lower_text.contains("latency")
-        || lower_text.contains("availability")
-    {
-        "non_functional".to_string()
-    } else if lower_section.contains("constraint")
-        || lower_text.contains("constraint")
-        || lower_text.contains("limitation")
+    collector.finish()
+}
+
+/// A list item being read. Once a nested list starts, the item's own text is
+/// complete: it is emitted early (`done`) so sub-items can reference it.
+#[derive(Default)]
+struct ListItem {
+    text: String,
+    /// Byte range of the marker through the item's own text
+    start: usize,
+    end: usize,
+    done: bool,
+    requirement_id: Option<String>,
+}
+
+#[derive(Default)]
+struct HeadingBody {
+    text: Vec<String>,
+    start: Option<usize>,
+    end: usize,
+}
+
+/// Maps byte offsets of the parsed body to offsets and 1-based line numbers
+/// in the full spec.
+pub struct LineIndex {
+    base: usize,
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(content: &str, base: usize) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { base, line_starts }
+    }
+
+    pub fn span(&self, range: Range<usize>) -> SourceSpan {
+        let start = self.base + range.start;
+        let end = self.base + range.end;
+        SourceSpan {
+            start_offset: start as i64,
+            end_offset: end as i64,
+            start_line: self.line_of(start),
+            // The end is exclusive, and usually just past a newline
+            end_line: self.line_of(end.saturating_sub(1).max(start)),
+        }
+    }
+
+    fn line_of(&self, offset: usize) -> i64 {
+        self.line_starts.partition_point(|&s| s <= offset) as i64
+    }
+}
+
+/// ID of the innermost enclosing item that became a requirement.
+fn parent_requirement_id(items: &[ListItem]) -> Option<String> {
+    items.iter().rev().find_map(|item| item.requirement_id.clone())
+}
+
+/// Emits a list item as a requirement if it qualifies. Sub-items of a
+/// requirement always qualify: they are its acceptance criteria.
+fn emit_list_item(
+    collector: &mut RequirementCollector,
+    section: &str,
+    is_requirement_section: bool,
+    item: &ListItem,
+    parent_id: Option<String>,
+    lines: &LineIndex,
+) -> Option<String> {
+    let (external_id, text) = split_external_id(item.text.trim());
+    if text.is_empty()
+        || !(external_id.is_some()
+            || parent_id.is_some()
+            || is_requirement_section
+            || collector.rules().looks_like_requirement(&text))
     {
-        "constraint".to_string()
-    } else {
-        "functional".to_string()
+        return None;
     }
+    let req = collector.push(section, external_id, text, None, None);
+    req.parent_id = parent_id;
+    req.span = Some(lines.span(item.start..item.end));
+    Some(req.id.clone())
+}
+
+/// Emits a numbered heading's body text as a single requirement.
+fn flush_heading_body(
+    collector: &mut RequirementCollector,
+    section: &str,
+    body: Option<HeadingBody>,
+    lines: &LineIndex,
+) {
+    let Some(body) = body else {
+        return;
+    };
+    if let Some(start) = body.start {
+        let req = collector.push(section, None, body.text.join(" "), None, None);
+        req.span = Some(lines.span(start..body.end));
+    }
+}
+
+/// Adds a table row as a requirement when it has description text. Explicit
+/// type and priority cells win over the wording.
+pub fn push_table_row<'c>(
+    collector: &'c mut RequirementCollector,
+    section: &str,
+    columns: &TableColumns,
+    cells: &[String],
+) -> Option<&'c mut Requirement> {
+    let cell = |idx: Option<usize>| {
+        idx.and_then(|i| cells.get(i))
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+    };
+    let raw = cell(columns.description)?;
+    let (inline_id, description) = split_external_id(raw);
+    let external_id = cell(columns.id).map(str::to_string).or(inline_id);
+    let section = cell(columns.section).unwrap_or(section);
+    let req_type = cell(columns.req_type).and_then(|t| collector.rules().normalize_req_type(t));
+    let priority = cell(columns.priority).and_then(|p| collector.rules().normalize_priority(p));
+    Some(collector.push(section, external_id, description, req_type, priority))
 }
 
-fn classify_priority(text: &str) -> String {
-    let lower = text.to_lowercase();
-    if lower.contains("critical") || lower.contains("must have") || lower.contains("**must**") {
-        "high".to_string()
-    } else if lower.contains("nice to have") || lower.contains("optional") || lower.contains("could") {
-        "low".to_string()
+pub fn normalize_req_type(value: &str) -> Option<String> {
+    let lower = value.to_lowercase();
+    let req_type = if lower.starts_with("non") || lower == "nfr" || lower == "performance" || lower == "security" {
+        "non_functional"
+    } else if lower.starts_with("func") || lower == "fr" {
+        "functional"
+    } else if lower.starts_with("constraint") {
+        "constraint"
     } else {
-        "medium".to_string()
+        return None;
+    };
+    Some(req_type.to_string())
+}
+
+pub fn normalize_priority(value: &str) -> Option<String> {
+    let lower = value.to_lowercase();
+    let priority = match lower.as_str() {
+        "high" | "critical" | "must" | "must have" | "shall" | "required" | "p0" | "p1" | "1" => "high",
+        "medium" | "med" | "normal" | "should" | "should have" | "recommended" | "p2" | "2" => "medium",
+        "low" | "could" | "could have" | "may" | "nice to have" | "optional" | "won't" | "wont" | "won't have"
+        | "p3" | "3" => "low",
+        _ => return None,
+    };
+    Some(priority.to_string())
+}
+
+/// True for IEEE-830 style headings such as `3.2.1 User login` or `4. Export`.
+fn is_numbered_heading(heading: &str) -> bool {
+    let Some(number) = heading.split_whitespace().next() else {
+        return false;
+    };
+    heading.contains(' ')
+        && number.trim_end_matches('.').split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Normalized identity of a requirement within its spec: section plus
+/// description, lowercased with whitespace collapsed.
+pub fn requirement_key(section: &str, description: &str) -> String {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    format!("{}\u{1f}{}", normalize(section), normalize(description))
+}
+
+/// Key used to recognise the same requirement across parses. An explicit
+/// external ID wins, so tagged requirements keep their identity when edited.
+pub fn identity_key(external_id: Option<&str>, section: &str, description: &str) -> String {
+    match external_id {
+        Some(ext) => format!("id\u{1f}{}", ext.to_uppercase()),
+        None => requirement_key(section, description),
+    }
+}
+
+/// Derives a deterministic requirement ID so that re-parsing unchanged text
+/// yields the same ID. `occurrence` disambiguates identical requirements
+/// repeated within the same section.
+pub fn stable_requirement_id(spec_id: &str, key: &str, occurrence: usize) -> String {
+    let name = format!("{}\u{1f}{}\u{1f}{}", spec_id, key, occurrence);
+    Uuid::new_v5(&REQUIREMENT_NAMESPACE, name.as_bytes()).to_string()
+}
+
+/// Splits a leading human requirement ID off a list item, accepting both
+/// `[REQ-AUTH-001] text` and `FR-12: text`.
+pub fn split_external_id(text: &str) -> (Option<String>, String) {
+    if let Some(rest) = text.strip_prefix('[') {
+        if let Some(end) = rest.find(']') {
+            let tag = rest[..end].trim();
+            if is_external_id(tag) {
+                let body = rest[end + 1..].trim_start();
+                let body = body.strip_prefix(':').or_else(|| body.strip_prefix('-')).unwrap_or(body);
+                return (Some(tag.to_string()), body.trim().to_string());
+            }
+        }
+    } else if let Some(colon) = text.find(':') {
+        let tag = &text[..colon];
+        if is_external_id(tag) {
+            return (Some(tag.to_string()), text[colon + 1..].trim().to_string());
+        }
     }
+    (None, text.to_string())
+}
+
+/// Matches IDs such as `REQ-AUTH-001`, `FR-12` or `SRS-3.2.1`: an uppercase
+/// prefix, at least one hyphenated segment, and a digit in the last segment.
+pub fn is_external_id(tag: &str) -> bool {
+    let mut segments = tag.split('-');
+    let prefix = segments.next().unwrap_or("");
+    let rest: Vec<&str> = segments.collect();
+    !prefix.is_empty()
+        && prefix.chars().all(|c| c.is_ascii_uppercase())
+        && !rest.is_empty()
+        && rest.iter().all(|seg| {
+            !seg.is_empty() && seg.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '.')
+        })
+        && rest.last().is_some_and(|seg| seg.chars().any(|c| c.is_ascii_digit()))
 }
 
 #[cfg(test)]
@@ -154,4 +556,229 @@ mod tests {
         assert_eq!(reqs[0].req_type, "functional");
         assert_eq!(reqs[3].req_type, "non_functional");
     }
+
+    #[test]
+    fn test_nested_items_become_acceptance_criteria() {
+        let content = r#"## Overview
+
+- The system shall let users reset their password
+  - A reset link is e-mailed
+  - The link expires after
+    one hour
+- Unrelated note
+  - Sub note
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 3);
+        assert_eq!(reqs[0].description, "The system shall let users reset their password");
+        assert_eq!(reqs[0].parent_id, None);
+        assert_eq!(reqs[1].description, "A reset link is e-mailed");
+        assert_eq!(reqs[1].parent_id.as_deref(), Some(reqs[0].id.as_str()));
+        assert_eq!(reqs[2].description, "The link expires after one hour");
+        assert_eq!(reqs[2].parent_id.as_deref(), Some(reqs[0].id.as_str()));
+    }
+
+    #[test]
+    fn test_project_rules_drive_detection() {
+        use crate::models::rules::{DetectionRules, TypeRule};
+        let content = r#"## Musts
+
+- Sessions are encrypted with TLS
+- Users can export data
+"#;
+        assert!(parse_spec("spec-1", content).is_empty());
+
+        let mut config = DetectionRules::default();
+        config.section_keywords.push("musts".to_string());
+        config.type_rules.insert(0, TypeRule {
+            req_type: "security".to_string(),
+            text_keywords: vec!["encrypt".to_string()],
+            ..Default::default()
+        });
+        let rules = ParserRules::compile(&config).unwrap();
+        let reqs = parse_spec_with_rules("spec-1", content, &rules);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].req_type, "security");
+        assert_eq!(reqs[1].req_type, "functional");
+    }
+
+    #[test]
+    fn test_project_priority_keywords_apply_to_table_cells() {
+        use crate::models::rules::{DetectionRules, PriorityRule};
+        let content = r#"## Requirements
+
+| ID | Requirement | Priority |
+|----|-------------|----------|
+| BIL-1 | Invoices are generated monthly | Blocker |
+"#;
+        let mut config = DetectionRules::default();
+        config.priority_keywords.insert(0, PriorityRule {
+            keyword: "blocker".to_string(),
+            priority: "high".to_string(),
+            negatable: false,
+        });
+        let rules = ParserRules::compile(&config).unwrap();
+        let reqs = parse_spec_with_rules("spec-1", content, &rules);
+        assert_eq!(reqs[0].priority, "high");
+        assert_eq!(reqs[0].priority_keyword, None);
+    }
+
+    #[test]
+    fn test_source_spans() {
+        let content = "---\nstatus: draft\n---\n## Requirements\n\n- The system shall export\n  reports as CSV\n\n## Notes\n\nThe system shall log every export.\n\n| ID | Requirement |\n|----|-------------|\n| FR-1 | Users can sign in |\n";
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 3);
+        let text = |r: &Requirement| {
+            let span = r.span.as_ref().unwrap();
+            &content[span.start_offset as usize..span.end_offset as usize]
+        };
+        let lines = |r: &Requirement| {
+            let span = r.span.as_ref().unwrap();
+            (span.start_line, span.end_line)
+        };
+        assert_eq!(text(&reqs[0]), "- The system shall export\n  reports as CSV");
+        assert_eq!(lines(&reqs[0]), (6, 7));
+        assert_eq!(text(&reqs[1]).trim_end(), "The system shall log every export.");
+        assert_eq!(lines(&reqs[1]), (11, 11));
+        assert!(text(&reqs[2]).contains("Users can sign in"));
+        assert_eq!(lines(&reqs[2]), (15, 15));
+    }
+
+    #[test]
+    fn test_front_matter_defaults() {
+        let content = r#"---
+status: approved
+priority: high
+type: non-functional
+---
+
+## Requirements
+
+- Pages load quickly on 3G connections
+- Dark mode is optional
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].priority, "high");
+        assert_eq!(reqs[0].req_type, "non_functional");
+        assert_eq!(reqs[0].priority_keyword, None);
+        // Wording still wins over the default
+        assert_eq!(reqs[1].priority, "low");
+        assert_eq!(reqs[1].priority_keyword.as_deref(), Some("optional"));
+    }
+
+    #[test]
+    fn test_parse_paragraph_requirements() {
+        let content = r#"## Overview
+
+This document describes the billing module. Note that invoices must
+comply with local tax law (all regions shall be supported eventually).
+
+The system shall send an invoice e-mail after
+each successful payment.
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].description, "The system shall send an invoice e-mail after each successful payment.");
+    }
+
+    #[test]
+    fn test_parse_requirements_table() {
+        let content = r#"## Requirements
+
+| ID | Requirement | Priority | Type |
+|----|-------------|----------|------|
+| BIL-1 | Invoices are generated monthly | Must | Functional |
+| BIL-2 | Invoice PDFs render in under 2s | Could | Non-functional |
+
+| Term | Meaning |
+|------|---------|
+| PDF | Portable Document Format |
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("BIL-1"));
+        assert_eq!(reqs[0].description, "Invoices are generated monthly");
+        assert_eq!(reqs[0].priority, "high");
+        assert_eq!(reqs[1].priority, "low");
+        assert_eq!(reqs[1].req_type, "non_functional");
+    }
+
+    #[test]
+    fn test_parse_numbered_heading_bodies() {
+        let content = r#"# 1 Introduction
+
+## 1.1 Purpose
+
+This document specifies the billing module.
+
+# 3 Specific Requirements
+
+### 3.2.1 Invoice history
+
+Users can browse all invoices from the past two years.
+
+### 3.2.2 Refunds
+
+The system shall allow refunds within 30 days.
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].section, "3.2.1 Invoice history");
+        assert_eq!(reqs[0].description, "Users can browse all invoices from the past two years.");
+        assert_eq!(reqs[1].description, "The system shall allow refunds within 30 days.");
+    }
+
+    #[test]
+    fn test_requirement_ids_are_stable_across_parses() {
+        let content = "## Requirements\n\n- The system shall export reports\n- The system shall export reports\n";
+        let first = parse_spec("spec-1", content);
+        let second = parse_spec("spec-1", content);
+        assert_eq!(first[0].id, second[0].id);
+        assert_eq!(first[1].id, second[1].id);
+        // Repeated text still gets distinct IDs
+        assert_ne!(first[0].id, first[1].id);
+        // IDs are scoped to the spec
+        assert_ne!(first[0].id, parse_spec("spec-2", content)[0].id);
+    }
+
+    #[test]
+    fn test_inline_tags_keep_requirement_ids() {
+        let reqs = parse_spec("spec-1", "## Requirements\n\n- Users shall log in with SSO #auth\n");
+        assert_eq!(reqs[0].description, "Users shall log in with SSO");
+        assert_eq!(reqs[0].tags, vec!["auth"]);
+        let key = requirement_key("Requirements", "Users shall log in with SSO #auth");
+        assert_eq!(reqs[0].id, stable_requirement_id("spec-1", &key, 0));
+    }
+
+    #[test]
+    fn test_parse_external_ids() {
+        let content = r#"## Overview
+
+- [REQ-AUTH-001] Users log in with SSO
+- FR-12: Sessions expire after 30 minutes
+- Note: this is not tagged
+"#;
+        let reqs = parse_spec("spec-1", content);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].external_id.as_deref(), Some("REQ-AUTH-001"));
+        assert_eq!(reqs[0].description, "Users log in with SSO");
+        assert_eq!(reqs[1].external_id.as_deref(), Some("FR-12"));
+        assert_eq!(reqs[1].description, "Sessions expire after 30 minutes");
+    }
+
+    #[test]
+    fn test_tagged_requirement_id_survives_edits() {
+        let before = parse_spec("spec-1", "- [REQ-1] Users log in with SSO\n");
+        let after = parse_spec("spec-1", "- [REQ-1] Users log in with SSO or a password\n");
+        assert_eq!(before[0].id, after[0].id);
+    }
+
+    #[test]
+    fn test_split_external_id_rejects_plain_words() {
+        assert_eq!(split_external_id("[draft] text").0, None);
+        assert_eq!(split_external_id("NOTE: text").0, None);
+        assert_eq!(split_external_id("NON-FUNCTIONAL: text").0, None);
+        assert_eq!(split_external_id("[SRS-3.2.1] - text"), (Some("SRS-3.2.1".to_string()), "text".to_string()));
+    }
 }
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use rusqlite::Connection;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::spec::{ParsedSpec, SpecImport, SpecImportError};
+use crate::services::codebase_scanner::IGNORE_DIRS;
+use crate::services::spec_sync;
+
+const GLOB_CHARS: &[char] = &['*', '?', '[', '{'];
+
+const MAX_DEPTH: usize = 12;
+
+/// Cleans up a spec source pattern and checks it stays inside the codebase:
+/// `.\docs\specs\*.md` -> `docs/specs/*.md`.
+pub fn normalize_pattern(pattern: &str) -> Result<String, AppError> {
+    let pattern = pattern.trim().replace('\\', "/");
+    let pattern = pattern.trim_start_matches("./").to_string();
+    if pattern.is_empty() {
+        return Err(AppError::InvalidInput("Spec source pattern cannot be empty".into()));
+    }
+    if pattern.starts_with('/') || Path::new(&pattern).is_absolute() || pattern.split('/').any(|c| c == "..") {
+        return Err(AppError::InvalidInput(format!(
+            "Spec source pattern must be relative to the codebase: {}",
+            pattern
+        )));
+    }
+    compile_glob(&pattern)?;
+    Ok(pattern)
+}
+
+/// A project's spec source patterns, matched against paths in its codebase.
+/// `*` stays within a directory; `**` crosses directories.
+#[derive(Debug)]
+pub struct SpecGlobs {
+    root: PathBuf,
+    set: GlobSet,
+    /// Literal directory prefix of each pattern, e.g. `docs/specs` for `docs/specs/**/*.md`
+    bases: Vec<PathBuf>,
+}
+
+impl SpecGlobs {
+    pub fn new(codebase_path: &str, patterns: &[String]) -> Result<Self, AppError> {
+        let root = PathBuf::from(codebase_path);
+        let mut builder = GlobSetBuilder::new();
+        let mut bases = Vec::new();
+        for pattern in patterns {
+            builder.add(compile_glob(pattern)?);
+            bases.push(root.join(literal_prefix(pattern)));
+        }
+        let set = builder
+            .build()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid spec source patterns: {}", e)))?;
+        Ok(Self { root, set, bases })
+    }
+
+    /// The spec filename for a matching path: relative to the codebase and
+    /// `/`-separated, so it is the same on every platform.
+    pub fn match_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_str()?),
+                _ => return None,
+            }
+        }
+        if parts.iter().any(|p| IGNORE_DIRS.contains(p)) {
+            return None;
+        }
+        let filename = parts.join("/");
+        self.set.is_match(&filename).then_some(filename)
+    }
+
+    /// Directories that cover every pattern: each pattern's literal prefix, or
+    /// its nearest existing ancestor so that folders created later are seen.
+    pub fn watch_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self
+            .bases
+            .iter()
+            .map(|base| {
+                base.ancestors()
+                    .take_while(|dir| dir.starts_with(&self.root))
+                    .find(|dir| dir.is_dir())
+                    .unwrap_or(self.root.as_path())
+                    .to_path_buf()
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        let covered: Vec<PathBuf> = dirs
+            .iter()
+            .filter(|dir| dirs.iter().any(|other| other != *dir && dir.starts_with(other)))
+            .cloned()
+            .collect();
+        dirs.retain(|dir| !covered.contains(dir));
+        dirs
+    }
+
+    /// Matching files as (spec filename, path), ordered by filename.
+    pub fn find_files(&self) -> Vec<(String, PathBuf)> {
+        let mut files = BTreeMap::new();
+        for dir in self.watch_dirs() {
+            self.walk(&dir, &mut files, 0);
+        }
+        files.into_iter().collect()
+    }
+
+    fn walk(&self, dir: &Path, files: &mut BTreeMap<String, PathBuf>, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if IGNORE_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            if path.is_dir() {
+                self.walk(&path, files, depth + 1);
+            } else if let Some(filename) = self.match_path(&path) {
+                files.insert(filename, path);
+            }
+        }
+    }
+}
+
+/// Imports every file matching the project's spec sources. Each file is saved
+/// in its own transaction, so one invalid spec does not block the others.
+pub fn import_sources(conn: &Connection, project_id: &str) -> Result<SpecImport, AppError> {
+    let project = queries::get_project(conn, project_id)?.project;
+    let patterns: Vec<String> = queries::list_spec_sources(conn, project_id)?
+        .into_iter()
+        .map(|s| s.pattern)
+        .collect();
+    let globs = SpecGlobs::new(&project.codebase_path, &patterns)?;
+
+    let mut import = SpecImport::default();
+    for (filename, path) in globs.find_files() {
+        match import_file(conn, project_id, &filename, &path) {
+            Ok(Some(parsed)) => import.specs.push(parsed.spec),
+            Ok(None) => import.unchanged += 1,
+            Err(e) => import.errors.push(SpecImportError {
+                filename,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(import)
+}
+
+/// Saves one source file as a spec. Returns `None` when there is nothing to
+/// do: the file is empty (e.g. mid-save) or the spec already has its content.
+pub fn import_file(
+    conn: &Connection,
+    project_id: &str,
+    filename: &str,
+    path: &Path,
+) -> Result<Option<ParsedSpec>, AppError> {
+    let content = std::fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    if queries::find_spec_by_filename(conn, project_id, filename)?.is_some_and(|s| s.content == content) {
+        return Ok(None);
+    }
+
+    let tx = conn.unchecked_transaction().map_err(AppError::Database)?;
+    let parsed = spec_sync::save_spec(&tx, project_id, filename, &content)?;
+    tx.commit().map_err(AppError::Database)?;
+    Ok(Some(parsed))
+}
+
+fn compile_glob(pattern: &str) -> Result<globset::Glob, AppError> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid glob '{}': {}", pattern, e)))
+}
+
+/// Leading directories of a pattern that contain no glob syntax.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    let mut parts: Vec<&str> = pattern.split('/').collect();
+    // The last part names files, not a directory
+    parts.pop();
+    parts
+        .into_iter()
+        .take_while(|part| !part.contains(GLOB_CHARS))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_pattern() {
+        assert_eq!(normalize_pattern(r" .\docs\specs\*.md ").unwrap(), "docs/specs/*.md");
+        assert!(normalize_pattern("").is_err());
+        assert!(normalize_pattern("/etc/*.md").is_err());
+        assert!(normalize_pattern("../other/*.md").is_err());
+        assert!(normalize_pattern("docs/[.md").is_err());
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("docs/specs/**/*.md"), PathBuf::from("docs/specs"));
+        assert_eq!(literal_prefix("docs/*/specs/*.md"), PathBuf::from("docs"));
+        assert_eq!(literal_prefix("*.md"), PathBuf::new());
+    }
+
+    #[test]
+    fn test_match_path() {
+        let globs = SpecGlobs::new("/repo", &["docs/specs/**/*.md".to_string(), "*.feature".to_string()]).unwrap();
+        assert_eq!(
+            globs.match_path(Path::new("/repo/docs/specs/auth/login.md")).as_deref(),
+            Some("docs/specs/auth/login.md")
+        );
+        assert_eq!(globs.match_path(Path::new("/repo/docs/specs/billing.md")).as_deref(), Some("docs/specs/billing.md"));
+        assert_eq!(globs.match_path(Path::new("/repo/checkout.feature")).as_deref(), Some("checkout.feature"));
+        // `*` does not cross directories
+        assert_eq!(globs.match_path(Path::new("/repo/tests/checkout.feature")), None);
+        assert_eq!(globs.match_path(Path::new("/repo/docs/readme.md")), None);
+        assert_eq!(globs.match_path(Path::new("/elsewhere/docs/specs/a.md")), None);
+    }
+
+    #[test]
+    fn test_find_files_skips_ignored_dirs() {
+        let root = std::env::temp_dir().join(format!("spec-sources-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("docs/specs/auth")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg/docs/specs")).unwrap();
+        std::fs::write(root.join("docs/specs/auth/login.md"), "# Login").unwrap();
+        std::fs::write(root.join("docs/specs/notes.txt"), "notes").unwrap();
+        std::fs::write(root.join("node_modules/pkg/docs/specs/dep.md"), "# Dep").unwrap();
+
+        let globs = SpecGlobs::new(
+            root.to_str().unwrap(),
+            &["docs/specs/**/*.md".to_string(), "**/docs/specs/*.md".to_string()],
+        )
+        .unwrap();
+        let names: Vec<String> = globs.find_files().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["docs/specs/auth/login.md"]);
+        assert_eq!(globs.watch_dirs(), vec![root.clone()]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, HashSet};
+use rusqlite::Connection;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::spec::{ParsedSpec, Requirement, RequirementFields, Spec};
+use crate::services::{detection_rules, front_matter, requirement_diff, requirement_lint, spec_format, tags};
+
+/// Stores a spec's content and parses it. A filename the project already has
+/// gets a new revision (when the content differs) and keeps its requirement
+/// IDs. Callers are expected to wrap this in a transaction.
+pub fn save_spec(
+    conn: &Connection,
+    project_id: &str,
+    filename: &str,
+    content: &str,
+) -> Result<ParsedSpec, AppError> {
+    let spec = match queries::find_spec_by_filename(conn, project_id, filename)? {
+        Some(existing) => {
+            if existing.content != content {
+                queries::add_spec_revision(conn, &existing.id, content)?;
+            }
+            existing
+        }
+        None => {
+            let (metadata, _) = front_matter::split_front_matter(content);
+            queries::create_spec(conn, project_id, filename, content, &metadata)?
+        }
+    };
+    let spec = queries::get_spec(conn, &spec.id)?;
+    let requirements = sync_requirements(conn, &spec)?;
+    queries::touch_project_updated_at(conn, project_id)?;
+
+    Ok(ParsedSpec {
+        spec: queries::get_spec(conn, &spec.id)?,
+        requirements,
+    })
+}
+
+/// Re-parses a spec's current content into its stored requirements and
+/// returns the active ones. Merges instead of replacing, so unchanged
+/// requirements keep their tests and results, and leaves requirements that
+/// were created, edited or deleted by hand as they are. Lint findings are
+/// refreshed to match.
+pub fn sync_requirements(conn: &Connection, spec: &Spec) -> Result<Vec<Requirement>, AppError> {
+    let (metadata, _) = front_matter::split_front_matter(&spec.content);
+    queries::update_spec_metadata(conn, &spec.id, &metadata)?;
+
+    let existing = as_parsed(conn, spec, queries::get_all_requirements_for_spec(conn, &spec.id)?)?;
+    let rules = detection_rules::rules_for_project(conn, &spec.project_id)?;
+    let parsed = spec_format::parse_spec_file(&spec.id, &spec.filename, &spec.content, &rules)?;
+    ensure_unique_external_ids(conn, &spec.project_id, &spec.id, &parsed)?;
+    let mut merge = requirement_diff::merge_requirements(&existing, parsed);
+    let manual: HashSet<&str> = existing.iter().filter(|r| r.manually_edited).map(|r| r.id.as_str()).collect();
+    merge.to_update.retain(|r| !manual.contains(r.id.as_str()));
+    merge.to_retire.retain(|id| !manual.contains(id.as_str()));
+
+    // Inserts first: a kept requirement may now hang under a new parent
+    queries::insert_requirements(conn, &merge.to_insert)?;
+    queries::update_requirements(conn, &merge.to_update)?;
+    queries::retire_requirements(conn, &merge.to_retire)?;
+    tags::link_inline_tags(conn, &spec.project_id, merge.to_insert.iter().chain(&merge.to_update))?;
+
+    queries::update_spec_parsed_at(conn, &spec.id)?;
+    refresh_lint_findings(conn, &spec.id)?;
+    queries::get_requirements_for_spec(conn, &spec.id)
+}
+
+/// Re-lints a spec's active requirements.
+pub fn refresh_lint_findings(conn: &Connection, spec_id: &str) -> Result<(), AppError> {
+    let requirements = queries::get_requirements_for_spec(conn, spec_id)?;
+    queries::replace_lint_findings(conn, spec_id, &requirement_lint::lint_requirements(&requirements))
+}
+
+/// Stored requirements as the parser last produced them, for matching
+/// against a fresh parse: hand-edited ones get their parsed text back, and
+/// hand-created ones are left out since no parse will ever produce them.
+fn as_parsed(conn: &Connection, spec: &Spec, stored: Vec<Requirement>) -> Result<Vec<Requirement>, AppError> {
+    let mut originals: HashMap<String, Option<RequirementFields>> = HashMap::new();
+    for edit in queries::get_requirement_edits_for_spec(conn, &spec.id)? {
+        originals.entry(edit.requirement_id).or_insert(edit.before);
+    }
+    Ok(stored
+        .into_iter()
+        .filter_map(|mut req| {
+            if !req.manually_edited {
+                return Some(req);
+            }
+            let original = originals.get(&req.id).cloned().flatten()?;
+            req.section = original.section;
+            req.description = original.description;
+            Some(req)
+        })
+        .collect())
+}
+
+/// Rejects specs that repeat an external requirement ID, either within
+/// themselves or against another spec of the same project.
+fn ensure_unique_external_ids(
+    conn: &Connection,
+    project_id: &str,
+    spec_id: &str,
+    requirements: &[Requirement],
+) -> Result<(), AppError> {
+    let mut seen: HashSet<String> = queries::get_external_ids_in_other_specs(conn, project_id, spec_id)?
+        .into_iter()
+        .map(|id| id.to_uppercase())
+        .collect();
+    let mut duplicates = Vec::new();
+    for ext in requirements.iter().filter_map(|r| r.external_id.as_deref()) {
+        if !seen.insert(ext.to_uppercase()) && !duplicates.contains(&ext) {
+            duplicates.push(ext);
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Duplicate requirement IDs in project: {}",
+            duplicates.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::CreateProjectRequest;
+    use crate::models::spec::UpdateRequirementRequest;
+    use crate::services::requirement_edit;
+
+    const SPEC: &str = "## Requirements\n\n- The system shall email invoices\n- The system shall archive invoices\n";
+
+    fn setup() -> (Connection, String) {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::run_migrations(&conn).unwrap();
+        let project = queries::create_project(
+            &conn,
+            &CreateProjectRequest { name: "project".to_string(), codebase_path: "/tmp".to_string() },
+        )
+        .unwrap();
+        (conn, project.id)
+    }
+
+    fn find<'a>(requirements: &'a [Requirement], description: &str) -> &'a Requirement {
+        requirements.iter().find(|r| r.description == description).unwrap()
+    }
+
+    #[test]
+    fn test_hand_edits_survive_resync() {
+        let (conn, project_id) = setup();
+        let parsed = save_spec(&conn, &project_id, "billing.md", SPEC).unwrap();
+        let id = find(&parsed.requirements, "The system shall email invoices").id.clone();
+        let request = UpdateRequirementRequest {
+            description: Some("The system shall email invoices as PDF".to_string()),
+            ..Default::default()
+        };
+        requirement_edit::update_requirement(&conn, &id, &request, "alice").unwrap();
+
+        let resynced = save_spec(&conn, &project_id, "billing.md", SPEC).unwrap();
+        assert_eq!(resynced.requirements.len(), 2);
+        let edited = find(&resynced.requirements, "The system shall email invoices as PDF");
+        assert_eq!(edited.id, id);
+        assert!(edited.manually_edited);
+        let history = queries::get_requirement_edits(&conn, &id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, "edit");
+        assert_eq!(history[0].before.as_ref().unwrap().description, "The system shall email invoices");
+    }
+
+    #[test]
+    fn test_deleted_requirement_stays_retired() {
+        let (conn, project_id) = setup();
+        let parsed = save_spec(&conn, &project_id, "billing.md", SPEC).unwrap();
+        let id = find(&parsed.requirements, "The system shall archive invoices").id.clone();
+        requirement_edit::delete_requirement(&conn, &id, "alice").unwrap();
+
+        let resynced = save_spec(&conn, &project_id, "billing.md", SPEC).unwrap();
+        assert_eq!(resynced.requirements.len(), 1);
+        assert!(resynced.requirements.iter().all(|r| r.id != id));
+        assert!(queries::get_requirement(&conn, &id).unwrap().retired_at.is_some());
+        let history = queries::get_requirement_edits(&conn, &id).unwrap();
+        assert_eq!(history.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(), vec!["delete"]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::Connection;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::db::{queries, Database};
+use crate::errors::AppError;
+use crate::models::spec::SpecChanged;
+use crate::services::spec_sources::{self, SpecGlobs};
+
+/// Filesystem watchers for projects with spec sources, keyed by project ID.
+/// Dropping a watcher stops it.
+#[derive(Default)]
+pub struct SpecWatchers {
+    watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+}
+
+impl SpecWatchers {
+    /// (Re)starts watching a project's spec sources. Created or edited files
+    /// that match are re-imported and announced with a `spec-changed` event.
+    /// A project without sources is left unwatched.
+    pub fn watch_project(&self, app_handle: &AppHandle, conn: &Connection, project_id: &str) -> Result<(), AppError> {
+        self.unwatch_project(project_id)?;
+
+        let project = queries::get_project(conn, project_id)?.project;
+        let patterns: Vec<String> = queries::list_spec_sources(conn, project_id)?
+            .into_iter()
+            .map(|s| s.pattern)
+            .collect();
+        if patterns.is_empty() {
+            return Ok(());
+        }
+        let globs = SpecGlobs::new(&project.codebase_path, &patterns)?;
+        let dirs = globs.watch_dirs();
+
+        let app = app_handle.clone();
+        let id = project.id.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+            for path in &event.paths {
+                if let Some(filename) = globs.match_path(path) {
+                    reimport(&app, &id, &filename, path);
+                }
+            }
+        })
+        .map_err(watch_error)?;
+        for dir in &dirs {
+            watcher.watch(dir, RecursiveMode::Recursive).map_err(watch_error)?;
+        }
+
+        self.watchers
+            .lock()
+            .map_err(|e| AppError::General(e.to_string()))?
+            .insert(project.id, watcher);
+        Ok(())
+    }
+
+    pub fn unwatch_project(&self, project_id: &str) -> Result<(), AppError> {
+        self.watchers
+            .lock()
+            .map_err(|e| AppError::General(e.to_string()))?
+            .remove(project_id);
+        Ok(())
+    }
+}
+
+/// Picks up spec files edited while the app was closed and starts watching
+/// every project with spec sources. The database is locked one project at a
+/// time so commands aren't held up behind the whole import.
+pub fn resume(app_handle: &AppHandle) {
+    let state = app_handle.state::<Database>();
+    let watchers = app_handle.state::<SpecWatchers>();
+    let project_ids = match state.conn.lock() {
+        Ok(conn) => queries::list_projects_with_spec_sources(&conn).unwrap_or_default(),
+        Err(_) => return,
+    };
+    for project_id in project_ids {
+        let Ok(conn) = state.conn.lock() else {
+            return;
+        };
+        let _ = spec_sources::import_sources(&conn, &project_id);
+        let _ = watchers.watch_project(app_handle, &conn, &project_id);
+    }
+}
+
+/// Imports a changed file and tells the frontend, including when the new
+/// content could not be imported. Saves with unchanged content are silent.
+fn reimport(app_handle: &AppHandle, project_id: &str, filename: &str, path: &Path) {
+    let state = app_handle.state::<Database>();
+    let result = state
+        .conn
+        .lock()
+        .map_err(|e| AppError::General(e.to_string()))
+        .and_then(|conn| spec_sources::import_file(&conn, project_id, filename, path));
+
+    let event = match result {
+        Ok(None) => return,
+        Ok(Some(parsed)) => SpecChanged {
+            project_id: project_id.to_string(),
+            filename: filename.to_string(),
+            spec_id: Some(parsed.spec.id),
+            revision: Some(parsed.spec.revision),
+            error: None,
+        },
+        Err(e) => SpecChanged {
+            project_id: project_id.to_string(),
+            filename: filename.to_string(),
+            spec_id: None,
+            revision: None,
+            error: Some(e.to_string()),
+        },
+    };
+    let _ = app_handle.emit("spec-changed", event);
+}
+
+fn watch_error(e: notify::Error) -> AppError {
+    AppError::General(format!("Failed to watch spec sources: {}", e))
+}
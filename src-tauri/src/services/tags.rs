@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use rusqlite::Connection;
+use crate::db::queries;
+use crate::errors::AppError;
+use crate::models::spec::Requirement;
+use crate::models::tag::Tag;
+use crate::services::codebase_scanner::CodeSymbol;
+
+/// `#auth`, `#mobile-app`; must start with a letter, so "#1" issue numbers
+/// and "C#" are left alone.
+fn inline_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|\s)#([A-Za-z][\w-]*)").expect("inline tag pattern is valid"))
+}
+
+/// Splits inline `#tag` tokens off a requirement's text. Returns the text
+/// without them and the normalized tags, sorted. Text made only of tags is
+/// kept as written.
+pub fn extract_inline_tags(text: &str) -> (String, Vec<String>) {
+    let mut tags = Vec::new();
+    let stripped = inline_tag_regex().replace_all(text, |caps: &regex::Captures| {
+        if let Some(tag) = normalize_tag(&caps[1]) {
+            tags.push(tag);
+        }
+        ""
+    });
+    if tags.is_empty() {
+        return (text.to_string(), tags);
+    }
+    let stripped = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stripped = stripped.trim_end_matches([',', ';']).trim().to_string();
+    if stripped.is_empty() {
+        return (text.to_string(), Vec::new());
+    }
+    tags.sort();
+    tags.dedup();
+    (stripped, tags)
+}
+
+/// Lowercase tag name without the leading `#`, or `None` when it isn't a
+/// valid tag.
+pub fn normalize_tag(name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches('#').to_lowercase();
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then_some(name)
+}
+
+/// Links requirements to the tags written in their text, creating tags the
+/// project doesn't have yet.
+pub fn link_inline_tags<'a>(
+    conn: &Connection,
+    project_id: &str,
+    requirements: impl IntoIterator<Item = &'a Requirement>,
+) -> Result<(), AppError> {
+    let mut ids: HashMap<String, String> = HashMap::new();
+    for req in requirements {
+        let mut tag_ids = Vec::new();
+        for name in &req.tags {
+            if !ids.contains_key(name) {
+                ids.insert(name.clone(), queries::get_or_create_tag(conn, project_id, name)?.id);
+            }
+            tag_ids.push(ids[name].clone());
+        }
+        queries::set_inline_tags(conn, &req.id, &tag_ids)?;
+    }
+    Ok(())
+}
+
+/// Creates a tag or updates its codebase paths.
+pub fn save_tag(conn: &Connection, project_id: &str, name: &str, paths: &[String]) -> Result<Tag, AppError> {
+    let name = normalize_tag(name).ok_or_else(|| AppError::InvalidInput(format!("Invalid tag: {}", name)))?;
+    let paths: Vec<String> = paths.iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+    component_globs(&paths)?;
+    let tag = queries::get_or_create_tag(conn, project_id, &name)?;
+    queries::update_tag_paths(conn, &tag.id, &paths)?;
+    queries::get_tag(conn, &tag.id)
+}
+
+/// The project's tag with this name; an unknown tag is an error rather than
+/// an empty selection.
+pub fn find_tag(conn: &Connection, project_id: &str, name: &str) -> Result<Tag, AppError> {
+    let normalized = normalize_tag(name).ok_or_else(|| AppError::InvalidInput(format!("Invalid tag: {}", name)))?;
+    queries::get_tag_by_name(conn, project_id, &normalized)?
+        .ok_or_else(|| AppError::NotFound(format!("Tag not found: {}", name)))
+}
+
+/// Requirements carrying the tag, together with their acceptance criteria.
+pub fn with_tag(requirements: Vec<Requirement>, tag: &str) -> Vec<Requirement> {
+    let parents: HashMap<&str, &str> = requirements
+        .iter()
+        .filter_map(|r| Some((r.id.as_str(), r.parent_id.as_deref()?)))
+        .collect();
+    let tagged: HashSet<&str> = requirements
+        .iter()
+        .filter(|r| r.tags.iter().any(|t| t == tag))
+        .map(|r| r.id.as_str())
+        .collect();
+
+    let mut keep = HashSet::new();
+    for req in &requirements {
+        let mut seen = HashSet::new();
+        let mut current = Some(req.id.as_str());
+        while let Some(id) = current.filter(|id| seen.insert(*id)) {
+            if tagged.contains(id) {
+                keep.insert(req.id.clone());
+                break;
+            }
+            current = parents.get(id).copied();
+        }
+    }
+    requirements.into_iter().filter(|r| keep.contains(&r.id)).collect()
+}
+
+/// Codebase paths mapped to a requirement through its tags.
+pub fn component_paths(requirement: &Requirement, tags: &[Tag]) -> Vec<String> {
+    let mut paths: Vec<String> = tags
+        .iter()
+        .filter(|t| requirement.tags.contains(&t.name))
+        .flat_map(|t| t.paths.iter().cloned())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Symbols in files under the given paths. With no paths, or none of the
+/// symbols under them, every symbol is returned.
+pub fn component_symbols(symbols: &[CodeSymbol], paths: &[String]) -> Result<Vec<CodeSymbol>, AppError> {
+    if paths.is_empty() {
+        return Ok(symbols.to_vec());
+    }
+    let globs = component_globs(paths)?;
+    let matching: Vec<CodeSymbol> = symbols
+        .iter()
+        .filter(|s| globs.is_match(s.file_path.replace('\\', "/")))
+        .cloned()
+        .collect();
+    Ok(if matching.is_empty() { symbols.to_vec() } else { matching })
+}
+
+/// Paths are globs; a plain directory such as `src/billing` covers
+/// everything below it.
+fn component_globs(paths: &[String]) -> Result<GlobSet, AppError> {
+    let mut builder = GlobSetBuilder::new();
+    for path in paths {
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        let pattern = if path.contains(['*', '?', '[', '{']) { path.to_string() } else { format!("{}{{,/**}}", path) };
+        let glob = Glob::new(&pattern).map_err(|e| AppError::InvalidInput(format!("Invalid glob '{}': {}", path, e)))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| AppError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, file_path: &str) -> CodeSymbol {
+        CodeSymbol { name: name.into(), kind: "function".into(), file_path: file_path.into(), line: 1 }
+    }
+
+    #[test]
+    fn test_extract_inline_tags() {
+        let (text, tags) = extract_inline_tags("Users shall log in with SSO #Auth #mobile-app #auth");
+        assert_eq!(text, "Users shall log in with SSO");
+        assert_eq!(tags, vec!["auth", "mobile-app"]);
+
+        let (text, tags) = extract_inline_tags("Fixes #12 and supports C# clients");
+        assert_eq!(text, "Fixes #12 and supports C# clients");
+        assert!(tags.is_empty());
+
+        assert_eq!(extract_inline_tags("#billing").0, "#billing");
+    }
+
+    #[test]
+    fn test_component_symbols() {
+        let symbols = vec![symbol("charge", "src/billing/charge.ts"), symbol("login", "src/auth/login.ts")];
+        let billing = component_symbols(&symbols, &["src/billing".to_string()]).unwrap();
+        assert_eq!(billing.len(), 1);
+        assert_eq!(billing[0].name, "charge");
+        assert_eq!(component_symbols(&symbols, &["**/*.py".to_string()]).unwrap().len(), 2);
+        assert!(component_symbols(&symbols, &["src/[".to_string()]).is_err());
+    }
+}
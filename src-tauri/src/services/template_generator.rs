@@ -1,12 +1,18 @@
-use crate::models::spec::Requirement;
+use crate::models::spec::{GherkinScenario, GherkinStep, Requirement, Threshold};
 use crate::services::codebase_scanner::CodeSymbol;
 
+/// Runs measured to estimate a latency percentile.
+const PERCENTILE_SAMPLES: usize = 100;
+
 pub fn generate_jest_test(requirement: &Requirement, symbols: &[CodeSymbol]) -> String {
     let desc = &requirement.description;
     let section = &requirement.section;
     let relevant = find_relevant_symbols(desc, symbols);
 
     let mut code = String::new();
+    if let Some(ext) = &requirement.external_id {
+        code.push_str(&format!("// Requirement ID: {}\n", ext));
+    }
     code.push_str(&format!("// Requirement: {}\n", desc));
     code.push_str(&format!("// Section: {}\n", section));
     code.push_str(&format!("// Type: {} | Priority: {}\n\n", requirement.req_type, requirement.priority));
@@ -18,6 +24,15 @@ pub fn generate_jest_test(requirement: &Requirement, symbols: &[CodeSymbol]) ->
         code.push('\n');
     }
 
+    if let Some(scenario) = &requirement.scenario {
+        push_jest_scenario(&mut code, requirement, scenario);
+        return code;
+    }
+    if let Some(threshold) = &requirement.threshold {
+        push_jest_threshold(&mut code, requirement, threshold);
+        return code;
+    }
+
     code.push_str(&format!("describe('{}', () => {{\n", escape_js_string(section)));
     code.push_str(&format!("  it('should {}', () => {{\n", escape_js_string(&make_test_description(desc))));
 
@@ -42,6 +57,9 @@ pub fn generate_pytest_test(requirement: &Requirement, symbols: &[CodeSymbol]) -
     let relevant = find_relevant_symbols(desc, symbols);
 
     let mut code = String::new();
+    if let Some(ext) = &requirement.external_id {
+        code.push_str(&format!("# Requirement ID: {}\n", ext));
+    }
     code.push_str(&format!("# Requirement: {}\n", desc));
     code.push_str(&format!("# Section: {}\n", section));
     code.push_str(&format!("# Type: {} | Priority: {}\n\n", requirement.req_type, requirement.priority));
@@ -53,6 +71,15 @@ pub fn generate_pytest_test(requirement: &Requirement, symbols: &[CodeSymbol]) -
         code.push('\n');
     }
 
+    if let Some(scenario) = &requirement.scenario {
+        push_pytest_scenario(&mut code, requirement, scenario);
+        return code;
+    }
+    if let Some(threshold) = &requirement.threshold {
+        push_pytest_threshold(&mut code, requirement, threshold);
+        return code;
+    }
+
     let test_name = make_python_test_name(desc);
     code.push_str(&format!("class Test{}:\n", make_class_name(section)));
     code.push_str(&format!("    def {}(self):\n", test_name));
@@ -67,6 +94,266 @@ pub fn generate_pytest_test(requirement: &Requirement, symbols: &[CodeSymbol]) -
     code
 }
 
+/// Jest test for a Gherkin scenario: one comment per step, an assertion
+/// placeholder after each Then step, and `it.each` over outline Examples.
+fn push_jest_scenario(code: &mut String, requirement: &Requirement, scenario: &GherkinScenario) {
+    let (header, rows) = example_table(scenario);
+    let params: Vec<String> = header.iter().map(|h| make_identifier(h)).collect();
+
+    code.push_str(&format!("describe('{}', () => {{\n", escape_js_string(&requirement.section)));
+    if rows.is_empty() {
+        code.push_str(&format!("  it('{}', () => {{\n", escape_js_string(&requirement.description)));
+    } else {
+        code.push_str("  it.each([\n");
+        for row in &rows {
+            let fields: Vec<String> = params
+                .iter()
+                .zip(row.iter())
+                .map(|(name, value)| format!("{}: '{}'", name, escape_js_string(value)))
+                .collect();
+            code.push_str(&format!("    {{ {} }},\n", fields.join(", ")));
+        }
+        let title_params: Vec<String> = params.iter().map(|p| format!("${}", p)).collect();
+        code.push_str(&format!(
+            "  ])('{} ({})', ({{ {} }}) => {{\n",
+            escape_js_string(&requirement.description),
+            title_params.join(", "),
+            params.join(", "),
+        ));
+    }
+
+    for (keyword, step) in resolve_step_keywords(&scenario.steps) {
+        code.push_str(&format!("    // {} {}\n", step.keyword, step.text));
+        if keyword == "Then" {
+            code.push_str("    expect(true).toBe(true); // TODO: Replace with actual assertion\n");
+        }
+    }
+    code.push_str("  });\n");
+    code.push_str("});\n");
+}
+
+/// pytest test for a Gherkin scenario, parametrized over outline Examples.
+fn push_pytest_scenario(code: &mut String, requirement: &Requirement, scenario: &GherkinScenario) {
+    let (header, rows) = example_table(scenario);
+    let params: Vec<String> = header.iter().map(|h| make_identifier(h).to_lowercase()).collect();
+
+    if !rows.is_empty() {
+        code.push_str("import pytest\n\n\n");
+    }
+    code.push_str(&format!("class Test{}:\n", make_class_name(&requirement.section)));
+    if !rows.is_empty() {
+        code.push_str(&format!("    @pytest.mark.parametrize(\"{}\", [\n", params.join(",")));
+        for row in &rows {
+            let values: Vec<String> = row.iter().map(|v| format!("\"{}\"", escape_py_string(v))).collect();
+            let tuple = if values.len() == 1 { format!("({},)", values[0]) } else { format!("({})", values.join(", ")) };
+            code.push_str(&format!("        {},\n", tuple));
+        }
+        code.push_str("    ])\n");
+    }
+    let mut signature = vec!["self".to_string()];
+    signature.extend(params.iter().cloned());
+    code.push_str(&format!("    def {}({}):\n", make_python_test_name(&requirement.description), signature.join(", ")));
+    code.push_str(&format!(
+        "        \"\"\"{}: {}\"\"\"\n",
+        scenario.keyword,
+        requirement.description.replace("\"\"\"", "\\\"\\\"\\\""),
+    ));
+    for (keyword, step) in resolve_step_keywords(&scenario.steps) {
+        code.push_str(&format!("        # {} {}\n", step.keyword, step.text));
+        if keyword == "Then" {
+            code.push_str("        assert True  # TODO: Replace with actual assertion\n");
+        }
+    }
+}
+
+/// Jest test that measures the requirement's metric and asserts its limit,
+/// taking the percentile over repeated runs when one is given.
+fn push_jest_threshold(code: &mut String, requirement: &Requirement, threshold: &Threshold) {
+    code.push_str(&format!("describe('{}', () => {{\n", escape_js_string(&requirement.section)));
+    code.push_str(&format!("  it('should {}', async () => {{\n", escape_js_string(&make_test_description(&requirement.description))));
+    code.push_str(&format!("    // Threshold: {}\n", describe_threshold(threshold)));
+    code.push_str("    // Arrange\n");
+    code.push_str("    \n");
+    code.push_str("    // Act\n");
+    let variable = match (threshold.metric.as_str(), threshold.percentile) {
+        ("duration", Some(percentile)) => {
+            code.push_str("    const durations = [];\n");
+            code.push_str(&format!("    for (let i = 0; i < {}; i++) {{\n", PERCENTILE_SAMPLES));
+            code.push_str("      const start = performance.now();\n");
+            code.push_str("      // TODO: call the code under test\n");
+            code.push_str("      durations.push(performance.now() - start);\n");
+            code.push_str("    }\n");
+            code.push_str("    durations.sort((a, b) => a - b);\n");
+            code.push_str(&format!(
+                "    const duration = durations[Math.ceil(durations.length * {}) - 1];\n",
+                format_number(percentile / 100.0)
+            ));
+            "duration"
+        }
+        ("duration", None) => {
+            code.push_str("    const start = performance.now();\n");
+            code.push_str("    // TODO: call the code under test\n");
+            code.push_str("    const duration = performance.now() - start;\n");
+            "duration"
+        }
+        ("throughput", _) => {
+            code.push_str("    let completed = 0;\n");
+            code.push_str("    const start = performance.now();\n");
+            code.push_str("    while (performance.now() - start < 1000) {\n");
+            code.push_str("      // TODO: call the code under test\n");
+            code.push_str("      completed++;\n");
+            code.push_str("    }\n");
+            code.push_str("    const perSecond = completed / ((performance.now() - start) / 1000);\n");
+            "perSecond"
+        }
+        (metric, _) => {
+            let variable = if metric == "size" { "sizeBytes" } else { metric };
+            code.push_str(&format!("    const {} = 0; // TODO: measure {}\n", variable, threshold.text));
+            variable
+        }
+    };
+    code.push_str("\n    // Assert\n");
+    let matcher = match threshold.operator.as_str() {
+        "<" => "toBeLessThan",
+        "<=" => "toBeLessThanOrEqual",
+        ">" => "toBeGreaterThan",
+        ">=" => "toBeGreaterThanOrEqual",
+        _ => "toBe",
+    };
+    code.push_str(&format!("    expect({}).{}({});\n", variable, matcher, format_number(threshold.value)));
+    code.push_str("  });\n");
+    code.push_str("});\n");
+}
+
+/// pytest counterpart of [`push_jest_threshold`].
+fn push_pytest_threshold(code: &mut String, requirement: &Requirement, threshold: &Threshold) {
+    let timed = matches!(threshold.metric.as_str(), "duration" | "throughput");
+    let percentile = threshold.percentile.filter(|_| threshold.metric == "duration");
+    if percentile.is_some() {
+        code.push_str("import math\n");
+    }
+    if timed {
+        code.push_str("import time\n");
+    }
+    if timed || percentile.is_some() {
+        code.push_str("\n\n");
+    }
+    code.push_str(&format!("class Test{}:\n", make_class_name(&requirement.section)));
+    code.push_str(&format!("    def {}(self):\n", make_python_test_name(&requirement.description)));
+    code.push_str(&format!("        \"\"\"Test: {}\"\"\"\n", requirement.description.replace("\"\"\"", "\\\"\\\"\\\"")));
+    code.push_str(&format!("        # Threshold: {}\n", describe_threshold(threshold)));
+    code.push_str("        # Arrange\n");
+    code.push_str("        \n");
+    code.push_str("        # Act\n");
+    let variable = match (threshold.metric.as_str(), percentile) {
+        ("duration", Some(percentile)) => {
+            code.push_str("        durations = []\n");
+            code.push_str(&format!("        for _ in range({}):\n", PERCENTILE_SAMPLES));
+            code.push_str("            start = time.perf_counter()\n");
+            code.push_str("            # TODO: call the code under test\n");
+            code.push_str("            durations.append((time.perf_counter() - start) * 1000)\n");
+            code.push_str("        durations.sort()\n");
+            code.push_str(&format!(
+                "        duration = durations[math.ceil(len(durations) * {}) - 1]\n",
+                format_number(percentile / 100.0)
+            ));
+            "duration"
+        }
+        ("duration", None) => {
+            code.push_str("        start = time.perf_counter()\n");
+            code.push_str("        # TODO: call the code under test\n");
+            code.push_str("        duration = (time.perf_counter() - start) * 1000\n");
+            "duration"
+        }
+        ("throughput", _) => {
+            code.push_str("        completed = 0\n");
+            code.push_str("        start = time.perf_counter()\n");
+            code.push_str("        while time.perf_counter() - start < 1:\n");
+            code.push_str("            # TODO: call the code under test\n");
+            code.push_str("            completed += 1\n");
+            code.push_str("        per_second = completed / (time.perf_counter() - start)\n");
+            "per_second"
+        }
+        (metric, _) => {
+            let variable = if metric == "size" { "size_bytes" } else { metric };
+            code.push_str(&format!("        {} = 0  # TODO: measure {}\n", variable, threshold.text));
+            variable
+        }
+    };
+    code.push_str("\n        # Assert\n");
+    let operator = if threshold.operator == "=" { "==" } else { threshold.operator.as_str() };
+    code.push_str(&format!("        assert {} {} {}\n", variable, operator, format_number(threshold.value)));
+}
+
+/// e.g. "p95 duration < 200 ms (within 200ms)".
+fn describe_threshold(threshold: &Threshold) -> String {
+    let percentile = threshold.percentile.map(|p| format!("p{} ", format_number(p))).unwrap_or_default();
+    format!(
+        "{}{} {} {} {} ({})",
+        percentile,
+        threshold.metric,
+        threshold.operator,
+        format_number(threshold.value),
+        threshold.unit,
+        threshold.text
+    )
+}
+
+/// Whole numbers without a trailing ".0".
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Pairs each step with its effective keyword, so `And`/`But` inherit the
+/// Given/When/Then they continue.
+fn resolve_step_keywords(steps: &[GherkinStep]) -> Vec<(&str, &GherkinStep)> {
+    let mut previous = "Given";
+    steps
+        .iter()
+        .map(|step| {
+            if matches!(step.keyword.as_str(), "Given" | "When" | "Then") {
+                previous = step.keyword.as_str();
+            }
+            (previous, step)
+        })
+        .collect()
+}
+
+/// Header and rows of all Examples tables that share the first table's header.
+fn example_table(scenario: &GherkinScenario) -> (Vec<String>, Vec<Vec<String>>) {
+    let Some(first) = scenario.examples.iter().find(|e| !e.header.is_empty()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let rows = scenario
+        .examples
+        .iter()
+        .filter(|e| e.header == first.header)
+        .flat_map(|e| e.rows.iter().cloned())
+        .collect();
+    (first.header.clone(), rows)
+}
+
+fn make_identifier(name: &str) -> String {
+    let ident: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if ident.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn escape_py_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 fn find_relevant_symbols<'a>(description: &str, symbols: &'a [CodeSymbol]) -> Vec<&'a CodeSymbol> {
     let lower_desc = description.to_lowercase();
     let words: Vec<&str> = lower_desc.split_whitespace()
@@ -153,3 +440,72 @@ fn generate_assertion_hint(desc: &str) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::gherkin_parser::parse_feature;
+    use crate::services::detection_rules::ParserRules;
+
+    const OUTLINE: &str = r#"Feature: Login
+  Scenario Outline: Lockout after failed attempts
+    Given a registered user
+    When the user fails to log in <attempts> times
+    Then the account is <state>
+    And an e-mail is sent
+
+    Examples:
+      | attempts | state  |
+      | 3        | active |
+      | 5        | locked |
+"#;
+
+    #[test]
+    fn test_jest_scenario_outline_uses_it_each() {
+        let reqs = parse_feature("spec-1", OUTLINE, ParserRules::builtin());
+        let code = generate_jest_test(&reqs[0], &[]);
+        assert!(code.contains("it.each(["));
+        assert!(code.contains("{ attempts: '3', state: 'active' },"));
+        assert!(code.contains("({ attempts, state }) => {"));
+        assert!(code.contains("    // When the user fails to log in <attempts> times\n"));
+        // One assertion per Then step, including the `And` that continues it
+        assert_eq!(code.matches("expect(true)").count(), 2);
+    }
+
+    #[test]
+    fn test_jest_threshold_asserts_the_limit() {
+        let content = "## Non-Functional Requirements\n\n- The API shall respond within 200ms for 95% of API calls\n- Uploads shall be limited to 25 MB\n";
+        let reqs = crate::services::spec_parser::parse_spec("spec-1", content);
+        let code = generate_jest_test(&reqs[0], &[]);
+        assert!(code.contains("    // Threshold: p95 duration < 200 ms (within 200ms)\n"));
+        assert!(code.contains("const duration = durations[Math.ceil(durations.length * 0.95) - 1];"));
+        assert!(code.contains("    expect(duration).toBeLessThan(200);\n"));
+        assert!(!code.contains("expect(true)"));
+
+        let code = generate_jest_test(&reqs[1], &[]);
+        assert!(code.contains("expect(sizeBytes).toBeLessThanOrEqual(25000000);"));
+    }
+
+    #[test]
+    fn test_pytest_threshold_times_the_call() {
+        let content = "## Non-Functional Requirements\n\n- Search shall return results in under 2 seconds\n";
+        let reqs = crate::services::spec_parser::parse_spec("spec-1", content);
+        let code = generate_pytest_test(&reqs[0], &[]);
+        assert!(code.contains("import time\n"));
+        assert!(!code.contains("import math"));
+        assert!(code.contains("        duration = (time.perf_counter() - start) * 1000\n"));
+        assert!(code.contains("        assert duration < 2000\n"));
+    }
+
+    #[test]
+    fn test_pytest_scenario_outline_is_parametrized() {
+        let reqs = parse_feature("spec-1", OUTLINE, ParserRules::builtin());
+        let code = generate_pytest_test(&reqs[0], &[]);
+        assert!(code.starts_with("# Requirement: Lockout after failed attempts"));
+        assert!(code.contains("import pytest"));
+        assert!(code.contains("@pytest.mark.parametrize(\"attempts,state\", ["));
+        assert!(code.contains("(\"5\", \"locked\"),"));
+        assert!(code.contains("(self, attempts, state):"));
+        assert!(code.contains("        # Given a registered user\n"));
+    }
+}
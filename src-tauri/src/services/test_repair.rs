@@ -0,0 +1,189 @@
+use crate::models::test::GeneratedTest;
+use crate::services::test_runner::{self, ExecutionResult};
+
+/// Most test output sent back to the model, taken from the end
+const MAX_OUTPUT_CHARS: usize = 4000;
+
+/// Output of a test that never got as far as running: it doesn't parse,
+/// imports something that doesn't exist or has nothing to run.
+const JEST_SETUP_ERRORS: &[&str] = &[
+    "SyntaxError",
+    "Cannot find module",
+    "Test suite failed to run",
+    "Jest encountered an unexpected token",
+    "Your test suite must contain at least one test",
+    "No tests found",
+];
+
+/// Errors that are the test's fault only when the test itself raised them,
+/// such as calling code that doesn't exist or with the wrong arguments.
+const JEST_CALL_ERRORS: &[&str] = &[
+    "ReferenceError",
+    "is not a function",
+    "is not a constructor",
+    "is not defined",
+];
+
+const PYTEST_SETUP_ERRORS: &[&str] = &[
+    "SyntaxError",
+    "IndentationError",
+    "ImportError",
+    "ModuleNotFoundError",
+    "ERROR collecting",
+    "fixture '",
+];
+
+const PYTEST_CALL_ERRORS: &[&str] = &[
+    "NameError",
+    "positional argument",
+    "unexpected keyword argument",
+    "has no attribute",
+];
+
+/// Whether a test failed for reasons unrelated to the requirement: it
+/// doesn't parse, imports something that doesn't exist, uses a missing
+/// fixture or calls code with the wrong signature. Those are worth a repair;
+/// a failed assertion is the test doing its job, and so is an error raised
+/// inside the code under test. Timeouts and runners that won't start aren't
+/// the test's fault either way.
+pub fn is_setup_failure(test: &GeneratedTest, result: &ExecutionResult) -> bool {
+    if result.status != "failed" {
+        return false;
+    }
+    let (setup_errors, call_errors) = match test.framework.as_str() {
+        "pytest" => (PYTEST_SETUP_ERRORS, PYTEST_CALL_ERRORS),
+        _ => (JEST_SETUP_ERRORS, JEST_CALL_ERRORS),
+    };
+    let test_file = test_runner::test_file_name(test);
+    [&result.stdout, &result.stderr].iter().any(|output| {
+        setup_errors.iter().any(|marker| output.contains(marker))
+            || call_errors.iter().any(|marker| {
+                output.match_indices(marker).any(|(at, _)| raised_in(&output[at..], &test_file))
+            })
+    })
+}
+
+/// Whether the first stack frame after an error message is in `test_file`.
+/// Jest lists the innermost frame first after the message; pytest follows
+/// the message with the location that raised it.
+fn raised_in(output: &str, test_file: &str) -> bool {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("at ") || is_pytest_location(line))
+        .is_some_and(|frame| frame.contains(test_file))
+}
+
+/// Lines like `tests/test_auth.py:12: TypeError`.
+fn is_pytest_location(line: &str) -> bool {
+    line.split_once(".py:")
+        .and_then(|(_, rest)| rest.split_once(':'))
+        .is_some_and(|(number, _)| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// How a generate-run-repair loop ended, from the last run's result.
+pub fn outcome(test: &GeneratedTest, result: &ExecutionResult) -> &'static str {
+    if result.status == "passed" {
+        "passed"
+    } else if result.status == "failed" && !is_setup_failure(test, result) {
+        "failed"
+    } else {
+        "broken"
+    }
+}
+
+/// The end of the run's output, where test runners put the error.
+pub fn failure_output(result: &ExecutionResult) -> String {
+    let output = format!("{}\n{}", result.stdout.trim(), result.stderr.trim());
+    let output = output.trim();
+    match output.char_indices().rev().nth(MAX_OUTPUT_CHARS - 1) {
+        Some((start, _)) => format!("...{}", &output[start..]),
+        None => output.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(status: &str, stderr: &str) -> ExecutionResult {
+        ExecutionResult { status: status.into(), execution_time_ms: 10, stdout: String::new(), stderr: stderr.into() }
+    }
+
+    fn test(framework: &str) -> GeneratedTest {
+        GeneratedTest {
+            id: "t1".into(),
+            requirement_id: "r1".into(),
+            framework: framework.into(),
+            code: String::new(),
+            generation_mode: "llm".into(),
+            file_path: None,
+            created_at: String::new(),
+            version: 1,
+            previous_version_id: None,
+            repair_outcome: None,
+        }
+    }
+
+    #[test]
+    fn test_setup_failures_are_repaired() {
+        let (jest, pytest) = (test("jest"), test("pytest"));
+        let import = run("failed", "Cannot find module '../src/auth' from 't1.test.js'");
+        assert!(is_setup_failure(&jest, &import));
+        assert_eq!(outcome(&jest, &import), "broken");
+        assert!(is_setup_failure(&jest, &run("failed", "No tests found, exiting with code 1")));
+
+        let fixture = run("failed", "E       fixture 'client' not found");
+        assert!(is_setup_failure(&pytest, &fixture));
+
+        let signature = run(
+            "failed",
+            ">       login('alice')\nE       TypeError: login() missing 1 required positional argument: 'password'\n\n.spec-companion-tests/test_t1.py:5: TypeError",
+        );
+        assert!(is_setup_failure(&pytest, &signature));
+
+        let undefined = run(
+            "failed",
+            "TypeError: auth.signIn is not a function\n\n  > 5 |   auth.signIn('alice');\n\n      at Object.<anonymous> (.spec-companion-tests/t1.test.js:5:8)",
+        );
+        assert!(is_setup_failure(&jest, &undefined));
+    }
+
+    #[test]
+    fn test_errors_from_the_code_under_test_are_kept() {
+        let jest = run(
+            "failed",
+            "TypeError: user.roles.includes is not a function\n\n      at hasRole (src/auth.js:12:22)\n      at Object.<anonymous> (.spec-companion-tests/t1.test.js:5:8)",
+        );
+        assert!(!is_setup_failure(&test("jest"), &jest));
+        assert_eq!(outcome(&test("jest"), &jest), "failed");
+
+        let pytest = run(
+            "failed",
+            "src/auth.py:12: in has_role\n    return user.roles.get(role)\nE   AttributeError: 'list' object has no attribute 'get'\n\nsrc/auth.py:12: AttributeError",
+        );
+        assert!(!is_setup_failure(&test("pytest"), &pytest));
+        assert_eq!(outcome(&test("pytest"), &pytest), "failed");
+    }
+
+    #[test]
+    fn test_assertion_failures_are_kept() {
+        let (jest, pytest) = (test("jest"), test("pytest"));
+        let assertion = run("failed", "expect(received).toBe(expected)\n\nExpected: 200\nReceived: 500");
+        assert!(!is_setup_failure(&jest, &assertion));
+        assert_eq!(outcome(&jest, &assertion), "failed");
+
+        assert!(!is_setup_failure(&pytest, &run("error", "Test timed out after 120s")));
+        assert_eq!(outcome(&pytest, &run("error", "Test timed out after 120s")), "broken");
+        assert_eq!(outcome(&pytest, &run("passed", "")), "passed");
+    }
+
+    #[test]
+    fn test_failure_output_keeps_the_end() {
+        let long = format!("{}ImportError: no module named app", "x".repeat(10_000));
+        let output = failure_output(&run("failed", &long));
+        assert!(output.starts_with("..."));
+        assert!(output.ends_with("ImportError: no module named app"));
+        assert_eq!(output.chars().count(), MAX_OUTPUT_CHARS + 3);
+    }
+}
@@ -1,10 +1,18 @@
 use std::process::Command;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use wait_timeout::ChildExt;
 use crate::errors::AppError;
+use crate::models::test::GeneratedTest;
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(120);
+/// How often a running test checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Where self-heal writes tests that haven't been saved while they run,
+/// inside the codebase so their relative imports resolve as they would once
+/// saved.
+pub const SCRATCH_DIR: &str = ".spec-companion-tests";
 
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
@@ -14,7 +22,7 @@ pub struct ExecutionResult {
     pub stderr: String,
 }
 
-fn run_with_timeout(mut child: std::process::Child, timeout: Duration, start: Instant) -> ExecutionResult {
+fn run_with_timeout(mut child: std::process::Child, timeout: Duration, start: Instant, stop: &AtomicBool) -> ExecutionResult {
     let elapsed_fn = || start.elapsed().as_millis() as i64;
 
     // Read stdout/stderr in separate threads to avoid pipe deadlocks
@@ -33,7 +41,24 @@ fn run_with_timeout(mut child: std::process::Child, timeout: Duration, start: In
         })
     });
 
-    match child.wait_timeout(timeout) {
+    let waited = loop {
+        match child.wait_timeout(POLL_INTERVAL) {
+            Ok(None) if stop.load(Ordering::SeqCst) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return ExecutionResult {
+                    status: "error".to_string(),
+                    execution_time_ms: elapsed_fn(),
+                    stdout: String::new(),
+                    stderr: "Test run was cancelled".to_string(),
+                };
+            }
+            Ok(None) if start.elapsed() < timeout => continue,
+            waited => break waited,
+        }
+    };
+
+    match waited {
         Ok(Some(status)) => {
             let stdout = stdout_handle.and_then(|h| h.join().ok()).unwrap_or_default();
             let stderr = stderr_handle.and_then(|h| h.join().ok()).unwrap_or_default();
@@ -65,7 +90,7 @@ fn run_with_timeout(mut child: std::process::Child, timeout: Duration, start: In
     }
 }
 
-pub fn run_jest_test(test_file: &str, working_dir: &str) -> Result<ExecutionResult, AppError> {
+pub fn run_jest_test(test_file: &str, working_dir: &str, stop: &AtomicBool) -> Result<ExecutionResult, AppError> {
     let start = Instant::now();
 
     let npx = if cfg!(target_os = "windows") { "npx.cmd" } else { "npx" };
@@ -80,7 +105,7 @@ pub fn run_jest_test(test_file: &str, working_dir: &str) -> Result<ExecutionResu
         .spawn();
 
     match result {
-        Ok(child) => Ok(run_with_timeout(child, TEST_TIMEOUT, start)),
+        Ok(child) => Ok(run_with_timeout(child, TEST_TIMEOUT, start, stop)),
         Err(e) => Ok(ExecutionResult {
             status: "error".to_string(),
             execution_time_ms: start.elapsed().as_millis() as i64,
@@ -90,7 +115,7 @@ pub fn run_jest_test(test_file: &str, working_dir: &str) -> Result<ExecutionResu
     }
 }
 
-pub fn run_pytest_test(test_file: &str, working_dir: &str) -> Result<ExecutionResult, AppError> {
+pub fn run_pytest_test(test_file: &str, working_dir: &str, stop: &AtomicBool) -> Result<ExecutionResult, AppError> {
     let start = Instant::now();
 
     let python = find_python();
@@ -105,7 +130,7 @@ pub fn run_pytest_test(test_file: &str, working_dir: &str) -> Result<ExecutionRe
         .spawn();
 
     match result {
-        Ok(child) => Ok(run_with_timeout(child, TEST_TIMEOUT, start)),
+        Ok(child) => Ok(run_with_timeout(child, TEST_TIMEOUT, start, stop)),
         Err(e) => Ok(ExecutionResult {
             status: "error".to_string(),
             execution_time_ms: start.elapsed().as_millis() as i64,
@@ -115,6 +140,68 @@ pub fn run_pytest_test(test_file: &str, working_dir: &str) -> Result<ExecutionRe
     }
 }
 
+/// Runs a generated test from its saved file, or from a temporary file when
+/// it hasn't been saved to disk. Setting `stop` kills the test process.
+pub fn run_test(test: &GeneratedTest, codebase_path: &str, stop: &AtomicBool) -> Result<ExecutionResult, AppError> {
+    run_from(test, codebase_path, &std::env::temp_dir().join("spec-companion-tests"), stop)
+}
+
+/// Like `run_test`, but an unsaved test runs from a scratch file in the
+/// codebase, so that it fails the way it would once saved next to the code.
+pub fn run_test_in_codebase(test: &GeneratedTest, codebase_path: &str, stop: &AtomicBool) -> Result<ExecutionResult, AppError> {
+    run_from(test, codebase_path, &Path::new(codebase_path).join(SCRATCH_DIR), stop)
+}
+
+fn run_from(test: &GeneratedTest, codebase_path: &str, scratch_dir: &Path, stop: &AtomicBool) -> Result<ExecutionResult, AppError> {
+    let scratch;
+    let test_file_path = match &test.file_path {
+        Some(path) => path.clone(),
+        None => {
+            scratch = ScratchFile(scratch_dir.join(test_file_name(test)));
+            let write = || {
+                std::fs::create_dir_all(scratch_dir)?;
+                std::fs::write(&scratch.0, &test.code)
+            };
+            // A run finishing alongside may remove the directory in between
+            write().or_else(|_| write())?;
+            scratch.0.to_string_lossy().to_string()
+        }
+    };
+
+    match test.framework.as_str() {
+        "pytest" => run_pytest_test(&test_file_path, codebase_path, stop),
+        _ => run_jest_test(&test_file_path, codebase_path, stop),
+    }
+}
+
+/// A test written out to run. Removed, with its directory unless other tests
+/// are still running from it, however the run ends.
+struct ScratchFile(PathBuf);
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+        if let Some(dir) = self.0.parent() {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Name of the file a test runs from, as it appears in the runner's stack
+/// traces.
+pub fn test_file_name(test: &GeneratedTest) -> String {
+    match &test.file_path {
+        Some(path) => PathBuf::from(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        None => match test.framework.as_str() {
+            "pytest" => format!("test_{}.py", test.id.replace('-', "_")),
+            _ => format!("{}.test.js", test.id),
+        },
+    }
+}
+
 fn find_python() -> String {
     // Try python3 first, fall back to python
     if let Ok(output) = Command::new("python3").arg("--version").output() {
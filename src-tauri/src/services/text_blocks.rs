@@ -0,0 +1,226 @@
+use std::ops::Range;
+use crate::models::spec::Requirement;
+use crate::services::detection_rules::ParserRules;
+use crate::services::spec_parser::{split_external_id, LineIndex, RequirementCollector};
+
+/// The parts of a line-oriented markup document (reStructuredText, AsciiDoc)
+/// that requirement detection looks at, in document order.
+#[derive(Debug, PartialEq)]
+pub enum TextBlock {
+    Heading(String),
+    /// `depth` is 0 for top-level items
+    ListItem { depth: usize, text: String, range: Range<usize> },
+    Paragraph { text: String, range: Range<usize> },
+}
+
+/// A line of a document with the byte offset it starts at. `text` excludes
+/// the line ending.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub start: usize,
+    pub text: &'a str,
+}
+
+impl Line<'_> {
+    pub fn indent(&self) -> usize {
+        self.text.len() - self.text.trim_start().len()
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// Offset just past the line's last non-whitespace character.
+    pub fn end(&self) -> usize {
+        self.start + self.text.trim_end().len()
+    }
+}
+
+/// A list item or paragraph whose lines are still being read.
+#[derive(Debug)]
+pub struct PendingBlock {
+    /// Set for list items
+    pub depth: Option<usize>,
+    pub lines: Vec<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl PendingBlock {
+    /// An item whose marker starts the line's text and whose own text is `text`.
+    pub fn item(depth: usize, line: Line, text: &str) -> Self {
+        Self {
+            depth: Some(depth),
+            lines: vec![text.trim().to_string()],
+            start: line.start + line.indent(),
+            end: line.end(),
+        }
+    }
+
+    pub fn paragraph(line: Line) -> Self {
+        Self {
+            depth: None,
+            lines: vec![line.text.trim().to_string()],
+            start: line.start + line.indent(),
+            end: line.end(),
+        }
+    }
+
+    pub fn push_line(&mut self, line: Line) {
+        self.lines.push(line.text.trim().to_string());
+        self.end = line.end();
+    }
+
+    pub fn into_block(self) -> TextBlock {
+        let text = strip_inline_markup(&self.lines.join(" "));
+        let range = self.start..self.end;
+        match self.depth {
+            Some(depth) => TextBlock::ListItem { depth, text, range },
+            None => TextBlock::Paragraph { text, range },
+        }
+    }
+}
+
+pub fn split_lines(content: &str) -> Vec<Line<'_>> {
+    let mut start = 0;
+    content
+        .split_inclusive('\n')
+        .map(|raw| {
+            let line = Line {
+                start,
+                text: raw.trim_end_matches(['\n', '\r']),
+            };
+            start += raw.len();
+            line
+        })
+        .collect()
+}
+
+/// Turns blocks into requirements with the same rules the Markdown parser
+/// applies: items under requirement headings, items and paragraphs that read
+/// like requirements or carry an external ID, and sub-items of requirements
+/// as their acceptance criteria.
+pub fn collect_requirements(
+    spec_id: &str,
+    content: &str,
+    rules: &ParserRules,
+    blocks: Vec<TextBlock>,
+) -> Vec<Requirement> {
+    let lines = LineIndex::new(content, 0);
+    let mut collector = RequirementCollector::new(spec_id).with_rules(rules);
+    let mut section = String::from("General");
+    let mut is_requirement_section = false;
+    // (depth, requirement ID) of each open list item, outermost first
+    let mut items: Vec<(usize, Option<String>)> = Vec::new();
+
+    for block in blocks {
+        match block {
+            TextBlock::Heading(text) => {
+                section = text;
+                is_requirement_section = rules.is_requirement_section(&section);
+                items.clear();
+            }
+            TextBlock::ListItem { depth, text, range } => {
+                while items.last().is_some_and(|(d, _)| *d >= depth) {
+                    items.pop();
+                }
+                let parent_id = items.iter().rev().find_map(|(_, id)| id.clone());
+                let (external_id, text) = split_external_id(text.trim());
+                let qualifies = !text.is_empty()
+                    && (external_id.is_some()
+                        || parent_id.is_some()
+                        || is_requirement_section
+                        || rules.looks_like_requirement(&text));
+                let id = qualifies.then(|| {
+                    let req = collector.push(&section, external_id, text, None, None);
+                    req.parent_id = parent_id;
+                    req.span = Some(lines.span(range));
+                    req.id.clone()
+                });
+                items.push((depth, id));
+            }
+            TextBlock::Paragraph { text, range } => {
+                items.clear();
+                let (external_id, text) = split_external_id(text.trim());
+                if text.is_empty() {
+                    continue;
+                }
+                if external_id.is_some() || rules.looks_like_requirement(&text) || rules.has_normative_keyword(&text) {
+                    let req = collector.push(&section, external_id, text, None, None);
+                    req.span = Some(lines.span(range));
+                }
+            }
+        }
+    }
+
+    collector.finish()
+}
+
+/// Removes emphasis and literal markers (`*`, `**`, `` ` ``, ``` `` ```) and
+/// reStructuredText role prefixes (`:term:`), leaving the plain text.
+/// A marker only counts when it opens or closes a word, so `2 * 3` is kept.
+pub fn strip_inline_markup(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let is_marker = |c: char| c == '*' || c == '`';
+    let is_edge = |c: Option<&char>| match c {
+        Some(c) => c.is_whitespace() || (c.is_ascii_punctuation() && !is_marker(*c)),
+        None => true,
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // `:role:` directly before a backtick
+        if c == ':' && (i == 0 || is_edge(chars.get(i - 1))) {
+            if let Some(len) = role_len(&chars[i..]) {
+                i += len;
+                continue;
+            }
+        }
+        if is_marker(c) {
+            let run_end = (i..chars.len()).find(|&j| chars[j] != c).unwrap_or(chars.len());
+            let before = if i == 0 { None } else { chars.get(i - 1) };
+            let after = chars.get(run_end);
+            if is_edge(before) != is_edge(after) {
+                i = run_end;
+                continue;
+            }
+            out.extend(&chars[i..run_end]);
+            i = run_end;
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+/// Length of a `:role:` prefix followed by a backtick, if `chars` starts with one.
+fn role_len(chars: &[char]) -> Option<usize> {
+    let close = chars.iter().skip(1).position(|&c| c == ':')? + 1;
+    let name = &chars[1..close];
+    let valid = !name.is_empty() && name.iter().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    (valid && chars.get(close + 1) == Some(&'`')).then_some(close + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_inline_markup() {
+        assert_eq!(strip_inline_markup("The system **must** log in"), "The system must log in");
+        assert_eq!(strip_inline_markup("Use ``cargo test`` and *care*"), "Use cargo test and care");
+        assert_eq!(strip_inline_markup("See :ref:`export` for 2 * 3"), "See export for 2 * 3");
+    }
+
+    #[test]
+    fn test_split_lines_offsets() {
+        let content = "one\r\ntwo\n\nthree";
+        let lines = split_lines(content);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1].text, "two");
+        assert_eq!(&content[lines[1].start..lines[1].end()], "two");
+        assert_eq!(&content[lines[3].start..lines[3].end()], "three");
+    }
+}
@@ -0,0 +1,224 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+use regex::Regex;
+use crate::models::spec::Threshold;
+use crate::services::words::{MODAL_VERBS, STOP_WORDS};
+
+/// A number with a recognised unit, optionally preceded by a comparison
+/// ("within 200ms", "at least 1,000 requests per second", "<= 5 MB").
+const QUANTITY_PATTERN: &str = r"(?x)
+    (?:(?P<op>
+        \b(?:within | under | below | less\s+than | fewer\s+than | faster\s+than | at\s+most
+        | no\s+more\s+than | up\s+to | not\s+exceed(?:ing)? | maximum\s+of | max(?:imum)?
+        | at\s+least | no\s+less\s+than | no\s+fewer\s+than | minimum\s+of | min(?:imum)?
+        | more\s+than | greater\s+than | over | above | exceeds?)
+        | <= | >= | < | > | ≤ | ≥
+    )\s*)?
+    \b(?P<value>\d+(?:,\d{3})*(?:\.\d+)?)\s*
+    (?P<unit>
+        %
+        | (?:percent | milliseconds? | ms | seconds? | secs? | s | minutes? | mins? | hours? | hrs? | h
+            | kb | mb | gb | bytes? | rps | qps | tps
+            | (?:requests? | req | queries | transactions | calls | messages | events)\s*(?:/|per)\s*(?:second|sec|s)
+            | (?:concurrent\s+)?(?:users | connections | sessions)
+        )\b
+    )";
+
+/// "p95", "99th percentile" or "95% of <things>".
+const PERCENTILE_PATTERN: &str =
+    r"\bp(?P<p>\d+(?:\.\d+)?)\b|\b(?P<nth>\d+(?:\.\d+)?)(?:th|st|nd|rd)\s+percentile\b|\b(?P<share>\d+(?:\.\d+)?)\s*%\s+of\b";
+
+fn quantity_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(QUANTITY_PATTERN).expect("quantity pattern is valid"))
+}
+
+fn percentile_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(PERCENTILE_PATTERN).expect("percentile pattern is valid"))
+}
+
+/// Reads the first numeric limit from a requirement's text, with its
+/// comparison and percentile. Without an explicit comparison, durations and
+/// sizes are upper bounds and rates, percentages and counts lower bounds.
+pub fn parse_threshold(text: &str) -> Option<Threshold> {
+    let lower = text.to_lowercase();
+
+    let percentiles: Vec<(Range<usize>, f64)> = percentile_regex()
+        .captures_iter(&lower)
+        .filter_map(|c| {
+            let m = c.name("p").or_else(|| c.name("nth")).or_else(|| c.name("share"))?;
+            Some((c.get(0)?.range(), m.as_str().parse().ok()?))
+        })
+        .collect();
+
+    let quantities: Vec<regex::Captures> = quantity_regex().captures_iter(&lower).collect();
+    let overlaps = |c: &regex::Captures| {
+        let range = c.name("value").map_or(0..0, |m| m.range());
+        percentiles.iter().any(|(p, _)| p.start < range.end && range.start < p.end)
+    };
+    // "99.9% of the time" is the limit itself when nothing else is measured
+    let quantity = quantities.iter().find(|c| !overlaps(c)).or_else(|| quantities.first())?;
+    let percentile = if overlaps(quantity) { None } else { percentiles.first().map(|(_, p)| *p) };
+
+    let value: f64 = quantity["value"].replace(',', "").parse().ok()?;
+    let (metric, unit, factor) = classify_unit(&quantity["unit"])?;
+    let operator = match quantity.name("op") {
+        Some(op) => normalize_operator(op.as_str()),
+        None if matches!(metric, "duration" | "size") => "<=",
+        None => ">=",
+    };
+
+    Some(Threshold {
+        metric: metric.to_string(),
+        operator: operator.to_string(),
+        value: value * factor,
+        unit,
+        percentile,
+        text: quantity[0].trim().to_string(),
+    })
+}
+
+/// A number with its unit, as found in tokenized text.
+pub struct Quantity {
+    /// Value in the unit's base (milliseconds, bytes)
+    pub value: f64,
+    pub unit: String,
+    /// As written, e.g. "200ms" or "5 seconds"
+    pub text: String,
+}
+
+/// A number with its unit starting at `tokens[i]` ("200ms", "5 seconds",
+/// "5-second", "99.9%"), and how many tokens it spans. Bare numbers are not
+/// limits; units other than the measured ones ("7 years", "12 characters")
+/// compare by their singular.
+pub fn quantity_at(tokens: &[String], i: usize) -> Option<(Quantity, usize)> {
+    let token = &tokens[i];
+    let digits = token.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(token.len());
+    if digits == 0 {
+        return None;
+    }
+    let value: f64 = token[..digits].parse().ok()?;
+    let (unit, consumed) = match &token[digits..] {
+        "" => {
+            let next = tokens.get(i + 1)?;
+            let is_word = next.chars().all(char::is_alphabetic)
+                && !STOP_WORDS.contains(&next.as_str())
+                && !MODAL_VERBS.contains(&next.as_str());
+            if !is_word {
+                return None;
+            }
+            (next.as_str(), 2)
+        }
+        suffix => (suffix.trim_start_matches('-'), 1),
+    };
+    let (unit, factor) = match classify_unit(unit) {
+        Some((_, base, factor)) => (base, factor),
+        None => (unit.strip_suffix('s').unwrap_or(unit).to_string(), 1.0),
+    };
+    Some((
+        Quantity { value: value * factor, unit, text: tokens[i..i + consumed].join(" ") },
+        consumed,
+    ))
+}
+
+/// Metric, base unit and conversion factor of a unit as written, so "2s"
+/// and "2000 ms" compare equal. `None` for units that aren't measured.
+fn classify_unit(unit: &str) -> Option<(&'static str, String, f64)> {
+    let unit = unit.split_whitespace().collect::<Vec<_>>().join(" ");
+    let duration = |factor: f64| ("duration", "ms".to_string(), factor);
+    let size = |factor: f64| ("size", "bytes".to_string(), factor);
+    Some(match unit.as_str() {
+        "ms" | "millisecond" | "milliseconds" => duration(1.0),
+        "s" | "sec" | "secs" | "second" | "seconds" => duration(1_000.0),
+        "min" | "mins" | "minute" | "minutes" => duration(60_000.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" => duration(3_600_000.0),
+        "day" | "days" => duration(86_400_000.0),
+        "b" | "byte" | "bytes" => size(1.0),
+        "kb" => size(1_000.0),
+        "mb" => size(1_000_000.0),
+        "gb" => size(1_000_000_000.0),
+        "%" | "percent" => ("percentage", "%".to_string(), 1.0),
+        u if u.contains("user") || u.contains("connection") || u.contains("session") => {
+            let noun = u.rsplit(' ').next().unwrap_or(u);
+            ("count", noun.to_string(), 1.0)
+        }
+        "rps" | "qps" | "tps" => ("throughput", "per_second".to_string(), 1.0),
+        u if u.contains('/') || u.contains(" per ") => ("throughput", "per_second".to_string(), 1.0),
+        _ => return None,
+    })
+}
+
+fn normalize_operator(op: &str) -> &'static str {
+    let op = op.split_whitespace().collect::<Vec<_>>().join(" ");
+    match op.as_str() {
+        "within" | "under" | "below" | "less than" | "fewer than" | "faster than" | "<" => "<",
+        "at most" | "no more than" | "up to" | "not exceed" | "not exceeding" | "maximum of" | "max"
+        | "maximum" | "<=" | "≤" => "<=",
+        "more than" | "greater than" | "over" | "above" | "exceed" | "exceeds" | ">" => ">",
+        _ => ">=",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::words::tokenize;
+
+    #[test]
+    fn test_latency_with_percentile() {
+        let t = parse_threshold("The API shall respond within 200ms for 95% of API calls").unwrap();
+        assert_eq!(t.metric, "duration");
+        assert_eq!(t.operator, "<");
+        assert_eq!(t.value, 200.0);
+        assert_eq!(t.unit, "ms");
+        assert_eq!(t.percentile, Some(95.0));
+        assert_eq!(t.text, "within 200ms");
+
+        let t = parse_threshold("p99 latency must be at most 1.5 seconds").unwrap();
+        assert_eq!((t.operator.as_str(), t.value, t.percentile), ("<=", 1_500.0, Some(99.0)));
+    }
+
+    #[test]
+    fn test_default_operators_and_units() {
+        let t = parse_threshold("The service shall handle 1,000 requests per second").unwrap();
+        assert_eq!((t.metric.as_str(), t.operator.as_str(), t.value, t.unit.as_str()), ("throughput", ">=", 1_000.0, "per_second"));
+
+        let t = parse_threshold("Uploaded files are limited to 25 MB").unwrap();
+        assert_eq!((t.metric.as_str(), t.operator.as_str(), t.value), ("size", "<=", 25_000_000.0));
+
+        let t = parse_threshold("The system must support more than 500 concurrent users").unwrap();
+        assert_eq!((t.metric.as_str(), t.operator.as_str(), t.unit.as_str()), ("count", ">", "users"));
+    }
+
+    #[test]
+    fn test_share_of_time_is_the_limit_itself() {
+        let t = parse_threshold("The service shall be available 99.9% of the time").unwrap();
+        assert_eq!((t.metric.as_str(), t.operator.as_str(), t.value, t.percentile), ("percentage", ">=", 99.9, None));
+    }
+
+    #[test]
+    fn test_quantities() {
+        let tokens = tokenize("Uploads up to 1,500 MB within 2.5s, a 5-second timeout and 99.9% uptime for 7 years");
+        let found: Vec<(f64, String)> = (0..tokens.len())
+            .filter_map(|i| quantity_at(&tokens, i))
+            .map(|(q, _)| (q.value, q.unit))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1_500_000_000.0, "bytes".to_string()),
+                (2_500.0, "ms".to_string()),
+                (5_000.0, "ms".to_string()),
+                (99.9, "%".to_string()),
+                (7.0, "year".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_without_limits() {
+        assert!(parse_threshold("Pages shall load quickly").is_none());
+        assert!(parse_threshold("Support 3 languages").is_none());
+    }
+}
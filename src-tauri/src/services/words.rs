@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+/// Function words, and words nearly every requirement uses, that say nothing
+/// about what a requirement is about.
+pub const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "of", "to", "in", "on", "for", "and", "or", "be", "is", "are", "was", "were",
+    "been", "being", "have", "has", "had", "that", "this", "with", "from", "into", "by", "as", "at",
+    "it", "its", "their", "all", "any", "each", "every", "not", "when", "then", "than", "able",
+    "system",
+];
+
+/// Verbs that state an obligation or permission.
+pub const MODAL_VERBS: &[&str] = &["shall", "must", "should", "will", "would", "may", "can", "could"];
+
+/// Word-set similarity from which two requirements count as duplicates.
+pub const DUPLICATE_THRESHOLD: f64 = 0.85;
+
+/// Lowercase words. Hyphens and apostrophes stay inside words
+/// ("user-friendly", "can't"); decimal points, percent signs and digit
+/// grouping stay inside numbers ("1,500" -> "1500", "99.9%").
+pub fn tokenize(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut cleaned = String::with_capacity(chars.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let between_digits = i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+        match c {
+            ',' if between_digits => {}
+            '.' if between_digits => cleaned.push('.'),
+            c if c.is_alphanumeric() || matches!(c, '%' | '\'' | '-') => cleaned.push(c),
+            _ => cleaned.push(' '),
+        }
+    }
+    cleaned
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c| c == '-' || c == '\''))
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("The UI shall be user-friendly, etc."), vec!["the", "ui", "shall", "be", "user-friendly", "etc"]);
+        assert_eq!(tokenize("Users can't upload 1,500 MB"), vec!["users", "can't", "upload", "1500", "mb"]);
+        assert_eq!(tokenize("99.9% uptime. Done."), vec!["99.9%", "uptime", "done"]);
+    }
+}
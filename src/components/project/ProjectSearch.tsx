@@ -0,0 +1,67 @@
+import { useState } from "react";
+import { Link } from "react-router-dom";
+import { useSearch } from "../../hooks/useSearch";
+import type { SearchHit } from "../../lib/types";
+
+interface Props {
+  projectId: string;
+}
+
+const groups: { key: "specs" | "requirements" | "tests"; label: string }[] = [
+  { key: "specs", label: "Specs" },
+  { key: "requirements", label: "Requirements" },
+  { key: "tests", label: "Generated Tests" },
+];
+
+function hitLink(projectId: string, hit: SearchHit) {
+  return hit.entity_type === "test" ? `/project/${projectId}/generate` : `/project/${projectId}/spec/${hit.spec_id}`;
+}
+
+export function ProjectSearch({ projectId }: Props) {
+  const [query, setQuery] = useState("");
+  const { data: results, isError, error } = useSearch(projectId, query);
+  const active = query.trim().length >= 2;
+
+  return (
+    <div className="mb-8">
+      <input
+        type="search"
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+        placeholder="Search specs, requirements and tests..."
+        className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text"
+      />
+      {active && isError && <p className="text-sm text-danger mt-2">{String(error)}</p>}
+      {active && results && (
+        <div className="mt-3 space-y-4">
+          {groups.every((g) => results[g.key].length === 0) && (
+            <p className="text-sm text-text-muted">No matches.</p>
+          )}
+          {groups
+            .filter((g) => results[g.key].length > 0)
+            .map((g) => (
+              <div key={g.key}>
+                <h4 className="text-sm font-medium text-text-muted mb-2">
+                  {g.label} ({results[g.key].length})
+                </h4>
+                <div className="space-y-1">
+                  {results[g.key].map((hit) => (
+                    <Link
+                      key={hit.entity_id}
+                      to={hitLink(projectId, hit)}
+                      className="block p-3 rounded-lg border border-border bg-surface hover:bg-surface-hover text-sm"
+                    >
+                      {/* Escaped by the backend; only <mark> is markup */}
+                      <p className="text-text" dangerouslySetInnerHTML={{ __html: hit.title }} />
+                      <p className="text-xs text-text-muted mt-1" dangerouslySetInnerHTML={{ __html: hit.snippet }} />
+                      <p className="text-xs text-text-muted mt-1">{hit.spec_filename}</p>
+                    </Link>
+                  ))}
+                </div>
+              </div>
+            ))}
+        </div>
+      )}
+    </div>
+  );
+}
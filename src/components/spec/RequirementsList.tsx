@@ -1,10 +1,14 @@
-import type { Requirement } from "../../lib/types";
+import type { LintFinding, Requirement, RequirementStatus } from "../../lib/types";
 
 interface Props {
   requirements: Requirement[];
   selectable?: boolean;
   selected?: Set<string>;
   onToggle?: (id: string) => void;
+  findings?: LintFinding[];
+  onStatusChange?: (requirement: Requirement, status: RequirementStatus) => void;
+  onEdit?: (requirement: Requirement) => void;
+  onDelete?: (requirement: Requirement) => void;
 }
 
 const typeBadgeColors: Record<string, string> = {
@@ -19,7 +23,14 @@ const priorityBadgeColors: Record<string, string> = {
   low: "text-text-muted",
 };
 
-export function RequirementsList({ requirements, selectable, selected, onToggle }: Props) {
+const statuses: RequirementStatus[] = ["draft", "approved", "deprecated", "waived"];
+
+const severityColors: Record<string, string> = {
+  warning: "text-warning",
+  info: "text-text-muted",
+};
+
+export function RequirementsList({ requirements, selectable, selected, onToggle, findings, onStatusChange, onEdit, onDelete }: Props) {
   // Group by section
   const grouped = requirements.reduce<Record<string, Requirement[]>>((acc, req) => {
     if (!acc[req.section]) acc[req.section] = [];
@@ -59,11 +70,63 @@ export function RequirementsList({ requirements, selectable, selected, onToggle
                     <span className={`text-xs px-1.5 py-0.5 rounded ${typeBadgeColors[req.req_type] ?? "bg-border text-text-muted"}`}>
                       {req.req_type.replace("_", "-")}
                     </span>
-                    <span className={`text-xs ${priorityBadgeColors[req.priority] ?? "text-text-muted"}`}>
+                    <span
+                      className={`text-xs ${priorityBadgeColors[req.priority] ?? "text-text-muted"}`}
+                      title={req.priority_keyword ? `Priority set by "${req.priority_keyword}"` : undefined}
+                    >
                       {req.priority}
                     </span>
+                    {onStatusChange ? (
+                      <select
+                        value={req.status}
+                        onChange={(e) => onStatusChange(req, e.target.value as RequirementStatus)}
+                        onClick={(e) => e.stopPropagation()}
+                        className="text-xs bg-transparent text-text-muted border border-border rounded px-1"
+                      >
+                        {statuses.map((s) => (
+                          <option key={s} value={s}>{s}</option>
+                        ))}
+                      </select>
+                    ) : (
+                      req.status !== "draft" && <span className="text-xs text-text-muted">{req.status}</span>
+                    )}
+                    {req.threshold && (
+                      <span className="text-xs text-text-muted" title="Asserted in generated tests">
+                        {req.threshold.percentile !== null && `p${req.threshold.percentile} `}
+                        {req.threshold.operator} {req.threshold.value} {req.threshold.unit}
+                      </span>
+                    )}
+                    {req.tags.map((tag) => (
+                      <span key={tag} className="text-xs text-primary-light">#{tag}</span>
+                    ))}
+                    {req.manually_edited && (
+                      <span className="text-xs text-text-muted" title="Kept as is when the spec is re-parsed">
+                        edited
+                      </span>
+                    )}
                   </div>
+                  {findings
+                    ?.filter((f) => f.requirement_id === req.id)
+                    .map((f) => (
+                      <p key={f.id} className={`text-xs mt-1 ${severityColors[f.severity] ?? "text-text-muted"}`}>
+                        {f.message}
+                      </p>
+                    ))}
                 </div>
+                {(onEdit || onDelete) && (
+                  <div className="flex gap-2 text-xs" onClick={(e) => e.stopPropagation()}>
+                    {onEdit && (
+                      <button onClick={() => onEdit(req)} className="text-text-muted hover:text-text">
+                        Edit
+                      </button>
+                    )}
+                    {onDelete && (
+                      <button onClick={() => onDelete(req)} className="text-text-muted hover:text-danger">
+                        Delete
+                      </button>
+                    )}
+                  </div>
+                )}
               </div>
             ))}
           </div>
@@ -13,7 +13,13 @@ export function SpecUploader({ projectId }: Props) {
     try {
       const selected = await open({
         multiple: false,
-        filters: [{ name: "Markdown", extensions: ["md", "txt", "markdown"] }],
+        filters: [
+          { name: "Markdown", extensions: ["md", "txt", "markdown"] },
+          { name: "Gherkin", extensions: ["feature"] },
+          { name: "reStructuredText", extensions: ["rst", "rest"] },
+          { name: "AsciiDoc", extensions: ["adoc", "asciidoc", "asc"] },
+          { name: "Requirements export", extensions: ["reqif", "csv"] },
+        ],
       });
       if (!selected || typeof selected !== "string") return;
 
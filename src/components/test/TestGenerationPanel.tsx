@@ -2,19 +2,51 @@ import { useState, useEffect, useRef } from "react";
 import { Highlight, themes } from "prism-react-renderer";
 import type { Requirement, GeneratedTest } from "../../lib/types";
 import { RequirementsList } from "../spec/RequirementsList";
-import { useGenerateTests, useSettings } from "../../hooks/useTestGeneration";
+import {
+  useCancelGeneration,
+  useGenerateTests,
+  useGeneratedTestVersions,
+  useGenerationProgress,
+  useSettings,
+} from "../../hooks/useTestGeneration";
+import { useTags } from "../../hooks/useTags";
 
 interface Props {
   projectId: string;
   requirements: Requirement[];
 }
 
-export function TestGenerationPanel({ projectId, requirements }: Props) {
+export function TestGenerationPanel({ projectId, requirements: allRequirements }: Props) {
   const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [tag, setTag] = useState("");
+  const { data: tags } = useTags(projectId);
+  const requirements = tag ? allRequirements.filter((r) => r.tags.includes(tag)) : allRequirements;
   const [framework, setFramework] = useState<"jest" | "pytest">("jest");
   const [mode, setMode] = useState<"template" | "llm">("template");
+  const [selfHeal, setSelfHeal] = useState(false);
   const [results, setResults] = useState<GeneratedTest[]>([]);
   const generateTests = useGenerateTests();
+  const cancelGeneration = useCancelGeneration();
+  // Code being written or run, by requirement; several requests can be in flight
+  const [streaming, setStreaming] = useState<Record<string, { status: string; code: string }>>({});
+  // Tests are shown as soon as each one is saved, not when the batch ends
+  const progress = useGenerationProgress((p) => {
+    setStreaming((prev) => {
+      const next = { ...prev };
+      if (p.status === "generating" || p.status === "running" || p.status === "repairing") {
+        next[p.current_requirement] = { status: p.status, code: p.partial_code };
+      }
+      else if (p.current_requirement) delete next[p.current_requirement];
+      else return {};
+      return next;
+    });
+    const test = p.test;
+    if (test) setResults((prev) => (prev.some((t) => t.id === test.id) ? prev : [...prev, test]));
+  });
+  const label = (requirementId: string) => {
+    const req = allRequirements.find((r) => r.id === requirementId);
+    return req?.external_id ?? req?.description ?? requirementId;
+  };
   const { data: settings } = useSettings();
   const defaultsApplied = useRef(false);
 
@@ -49,15 +81,19 @@ export function TestGenerationPanel({ projectId, requirements }: Props) {
   };
 
   const handleGenerate = () => {
+    setResults([]);
+    setStreaming({});
     generateTests.mutate(
       {
         requirement_ids: Array.from(selected),
         framework,
         mode,
         project_id: projectId,
+        tag: tag || undefined,
+        self_heal: mode === "llm" && selfHeal,
       },
       {
-        onSuccess: (data) => setResults(data),
+        onSuccess: (data) => setResults(data.tests),
       }
     );
   };
@@ -74,9 +110,15 @@ export function TestGenerationPanel({ projectId, requirements }: Props) {
             className="bg-surface border border-border rounded-lg px-3 py-1.5 text-sm text-text"
           >
             <option value="template">Template</option>
-            <option value="llm">LLM (Claude)</option>
+            <option value="llm">LLM</option>
           </select>
         </div>
+        {mode === "llm" && (
+          <label className="flex items-center gap-2 text-sm text-text-muted" title="Run each test and repair setup failures">
+            <input type="checkbox" checked={selfHeal} onChange={(e) => setSelfHeal(e.target.checked)} />
+            Self-heal
+          </label>
+        )}
         <div className="flex items-center gap-2">
           <label className="text-sm text-text-muted">Framework:</label>
           <select
@@ -88,18 +130,46 @@ export function TestGenerationPanel({ projectId, requirements }: Props) {
             <option value="pytest">PyTest</option>
           </select>
         </div>
+        {tags && tags.length > 0 && (
+          <div className="flex items-center gap-2">
+            <label className="text-sm text-text-muted">Tag:</label>
+            <select
+              value={tag}
+              onChange={(e) => {
+                setTag(e.target.value);
+                setSelected(new Set());
+              }}
+              className="bg-surface border border-border rounded-lg px-3 py-1.5 text-sm text-text"
+            >
+              <option value="">All</option>
+              {tags.map((t) => (
+                <option key={t.id} value={t.name}>#{t.name}</option>
+              ))}
+            </select>
+          </div>
+        )}
         <button
           onClick={selectAll}
           className="text-sm text-primary-light hover:text-primary transition-colors"
         >
           {selected.size === requirements.length ? "Deselect All" : "Select All"}
         </button>
+        {generateTests.isPending && (
+          <button
+            onClick={() => cancelGeneration.mutate(projectId)}
+            className="ml-auto px-4 py-2 border border-border hover:bg-surface-alt text-sm rounded-lg transition-colors"
+          >
+            Cancel
+          </button>
+        )}
         <button
           onClick={handleGenerate}
           disabled={selected.size === 0 || generateTests.isPending}
-          className="ml-auto px-4 py-2 bg-primary hover:bg-primary-dark text-white text-sm rounded-lg transition-colors disabled:opacity-50"
+          className={`${generateTests.isPending ? "" : "ml-auto "}px-4 py-2 bg-primary hover:bg-primary-dark text-white text-sm rounded-lg transition-colors disabled:opacity-50`}
         >
-          {generateTests.isPending ? "Generating..." : `Generate (${selected.size})`}
+          {generateTests.isPending
+            ? `Generating... (${progress?.completed ?? 0}/${progress?.total ?? selected.size})`
+            : `Generate (${selected.size})`}
         </button>
       </div>
 
@@ -112,35 +182,40 @@ export function TestGenerationPanel({ projectId, requirements }: Props) {
       />
 
       {/* Results */}
-      {results.length > 0 && (
+      {generateTests.data &&
+        (generateTests.data.cancelled || generateTests.data.errors.length > 0 || generateTests.data.repair_errors.length > 0) && (
+        <div className="rounded-lg bg-warning/10 border border-warning/30 p-3 text-sm space-y-1">
+          {generateTests.data.cancelled && <p>Generation cancelled; {results.length} tests were kept.</p>}
+          {generateTests.data.errors.map((e) => (
+            <p key={e.requirement_id}>
+              <span className="font-mono">{label(e.requirement_id)}</span>: {e.error}
+            </p>
+          ))}
+          {generateTests.data.repair_errors.map((e) => (
+            <p key={`repair-${e.requirement_id}`}>
+              <span className="font-mono">{label(e.requirement_id)}</span>: kept as broken. {e.error}
+            </p>
+          ))}
+        </div>
+      )}
+
+      {(results.length > 0 || Object.keys(streaming).length > 0) && (
         <div className="space-y-4">
           <h3 className="text-lg font-semibold">Generated Tests</h3>
           {results.map((test) => (
-            <div key={test.id} className="border border-border rounded-lg overflow-hidden">
-              <div className="flex items-center justify-between px-4 py-2 bg-surface-alt border-b border-border">
-                <span className="text-xs text-text-muted">
-                  {test.framework} | {test.generation_mode}
-                </span>
-              </div>
-              <Highlight
-                theme={themes.vsDark}
-                code={test.code}
-                language={test.framework === "pytest" ? "python" : "javascript"}
-              >
-                {({ style, tokens, getLineProps, getTokenProps }) => (
-                  <pre style={{ ...style, margin: 0, padding: "1rem" }} className="text-xs overflow-x-auto">
-                    {tokens.map((line, i) => (
-                      <div key={i} {...getLineProps({ line })}>
-                        {line.map((token, key) => (
-                          <span key={key} {...getTokenProps({ token })} />
-                        ))}
-                      </div>
-                    ))}
-                  </pre>
-                )}
-              </Highlight>
-            </div>
+            <GeneratedTestCard key={test.id} test={test} />
           ))}
+          {generateTests.isPending &&
+            Object.entries(streaming).map(([requirementId, { status, code }]) => (
+              <div key={requirementId} className="border border-primary/40 rounded-lg overflow-hidden">
+                <div className="flex items-center justify-between px-4 py-2 bg-surface-alt border-b border-border">
+                  <span className="text-xs text-text-muted">
+                    {label(requirementId)} | {framework} | {mode} | {status}...
+                  </span>
+                </div>
+                <CodeBlock code={code || "…"} framework={framework} />
+              </div>
+            ))}
         </div>
       )}
 
@@ -152,3 +227,71 @@ export function TestGenerationPanel({ projectId, requirements }: Props) {
     </div>
   );
 }
+
+const OUTCOME_STYLES: Record<string, string> = {
+  passed: "bg-success/10 text-success",
+  failed: "bg-warning/10 text-warning",
+  broken: "bg-danger/10 text-danger",
+};
+
+function GeneratedTestCard({ test }: { test: GeneratedTest }) {
+  const [showHistory, setShowHistory] = useState(false);
+  const { data: versions } = useGeneratedTestVersions(showHistory ? test.id : undefined);
+  const earlier = versions?.filter((v) => v.id !== test.id) ?? [];
+
+  return (
+    <div className="border border-border rounded-lg overflow-hidden">
+      <div className="flex items-center justify-between px-4 py-2 bg-surface-alt border-b border-border">
+        <span className="text-xs text-text-muted">
+          {test.framework} | {test.generation_mode}
+          {test.version > 1 && ` | v${test.version}`}
+        </span>
+        <div className="flex items-center gap-2">
+          {test.repair_outcome && (
+            <span className={`text-xs px-2 py-0.5 rounded ${OUTCOME_STYLES[test.repair_outcome]}`}>
+              {test.repair_outcome}
+            </span>
+          )}
+          {test.version > 1 && (
+            <button
+              onClick={() => setShowHistory(!showHistory)}
+              className="text-xs text-primary-light hover:text-primary transition-colors"
+            >
+              {showHistory ? "Hide attempts" : `Earlier attempts (${test.version - 1})`}
+            </button>
+          )}
+        </div>
+      </div>
+      <CodeBlock code={test.code} framework={test.framework} />
+      {showHistory &&
+        earlier.map((v) => (
+          <div key={v.id} className="border-t border-border">
+            <div className="px-4 py-1 text-xs text-text-muted bg-surface-alt">v{v.version}</div>
+            <CodeBlock code={v.code} framework={v.framework} />
+          </div>
+        ))}
+    </div>
+  );
+}
+
+function CodeBlock({ code, framework }: { code: string; framework: "jest" | "pytest" }) {
+  return (
+    <Highlight
+      theme={themes.vsDark}
+      code={code}
+      language={framework === "pytest" ? "python" : "javascript"}
+    >
+      {({ style, tokens, getLineProps, getTokenProps }) => (
+        <pre style={{ ...style, margin: 0, padding: "1rem" }} className="text-xs overflow-x-auto">
+          {tokens.map((line, i) => (
+            <div key={i} {...getLineProps({ line })}>
+              {line.map((token, key) => (
+                <span key={key} {...getTokenProps({ token })} />
+              ))}
+            </div>
+          ))}
+        </pre>
+      )}
+    </Highlight>
+  );
+}
@@ -20,7 +20,7 @@ export function useAlignmentReport(id: string | undefined) {
 export function useGenerateAlignmentReport(projectId: string) {
   const queryClient = useQueryClient();
   return useMutation({
-    mutationFn: () => api.generateAlignmentReport(projectId),
+    mutationFn: (tag?: string) => api.generateAlignmentReport(projectId, tag),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["reports", projectId] });
       queryClient.invalidateQueries({ queryKey: ["projects"] });
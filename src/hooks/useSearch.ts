@@ -0,0 +1,12 @@
+import { useQuery, keepPreviousData } from "@tanstack/react-query";
+import * as api from "../lib/api";
+
+export function useSearch(projectId: string | undefined, query: string) {
+  const trimmed = query.trim();
+  return useQuery({
+    queryKey: ["search", projectId, trimmed],
+    queryFn: () => api.search(projectId!, trimmed),
+    enabled: !!projectId && trimmed.length >= 2,
+    placeholderData: keepPreviousData,
+  });
+}
@@ -1,7 +1,31 @@
+import { useEffect } from "react";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
 import * as api from "../lib/api";
+import type {
+  CreateRequirementRequest,
+  RequirementStatus,
+  SpecChanged,
+  UpdateRequirementRequest,
+} from "../lib/types";
 
 export function useSpecs(projectId: string | undefined) {
+  const queryClient = useQueryClient();
+
+  // Specs imported from the codebase are re-parsed when their files change
+  useEffect(() => {
+    const unlisten = listen<SpecChanged>("spec-changed", (event) => {
+      if (event.payload.project_id !== projectId) return;
+      queryClient.invalidateQueries({ queryKey: ["specs", projectId] });
+      if (event.payload.spec_id) {
+        queryClient.invalidateQueries({ queryKey: ["spec", event.payload.spec_id] });
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [projectId, queryClient]);
+
   return useQuery({
     queryKey: ["specs", projectId],
     queryFn: () => api.listSpecs(projectId!),
@@ -17,6 +41,23 @@ export function useSpec(id: string | undefined) {
   });
 }
 
+export function useLintFindings(specId: string | undefined) {
+  return useQuery({
+    queryKey: ["spec", specId, "lint"],
+    queryFn: () => api.getLintFindings(specId!),
+    enabled: !!specId,
+  });
+}
+
+// Keyed under "specs" so uploads and deletes refresh it
+export function useRequirementOverlaps(projectId: string | undefined) {
+  return useQuery({
+    queryKey: ["specs", projectId, "overlaps"],
+    queryFn: () => api.findRequirementOverlaps(projectId!),
+    enabled: !!projectId,
+  });
+}
+
 export function useUploadSpec(projectId: string) {
   const queryClient = useQueryClient();
   return useMutation({
@@ -52,3 +93,45 @@ export function useReparseSpec() {
     },
   });
 }
+
+export function useSetRequirementStatus() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ id, status, reason }: { id: string; status: RequirementStatus; reason?: string }) =>
+      api.setRequirementStatus(id, status, reason),
+    onSuccess: (requirement) => {
+      queryClient.invalidateQueries({ queryKey: ["spec", requirement.spec_id] });
+    },
+  });
+}
+
+export function useCreateRequirement() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (req: CreateRequirementRequest) => api.createRequirement(req),
+    onSuccess: (requirement) => {
+      queryClient.invalidateQueries({ queryKey: ["spec", requirement.spec_id] });
+    },
+  });
+}
+
+export function useUpdateRequirement() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ id, request }: { id: string; request: UpdateRequirementRequest }) =>
+      api.updateRequirement(id, request),
+    onSuccess: (requirement) => {
+      queryClient.invalidateQueries({ queryKey: ["spec", requirement.spec_id] });
+    },
+  });
+}
+
+export function useDeleteRequirement(specId: string) {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (id: string) => api.deleteRequirement(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["spec", specId] });
+    },
+  });
+}
@@ -0,0 +1,31 @@
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import * as api from "../lib/api";
+
+export function useTags(projectId: string | undefined) {
+  return useQuery({
+    queryKey: ["tags", projectId],
+    queryFn: () => api.listTags(projectId!),
+    enabled: !!projectId,
+  });
+}
+
+export function useSaveTag(projectId: string) {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ name, paths }: { name: string; paths: string[] }) => api.saveTag(projectId, name, paths),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["tags", projectId] });
+    },
+  });
+}
+
+export function useDeleteTag(projectId: string) {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (id: string) => api.deleteTag(projectId, id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["tags", projectId] });
+      queryClient.invalidateQueries({ queryKey: ["spec"] });
+    },
+  });
+}
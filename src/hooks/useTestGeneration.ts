@@ -1,6 +1,8 @@
+import { useEffect, useRef, useState } from "react";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
 import * as api from "../lib/api";
-import type { GenerateTestsRequest, AppSettings } from "../lib/types";
+import type { GenerateTestsRequest, GenerationProgress, AppSettings } from "../lib/types";
 
 export function useGeneratedTests(requirementId: string | undefined) {
   return useQuery({
@@ -10,17 +12,53 @@ export function useGeneratedTests(requirementId: string | undefined) {
   });
 }
 
+export function useGeneratedTestVersions(testId: string | undefined) {
+  return useQuery({
+    queryKey: ["generated-test-versions", testId],
+    queryFn: () => api.getGeneratedTestVersions(testId!),
+    enabled: !!testId,
+  });
+}
+
 export function useGenerateTests() {
   const queryClient = useQueryClient();
   return useMutation({
     mutationFn: (req: GenerateTestsRequest) => api.generateTests(req),
-    onSuccess: () => {
+    // Tests are saved one by one, so a failed batch may still have added some
+    onSettled: () => {
       queryClient.invalidateQueries({ queryKey: ["generated-tests"] });
       queryClient.invalidateQueries({ queryKey: ["all-generated-tests"] });
+      // Self-heal runs the tests it generates
+      queryClient.invalidateQueries({ queryKey: ["test-results"] });
     },
   });
 }
 
+export function useCancelGeneration() {
+  return useMutation({
+    mutationFn: (projectId: string) => api.cancelGeneration(projectId),
+  });
+}
+
+/** Latest `generation-progress` event; `onProgress` sees every event, including ones React batches away. */
+export function useGenerationProgress(onProgress?: (progress: GenerationProgress) => void) {
+  const [progress, setProgress] = useState<GenerationProgress | null>(null);
+  const onProgressRef = useRef(onProgress);
+  onProgressRef.current = onProgress;
+
+  useEffect(() => {
+    const unlisten = listen<GenerationProgress>("generation-progress", (event) => {
+      setProgress(event.payload);
+      onProgressRef.current?.(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, []);
+
+  return progress;
+}
+
 export function useSaveTestToDisk() {
   return useMutation({
     mutationFn: ({ testId, path }: { testId: string; path: string }) =>
@@ -4,10 +4,26 @@ import type {
   CreateProjectRequest,
   ProjectWithStats,
   Spec,
+  SpecFilter,
+  SpecRevision,
+  SpecDiff,
+  LintFinding,
+  RequirementOverlaps,
+  SpecSource,
+  SpecImport,
   Requirement,
+  RequirementStatus,
+  RequirementStatusChange,
+  RequirementEdit,
+  Tag,
+  SearchResults,
+  CreateRequirementRequest,
+  UpdateRequirementRequest,
   ParsedSpec,
+  DetectionRules,
   GeneratedTest,
   GenerateTestsRequest,
+  GenerateTestsResult,
   TestResult,
   AlignmentReport,
   AlignmentReportWithMismatches,
@@ -37,8 +53,8 @@ export const uploadSpec = (projectId: string, filename: string, content: string)
 export const getSpec = (id: string) =>
   invoke<ParsedSpec>("get_spec", { id });
 
-export const listSpecs = (projectId: string) =>
-  invoke<Spec[]>("list_specs", { project_id: projectId });
+export const listSpecs = (projectId: string, filter?: SpecFilter) =>
+  invoke<Spec[]>("list_specs", { project_id: projectId, filter });
 
 export const deleteSpec = (id: string) =>
   invoke<void>("delete_spec", { id });
@@ -46,16 +62,95 @@ export const deleteSpec = (id: string) =>
 export const reparseSpec = (id: string) =>
   invoke<Requirement[]>("reparse_spec", { id });
 
+export const listSpecRevisions = (specId: string) =>
+  invoke<SpecRevision[]>("list_spec_revisions", { spec_id: specId });
+
+export const diffSpecRevisions = (specId: string, fromRevision: number, toRevision: number) =>
+  invoke<SpecDiff>("diff_spec_revisions", { spec_id: specId, from_revision: fromRevision, to_revision: toRevision });
+
+export const getLintFindings = (specId: string) =>
+  invoke<LintFinding[]>("get_lint_findings", { spec_id: specId });
+
+export const findRequirementOverlaps = (projectId: string) =>
+  invoke<RequirementOverlaps>("find_requirement_overlaps", { project_id: projectId });
+
 export const readFileContent = (path: string) =>
   invoke<string>("read_file_content", { path });
 
+// Spec source commands
+export const listSpecSources = (projectId: string) =>
+  invoke<SpecSource[]>("list_spec_sources", { project_id: projectId });
+
+export const addSpecSource = (projectId: string, pattern: string) =>
+  invoke<SpecSource>("add_spec_source", { project_id: projectId, pattern });
+
+export const removeSpecSource = (id: string) =>
+  invoke<void>("remove_spec_source", { id });
+
+export const importSpecSources = (projectId: string) =>
+  invoke<SpecImport>("import_spec_sources", { project_id: projectId });
+
+// Requirement commands
+export const setRequirementStatus = (requirementId: string, status: RequirementStatus, reason?: string) =>
+  invoke<Requirement>("set_requirement_status", { requirement_id: requirementId, status, reason });
+
+export const getRequirementStatusHistory = (requirementId: string) =>
+  invoke<RequirementStatusChange[]>("get_requirement_status_history", { requirement_id: requirementId });
+
+export const createRequirement = (req: CreateRequirementRequest) =>
+  invoke<Requirement>("create_requirement", { request: req });
+
+export const updateRequirement = (requirementId: string, req: UpdateRequirementRequest) =>
+  invoke<Requirement>("update_requirement", { requirement_id: requirementId, request: req });
+
+export const deleteRequirement = (requirementId: string) =>
+  invoke<void>("delete_requirement", { requirement_id: requirementId });
+
+export const getRequirementEditHistory = (requirementId: string) =>
+  invoke<RequirementEdit[]>("get_requirement_edit_history", { requirement_id: requirementId });
+
+// Tag commands
+export const listTags = (projectId: string) =>
+  invoke<Tag[]>("list_tags", { project_id: projectId });
+
+export const saveTag = (projectId: string, name: string, paths: string[]) =>
+  invoke<Tag>("save_tag", { project_id: projectId, name, paths });
+
+export const deleteTag = (projectId: string, id: string) =>
+  invoke<void>("delete_tag", { project_id: projectId, id });
+
+export const addRequirementTag = (requirementId: string, tag: string) =>
+  invoke<Requirement>("add_requirement_tag", { requirement_id: requirementId, tag });
+
+export const removeRequirementTag = (requirementId: string, tag: string) =>
+  invoke<Requirement>("remove_requirement_tag", { requirement_id: requirementId, tag });
+
+// Detection rule commands
+export const getDetectionRules = (projectId: string) =>
+  invoke<DetectionRules>("get_detection_rules", { project_id: projectId });
+
+export const saveDetectionRules = (projectId: string, rules: DetectionRules) =>
+  invoke<DetectionRules>("save_detection_rules", { project_id: projectId, rules });
+
+export const resetDetectionRules = (projectId: string) =>
+  invoke<DetectionRules>("reset_detection_rules", { project_id: projectId });
+
+export const previewSpecParse = (specId: string, rules: DetectionRules) =>
+  invoke<Requirement[]>("preview_spec_parse", { spec_id: specId, rules });
+
 // Test generation commands
 export const generateTests = (req: GenerateTestsRequest) =>
-  invoke<GeneratedTest[]>("generate_tests", { request: req });
+  invoke<GenerateTestsResult>("generate_tests", { request: req });
+
+export const cancelGeneration = (projectId: string) =>
+  invoke<boolean>("cancel_generation", { project_id: projectId });
 
 export const getGeneratedTests = (requirementId: string) =>
   invoke<GeneratedTest[]>("get_generated_tests", { requirement_id: requirementId });
 
+export const getGeneratedTestVersions = (testId: string) =>
+  invoke<GeneratedTest[]>("get_generated_test_versions", { test_id: testId });
+
 export const getAllGeneratedTests = (projectId: string) =>
   invoke<GeneratedTest[]>("get_all_generated_tests", { project_id: projectId });
 
@@ -80,8 +175,8 @@ export const getTestResult = (id: string) =>
   invoke<TestResult>("get_test_result", { id });
 
 // Report commands
-export const generateAlignmentReport = (projectId: string) =>
-  invoke<AlignmentReportWithMismatches>("generate_alignment_report", { project_id: projectId });
+export const generateAlignmentReport = (projectId: string, tag?: string) =>
+  invoke<AlignmentReportWithMismatches>("generate_alignment_report", { project_id: projectId, tag });
 
 export const getAlignmentReport = (id: string) =>
   invoke<AlignmentReportWithMismatches>("get_alignment_report", { id });
@@ -91,3 +186,7 @@ export const listReports = (projectId: string) =>
 
 export const exportReport = (reportId: string, format: "json" | "html" | "csv") =>
   invoke<string>("export_report", { report_id: reportId, format });
+
+// Search commands
+export const search = (projectId: string, query: string, limit?: number) =>
+  invoke<SearchResults>("search", { project_id: projectId, query, limit });
@@ -26,15 +26,248 @@ export interface Spec {
   content: string;
   parsed_at: string | null;
   created_at: string;
+  metadata: SpecMetadata;
+  revision: number;
+}
+
+export interface SpecRevision {
+  id: string;
+  spec_id: string;
+  revision: number;
+  content: string;
+  created_at: string;
+}
+
+export interface RequirementChange {
+  before: Requirement;
+  after: Requirement;
+}
+
+export interface SpecDiff {
+  spec_id: string;
+  from_revision: number;
+  to_revision: number;
+  added: Requirement[];
+  removed: Requirement[];
+  modified: RequirementChange[];
+  unchanged: number;
+}
+
+// Wording problem in a requirement, found when its spec is parsed
+export interface LintFinding {
+  id: string;
+  spec_id: string;
+  requirement_id: string;
+  rule: "ambiguous_term" | "unmeasurable" | "compound" | "passive_voice" | "duplicate";
+  severity: "warning" | "info";
+  message: string;
+  excerpt: string | null;
+  created_at: string;
+}
+
+export interface DuplicateCluster {
+  requirements: Requirement[];
+  similarity: number;
+}
+
+export interface RequirementConflict {
+  kind: "modal" | "numeric";
+  message: string;
+  first: Requirement;
+  second: Requirement;
+}
+
+// Near-duplicate and contradicting requirements across a project's specs
+export interface RequirementOverlaps {
+  duplicates: DuplicateCluster[];
+  conflicts: RequirementConflict[];
+}
+
+// Glob relative to the project's codebase, e.g. docs/specs/**/*.md
+export interface SpecSource {
+  id: string;
+  project_id: string;
+  pattern: string;
+  created_at: string;
+}
+
+export interface SpecImportError {
+  filename: string;
+  error: string;
+}
+
+export interface SpecImport {
+  specs: Spec[];
+  unchanged: number;
+  errors: SpecImportError[];
+}
+
+// Payload of the "spec-changed" event sent when a watched spec file is edited
+export interface SpecChanged {
+  project_id: string;
+  filename: string;
+  spec_id: string | null;
+  revision: number | null;
+  error: string | null;
+}
+
+export interface SpecMetadata {
+  owner: string | null;
+  version: string | null;
+  status: string | null;
+  component: string | null;
+  default_priority: "high" | "medium" | "low" | null;
+  default_req_type: "functional" | "non_functional" | "constraint" | null;
+  tags: string[];
+}
+
+export interface SpecFilter {
+  owner?: string;
+  version?: string;
+  status?: string;
+  component?: string;
+  default_priority?: SpecMetadata["default_priority"];
+  default_req_type?: SpecMetadata["default_req_type"];
+  tag?: string;
 }
 
 export interface Requirement {
   id: string;
   spec_id: string;
+  parent_id: string | null;
+  external_id: string | null;
   section: string;
   description: string;
-  req_type: "functional" | "non_functional" | "constraint";
+  // Built-in types, or a custom type from the project's detection rules
+  req_type: string;
   priority: "high" | "medium" | "low";
+  priority_keyword: string | null;
+  change_status: "new" | "unchanged" | "modified";
+  status: RequirementStatus;
+  retired_at: string | null;
+  scenario: GherkinScenario | null;
+  span: SourceSpan | null;
+  threshold: Threshold | null;
+  // Created, edited or deleted by hand; re-parsing leaves it alone
+  manually_edited: boolean;
+  tags: string[];
+}
+
+// Project-wide requirement label, mapped to codebase paths (globs)
+export interface Tag {
+  id: string;
+  project_id: string;
+  name: string;
+  paths: string[];
+  requirement_count: number;
+  created_at: string;
+}
+
+export interface CreateRequirementRequest {
+  spec_id: string;
+  section: string;
+  description: string;
+  req_type?: string;
+  priority?: string;
+  external_id?: string;
+}
+
+export interface UpdateRequirementRequest {
+  description?: string;
+  section?: string;
+  req_type?: string;
+  priority?: string;
+}
+
+export interface RequirementFields {
+  section: string;
+  description: string;
+  req_type: string;
+  priority: string;
+}
+
+export interface RequirementEdit {
+  id: string;
+  requirement_id: string;
+  action: "create" | "edit" | "delete";
+  before: RequirementFields | null;
+  after: RequirementFields | null;
+  edited_by: string;
+  edited_at: string;
+}
+
+// Review status; waived and deprecated requirements don't count toward coverage
+export type RequirementStatus = "draft" | "approved" | "deprecated" | "waived";
+
+export interface RequirementStatusChange {
+  id: string;
+  requirement_id: string;
+  from_status: RequirementStatus;
+  to_status: RequirementStatus;
+  changed_by: string;
+  reason: string | null;
+  changed_at: string;
+}
+
+// Numeric limit of a non-functional requirement, value in the unit shown
+export interface Threshold {
+  metric: "duration" | "throughput" | "size" | "percentage" | "count";
+  operator: "<" | "<=" | ">" | ">=" | "=";
+  value: number;
+  unit: string;
+  percentile: number | null;
+  text: string;
+}
+
+// Byte offsets (end exclusive) and 1-based lines (inclusive) in the spec file
+export interface SourceSpan {
+  start_offset: number;
+  end_offset: number;
+  start_line: number;
+  end_line: number;
+}
+
+export interface GherkinStep {
+  keyword: "Given" | "When" | "Then" | "And" | "But" | "*";
+  text: string;
+  doc_string: string | null;
+  table: string[][];
+}
+
+export interface GherkinExamples {
+  name: string;
+  header: string[];
+  rows: string[][];
+}
+
+export interface GherkinScenario {
+  keyword: "Scenario" | "Scenario Outline";
+  tags: string[];
+  steps: GherkinStep[];
+  examples: GherkinExamples[];
+}
+
+// Detection rules
+export interface PriorityRule {
+  keyword: string;
+  priority: "high" | "medium" | "low";
+}
+
+export interface TypeRule {
+  req_type: string;
+  section_keywords: string[];
+  text_keywords: string[];
+  patterns: string[];
+}
+
+export interface DetectionRules {
+  section_keywords: string[];
+  section_patterns: string[];
+  requirement_prefixes: string[];
+  requirement_patterns: string[];
+  normative_keywords: string[];
+  priority_keywords: PriorityRule[];
+  type_rules: TypeRule[];
 }
 
 export interface ParsedSpec {
@@ -51,6 +284,9 @@ export interface GeneratedTest {
   generation_mode: "template" | "llm";
   file_path: string | null;
   created_at: string;
+  version: number;
+  previous_version_id: string | null;
+  repair_outcome: "passed" | "failed" | "broken" | null;
 }
 
 export interface GenerateTestsRequest {
@@ -58,6 +294,30 @@ export interface GenerateTestsRequest {
   framework: "jest" | "pytest";
   mode: "template" | "llm";
   project_id: string;
+  tag?: string;
+  self_heal?: boolean;
+}
+
+export interface GenerationProgress {
+  total: number;
+  completed: number;
+  current_requirement: string;
+  status: "generating" | "running" | "repairing" | "generated" | "failed" | "completed" | "cancelled";
+  partial_code: string;
+  test: GeneratedTest | null;
+  error: string | null;
+}
+
+export interface GenerationError {
+  requirement_id: string;
+  error: string;
+}
+
+export interface GenerateTestsResult {
+  tests: GeneratedTest[];
+  errors: GenerationError[];
+  repair_errors: GenerationError[];
+  cancelled: boolean;
 }
 
 // Test execution types
@@ -85,6 +345,8 @@ export interface AlignmentReport {
   coverage_percent: number;
   total_requirements: number;
   covered_requirements: number;
+  excluded_requirements: number;
+  tag: string | null;
   generated_at: string;
 }
 
@@ -92,14 +354,29 @@ export interface Mismatch {
   id: string;
   report_id: string;
   requirement_id: string;
+  external_id: string | null;
+  spec_filename: string | null;
+  span: SourceSpan | null;
   spec_section: string;
   code_element: string | null;
   mismatch_type: "not_implemented" | "test_failing" | "no_test_generated" | "partial_coverage";
   details: string;
 }
 
+// Waived or deprecated requirement left out of a report's coverage
+export interface ExcludedRequirement {
+  requirement_id: string;
+  external_id: string | null;
+  spec_filename: string | null;
+  spec_section: string;
+  description: string;
+  status: RequirementStatus;
+  reason: string | null;
+}
+
 export interface AlignmentReportWithMismatches extends AlignmentReport {
   mismatches: Mismatch[];
+  excluded: ExcludedRequirement[];
 }
 
 // Settings
@@ -108,4 +385,37 @@ export interface AppSettings {
   default_framework: "jest" | "pytest";
   default_mode: "template" | "llm";
   scan_exclusions: string[];
+  llm: LlmSettings;
+}
+
+// Empty base_url and model use the provider's defaults
+export interface LlmSettings {
+  provider: "anthropic" | "openai" | "mock";
+  base_url: string;
+  model: string;
+  max_tokens: number;
+  temperature: number | null;
+  timeout_secs: number;
+  concurrency: number;
+  max_retries: number;
+  context_tokens: number;
+  repair_attempts: number;
+}
+
+// Search types
+export interface SearchHit {
+  entity_type: "spec" | "requirement" | "test";
+  entity_id: string;
+  spec_id: string;
+  spec_filename: string;
+  // Escaped HTML with matched terms in <mark>
+  title: string;
+  snippet: string;
+  score: number;
+}
+
+export interface SearchResults {
+  specs: SearchHit[];
+  requirements: SearchHit[];
+  tests: SearchHit[];
 }
@@ -1,14 +1,19 @@
 import { useState } from "react";
 import { useParams, Link, useNavigate } from "react-router-dom";
 import { useProject, useDeleteProject } from "../hooks/useProjects";
-import { useSpecs, useDeleteSpec } from "../hooks/useSpecs";
+import { useSpecs, useDeleteSpec, useRequirementOverlaps } from "../hooks/useSpecs";
+import { useTags, useSaveTag } from "../hooks/useTags";
 import { SpecUploader } from "../components/spec/SpecUploader";
+import { ProjectSearch } from "../components/project/ProjectSearch";
 
 export function ProjectView() {
   const { projectId } = useParams<{ projectId: string }>();
   const navigate = useNavigate();
   const { data: project, isLoading } = useProject(projectId);
   const { data: specs, isError: specsError } = useSpecs(projectId);
+  const { data: overlaps } = useRequirementOverlaps(projectId);
+  const { data: tags } = useTags(projectId);
+  const saveTag = useSaveTag(projectId ?? "");
   const deleteProject = useDeleteProject();
   const deleteSpec = useDeleteSpec(projectId ?? "");
   const [confirmDelete, setConfirmDelete] = useState(false);
@@ -103,6 +108,8 @@ export function ProjectView() {
         </div>
       </div>
 
+      <ProjectSearch projectId={projectId!} />
+
       {/* Specs */}
       {(specsError || deleteSpec.isError) && (
         <div className="rounded-lg border border-danger/30 bg-danger/5 p-4 text-sm text-danger mb-4">
@@ -165,6 +172,67 @@ export function ProjectView() {
           <p className="text-text-muted">No specs uploaded yet.</p>
         </div>
       )}
+
+      {/* Tags and the code they map to */}
+      {tags && tags.length > 0 && (
+        <div className="mt-8">
+          <h3 className="text-lg font-semibold mb-4">Tags</h3>
+          {saveTag.isError && <p className="text-sm text-danger mb-2">{String(saveTag.error)}</p>}
+          <div className="space-y-1">
+            {tags.map((tag) => (
+              <div key={tag.id} className="flex items-center gap-3 p-3 rounded-lg border border-border bg-surface text-sm">
+                <span className="text-primary-light">#{tag.name}</span>
+                <span className="text-text-muted">{tag.requirement_count} requirements</span>
+                <span className="flex-1 text-text-muted truncate">
+                  {tag.paths.length > 0 ? tag.paths.join(", ") : "No code paths mapped"}
+                </span>
+                <button
+                  onClick={() => {
+                    const paths = window.prompt("Codebase paths for this tag, comma-separated", tag.paths.join(", "));
+                    if (paths === null) return;
+                    saveTag.mutate({ name: tag.name, paths: paths.split(",").map((p) => p.trim()).filter(Boolean) });
+                  }}
+                  className="text-text-muted hover:text-text"
+                >
+                  Map paths
+                </button>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
+      {/* Overlapping requirements */}
+      {overlaps && (overlaps.conflicts.length > 0 || overlaps.duplicates.length > 0) && (
+        <div className="mt-8">
+          <h3 className="text-lg font-semibold mb-4">Overlapping Requirements</h3>
+          <div className="space-y-2">
+            {overlaps.conflicts.map((conflict) => (
+              <div
+                key={`${conflict.first.id}-${conflict.second.id}`}
+                className="p-4 rounded-lg border border-danger/30 bg-danger/5 text-sm"
+              >
+                <p className="text-danger font-medium mb-1">{conflict.message}</p>
+                <p className="text-text">{conflict.first.description}</p>
+                <p className="text-text">{conflict.second.description}</p>
+              </div>
+            ))}
+            {overlaps.duplicates.map((cluster) => (
+              <div
+                key={cluster.requirements[0].id}
+                className="p-4 rounded-lg border border-warning/30 bg-warning/5 text-sm"
+              >
+                <p className="text-warning font-medium mb-1">
+                  {cluster.requirements.length} likely duplicates
+                </p>
+                {cluster.requirements.map((req) => (
+                  <p key={req.id} className="text-text">{req.description}</p>
+                ))}
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
     </div>
   );
 }
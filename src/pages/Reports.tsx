@@ -7,6 +7,7 @@ import {
   useGenerateAlignmentReport,
   useExportReport,
 } from "../hooks/useReports";
+import { useTags } from "../hooks/useTags";
 import { CoverageGauge } from "../components/report/CoverageGauge";
 import { AlignmentChart } from "../components/report/AlignmentChart";
 import { MismatchTable } from "../components/report/MismatchTable";
@@ -17,6 +18,8 @@ export function Reports() {
   const { data: reports, isError: reportsError } = useReports(projectId);
   const generateReport = useGenerateAlignmentReport(projectId ?? "");
   const exportReport = useExportReport();
+  const { data: tags } = useTags(projectId);
+  const [tag, setTag] = useState("");
   const [selectedReportId, setSelectedReportId] = useState<string | undefined>();
 
   const { data: report } = useAlignmentReport(selectedReportId);
@@ -63,8 +66,20 @@ export function Reports() {
       <div className="flex items-center justify-between mb-6">
         <h2 className="text-2xl font-bold">Alignment Reports</h2>
         <div className="flex gap-2">
+          {tags && tags.length > 0 && (
+            <select
+              value={tag}
+              onChange={(e) => setTag(e.target.value)}
+              className="bg-surface border border-border rounded-lg px-3 py-1.5 text-sm text-text"
+            >
+              <option value="">All requirements</option>
+              {tags.map((t) => (
+                <option key={t.id} value={t.name}>#{t.name}</option>
+              ))}
+            </select>
+          )}
           <button
-            onClick={() => generateReport.mutate(undefined, {
+            onClick={() => generateReport.mutate(tag || undefined, {
               onSuccess: (data) => setSelectedReportId(data.id),
             })}
             disabled={generateReport.isPending}
@@ -93,6 +108,7 @@ export function Reports() {
             {reports.map((r) => (
               <option key={r.id} value={r.id}>
                 {new Date(r.generated_at).toLocaleString()} — {r.coverage_percent.toFixed(0)}%
+                {r.tag && ` (#${r.tag})`}
               </option>
             ))}
           </select>
@@ -134,6 +150,27 @@ export function Reports() {
             <h3 className="text-lg font-semibold mb-3">Mismatches ({report.mismatches.length})</h3>
             <MismatchTable mismatches={report.mismatches} />
           </div>
+
+          {/* Waived and deprecated requirements */}
+          {report.excluded.length > 0 && (
+            <div>
+              <h3 className="text-lg font-semibold mb-3">Not Counted ({report.excluded.length})</h3>
+              <div className="space-y-1">
+                {report.excluded.map((e) => (
+                  <div key={e.requirement_id} className="p-3 rounded-lg border border-border bg-surface text-sm">
+                    <p className="text-text">
+                      {e.external_id && <span className="text-text-muted mr-2">{e.external_id}</span>}
+                      {e.description}
+                    </p>
+                    <p className="text-xs text-text-muted mt-1">
+                      {e.status}
+                      {e.reason && ` — ${e.reason}`}
+                    </p>
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
         </div>
       ) : reports && reports.length === 0 ? (
         <div className="rounded-xl border border-border bg-surface-alt p-8 text-center">
@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef } from "react";
 import { useSettings, useSaveSettings } from "../hooks/useTestGeneration";
-import type { AppSettings } from "../lib/types";
+import type { AppSettings, LlmSettings } from "../lib/types";
 
 export function Settings() {
   const { data: settings, isLoading } = useSettings();
@@ -10,6 +10,18 @@ export function Settings() {
     default_framework: "jest",
     default_mode: "template",
     scan_exclusions: [],
+    llm: {
+      provider: "anthropic",
+      base_url: "",
+      model: "",
+      max_tokens: 2048,
+      temperature: null,
+      timeout_secs: 60,
+      concurrency: 4,
+      max_retries: 3,
+      context_tokens: 6000,
+      repair_attempts: 2,
+    },
   });
   const [exclusionInput, setExclusionInput] = useState("");
   const [showSaved, setShowSaved] = useState(false);
@@ -49,9 +61,130 @@ export function Settings() {
     <div>
       <h2 className="text-2xl font-bold mb-6">Settings</h2>
       <div className="max-w-lg space-y-6">
+        {/* LLM Provider */}
+        <div>
+          <label className="block text-sm text-text-muted mb-1">LLM Provider</label>
+          <select
+            value={form.llm.provider}
+            onChange={(e) => setForm({ ...form, llm: { ...form.llm, provider: e.target.value as LlmSettings["provider"] } })}
+            className="bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text"
+          >
+            <option value="anthropic">Anthropic</option>
+            <option value="openai">OpenAI-compatible (OpenAI, Ollama, llama.cpp, vLLM)</option>
+            <option value="mock">Mock (offline, deterministic)</option>
+          </select>
+        </div>
+
+        {form.llm.provider !== "mock" && (
+          <div className="grid grid-cols-2 gap-4">
+            <div className="col-span-2">
+              <label className="block text-sm text-text-muted mb-1">Base URL</label>
+              <input
+                type="text"
+                value={form.llm.base_url}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, base_url: e.target.value } })}
+                placeholder={form.llm.provider === "openai" ? "http://localhost:11434/v1" : "https://api.anthropic.com/v1"}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div className="col-span-2">
+              <label className="block text-sm text-text-muted mb-1">Model</label>
+              <input
+                type="text"
+                value={form.llm.model}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, model: e.target.value } })}
+                placeholder={form.llm.provider === "openai" ? "llama3.1" : "claude-sonnet-4-20250514"}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Max Tokens</label>
+              <input
+                type="number"
+                min={1}
+                value={form.llm.max_tokens}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, max_tokens: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Temperature</label>
+              <input
+                type="number"
+                min={0}
+                max={2}
+                step={0.1}
+                value={form.llm.temperature ?? ""}
+                onChange={(e) =>
+                  setForm({
+                    ...form,
+                    llm: { ...form.llm, temperature: e.target.value === "" ? null : Number(e.target.value) },
+                  })
+                }
+                placeholder="Default"
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Idle Timeout (seconds)</label>
+              <input
+                type="number"
+                min={1}
+                value={form.llm.timeout_secs}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, timeout_secs: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Parallel Requests</label>
+              <input
+                type="number"
+                min={1}
+                max={16}
+                value={form.llm.concurrency}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, concurrency: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Max Retries</label>
+              <input
+                type="number"
+                min={0}
+                max={10}
+                value={form.llm.max_retries}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, max_retries: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Context Tokens</label>
+              <input
+                type="number"
+                min={1}
+                step={500}
+                value={form.llm.context_tokens}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, context_tokens: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-text-muted mb-1">Self-heal Repair Attempts</label>
+              <input
+                type="number"
+                min={0}
+                max={5}
+                value={form.llm.repair_attempts}
+                onChange={(e) => setForm({ ...form, llm: { ...form.llm, repair_attempts: Number(e.target.value) } })}
+                className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
+              />
+            </div>
+          </div>
+        )}
+
         {/* API Key */}
         <div>
-          <label className="block text-sm text-text-muted mb-1">Claude API Key</label>
+          <label className="block text-sm text-text-muted mb-1">API Key</label>
           <input
             type="password"
             value={form.api_key}
@@ -59,7 +192,9 @@ export function Settings() {
             placeholder="sk-ant-..."
             className="w-full bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text focus:outline-none focus:border-primary"
           />
-          <p className="text-xs text-text-muted mt-1">Required for LLM-powered test generation.</p>
+          <p className="text-xs text-text-muted mt-1">
+            Required for Anthropic and OpenAI; local OpenAI-compatible servers usually don't need one.
+          </p>
         </div>
 
         {/* Default Framework */}
@@ -84,7 +219,7 @@ export function Settings() {
             className="bg-surface border border-border rounded-lg px-3 py-2 text-sm text-text"
           >
             <option value="template">Template (offline)</option>
-            <option value="llm">LLM (Claude API)</option>
+            <option value="llm">LLM</option>
           </select>
         </div>
 
@@ -113,7 +248,7 @@ export function Settings() {
           <p className="text-sm text-success">Settings saved.</p>
         )}
         {saveSettings.isError && (
-          <p className="text-sm text-danger">Failed to save settings.</p>
+          <p className="text-sm text-danger">Failed to save settings: {String(saveSettings.error)}</p>
         )}
       </div>
     </div>
@@ -1,6 +1,14 @@
 import { useState } from "react";
 import { useParams, Link } from "react-router-dom";
-import { useSpec, useReparseSpec } from "../hooks/useSpecs";
+import {
+  useSpec,
+  useReparseSpec,
+  useLintFindings,
+  useSetRequirementStatus,
+  useCreateRequirement,
+  useUpdateRequirement,
+  useDeleteRequirement,
+} from "../hooks/useSpecs";
 import { useProject } from "../hooks/useProjects";
 import { SpecViewer } from "../components/spec/SpecViewer";
 import { RequirementsList } from "../components/spec/RequirementsList";
@@ -9,13 +17,28 @@ export function SpecView() {
   const { projectId, specId } = useParams<{ projectId: string; specId: string }>();
   const { data: project } = useProject(projectId);
   const { data: parsed, isLoading, isError } = useSpec(specId);
+  const { data: findings } = useLintFindings(specId);
   const reparse = useReparseSpec();
+  const setStatus = useSetRequirementStatus();
+  const createRequirement = useCreateRequirement();
+  const updateRequirement = useUpdateRequirement();
+  const deleteRequirement = useDeleteRequirement(specId ?? "");
   const [tab, setTab] = useState<"requirements" | "source">("requirements");
 
   if (isLoading) return <p className="text-text-muted">Loading...</p>;
   if (isError) return <p className="text-danger">Failed to load spec. It may have been deleted.</p>;
   if (!parsed) return <p className="text-text-muted">Spec not found.</p>;
 
+  const editError = setStatus.error ?? createRequirement.error ?? updateRequirement.error ?? deleteRequirement.error;
+
+  const addRequirement = () => {
+    const description = window.prompt("Requirement text");
+    if (!description?.trim()) return;
+    const section = window.prompt("Section", parsed.requirements[0]?.section ?? "Requirements");
+    if (!section?.trim()) return;
+    createRequirement.mutate({ spec_id: parsed.spec.id, section, description });
+  };
+
   return (
     <div>
       {/* Breadcrumb */}
@@ -43,6 +66,9 @@ export function SpecView() {
       {/* Stats */}
       <div className="flex gap-4 mb-4 text-sm text-text-muted">
         <span>{parsed.requirements.length} requirements extracted</span>
+        {findings && findings.length > 0 && (
+          <span className="text-warning">{findings.length} lint findings</span>
+        )}
         {parsed.spec.parsed_at && (
           <span>Parsed: {new Date(parsed.spec.parsed_at).toLocaleString()}</span>
         )}
@@ -73,7 +99,36 @@ export function SpecView() {
       </div>
 
       {tab === "requirements" ? (
-        <RequirementsList requirements={parsed.requirements} />
+        <>
+          <div className="flex items-center justify-between mb-2">
+            {editError ? <p className="text-sm text-danger">{String(editError)}</p> : <span />}
+            <button
+              onClick={addRequirement}
+              disabled={createRequirement.isPending}
+              className="text-sm text-primary-light hover:text-primary disabled:opacity-50"
+            >
+              + Add requirement
+            </button>
+          </div>
+          <RequirementsList
+            requirements={parsed.requirements}
+            findings={findings}
+            onStatusChange={(req, status) => {
+              const reason = status === "waived" ? window.prompt("Reason for waiving this requirement") : undefined;
+              if (reason === null) return;
+              setStatus.mutate({ id: req.id, status, reason });
+            }}
+            onEdit={(req) => {
+              const description = window.prompt("Requirement text", req.description);
+              if (description === null || description.trim() === req.description) return;
+              updateRequirement.mutate({ id: req.id, request: { description } });
+            }}
+            onDelete={(req) => {
+              if (!window.confirm("Delete this requirement and its acceptance criteria?")) return;
+              deleteRequirement.mutate(req.id);
+            }}
+          />
+        </>
       ) : (
         <div className="rounded-xl border border-border bg-surface-alt p-6">
           <SpecViewer content={parsed.spec.content} />